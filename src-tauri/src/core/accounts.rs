@@ -0,0 +1,591 @@
+use std::fs;
+use std::path::Path;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::core::auth::yggdrasil::YGGDRASIL_USER_TYPE;
+use crate::core::auth::LaunchAuth;
+
+pub(crate) const ACCOUNT_KIND_MSA: &str = "msa";
+pub(crate) const ACCOUNT_KIND_OFFLINE: &str = "offline";
+pub(crate) const ACCOUNT_KIND_YGGDRASIL: &str = "yggdrasil";
+pub(crate) const ACCOUNT_REFRESH_MARGIN_SECS: u64 = 5 * 60;
+
+const VAULT_KEY_LEN: usize = 32;
+
+pub(crate) const ACCOUNTS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS accounts (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    username TEXT NOT NULL,
+    uuid TEXT NOT NULL,
+    access_token BLOB,
+    refresh_token BLOB,
+    expires_at INTEGER,
+    is_default INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER,
+    last_used_at INTEGER,
+    auth_server TEXT
+);";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountSummary {
+    pub(crate) id: String,
+    pub(crate) kind: String,
+    pub(crate) username: String,
+    pub(crate) uuid: String,
+    pub(crate) expires_at: Option<u64>,
+    pub(crate) expired: bool,
+    pub(crate) has_refresh_token: bool,
+    pub(crate) is_default: bool,
+    pub(crate) created_at: u64,
+    pub(crate) last_used_at: Option<u64>,
+    pub(crate) auth_server: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StoredAccount {
+    pub(crate) id: String,
+    pub(crate) kind: String,
+    pub(crate) username: String,
+    pub(crate) uuid: String,
+    pub(crate) access_token: Option<String>,
+    pub(crate) refresh_token: Option<String>,
+    pub(crate) expires_at: Option<u64>,
+    pub(crate) is_default: bool,
+    pub(crate) created_at: u64,
+    pub(crate) last_used_at: Option<u64>,
+    /// URL del servidor Yggdrasil (sólo cuentas `yggdrasil`); el refresh token guarda el clientToken.
+    pub(crate) auth_server: Option<String>,
+}
+
+impl StoredAccount {
+    pub(crate) fn needs_refresh(&self, now_secs: u64) -> bool {
+        if self.kind == ACCOUNT_KIND_OFFLINE {
+            return false;
+        }
+        match self.expires_at {
+            Some(expires_at) => now_secs.saturating_add(ACCOUNT_REFRESH_MARGIN_SECS) >= expires_at,
+            None => self.access_token.is_none(),
+        }
+    }
+
+    pub(crate) fn summary(&self, now_secs: u64) -> AccountSummary {
+        AccountSummary {
+            id: self.id.clone(),
+            kind: self.kind.clone(),
+            username: self.username.clone(),
+            uuid: self.uuid.clone(),
+            expires_at: self.expires_at,
+            expired: self
+                .expires_at
+                .is_some_and(|expires_at| now_secs >= expires_at),
+            has_refresh_token: self.refresh_token.is_some(),
+            is_default: self.is_default,
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+            auth_server: self.auth_server.clone(),
+        }
+    }
+
+    pub(crate) fn launch_auth(&self) -> LaunchAuth {
+        LaunchAuth {
+            username: self.username.clone(),
+            uuid: self.uuid.clone(),
+            access_token: self
+                .access_token
+                .clone()
+                .filter(|token| !token.trim().is_empty())
+                .unwrap_or_else(|| "0".to_string()),
+            user_type: match self.kind.as_str() {
+                ACCOUNT_KIND_OFFLINE => "offline".to_string(),
+                ACCOUNT_KIND_YGGDRASIL => YGGDRASIL_USER_TYPE.to_string(),
+                kind => kind.to_string(),
+            },
+        }
+    }
+}
+
+pub(crate) struct AccountVault {
+    key: LessSafeKey,
+}
+
+impl AccountVault {
+    pub(crate) fn from_key_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let unbound = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| "Clave del almacén de cuentas inválida".to_string())?;
+        Ok(Self {
+            key: LessSafeKey::new(unbound),
+        })
+    }
+
+    /// Sólo genera una clave nueva si el archivo no existe: reemplazar una clave ilegible
+    /// dejaría todos los tokens guardados sin forma de descifrarse.
+    pub(crate) fn open_or_create_key(key_path: &Path) -> Result<Self, String> {
+        match fs::read(key_path) {
+            Ok(existing) if existing.len() == VAULT_KEY_LEN => {
+                return Self::from_key_bytes(&existing)
+            }
+            Ok(existing) => {
+                return Err(format!(
+                    "La clave de cuentas {} tiene {} bytes en vez de {VAULT_KEY_LEN}",
+                    key_path.display(),
+                    existing.len()
+                ))
+            }
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!(
+                    "No se pudo leer la clave de cuentas {}: {error}",
+                    key_path.display()
+                ))
+            }
+            Err(_) => {}
+        }
+
+        let mut bytes = [0_u8; VAULT_KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| "No se pudo generar la clave del almacén de cuentas".to_string())?;
+        if let Some(parent) = key_path.parent() {
+            fs::create_dir_all(parent).map_err(|error| {
+                format!("No se pudo crear carpeta para la clave de cuentas: {error}")
+            })?;
+        }
+        fs::write(key_path, bytes)
+            .map_err(|error| format!("No se pudo guardar la clave de cuentas: {error}"))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(key_path, fs::Permissions::from_mode(0o600));
+        }
+        Self::from_key_bytes(&bytes)
+    }
+
+    fn seal(&self, account_id: &str, plain: &str) -> Result<Vec<u8>, String> {
+        let mut nonce_bytes = [0_u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce_bytes)
+            .map_err(|_| "No se pudo generar nonce para cifrar token".to_string())?;
+        let mut in_out = plain.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(account_id.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| "No se pudo cifrar token de cuenta".to_string())?;
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(sealed)
+    }
+
+    fn open(&self, account_id: &str, sealed: &[u8]) -> Result<String, String> {
+        if sealed.len() <= NONCE_LEN {
+            return Err("Token cifrado truncado en el almacén de cuentas".to_string());
+        }
+        let (nonce_bytes, cipher) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+            .map_err(|_| "Nonce inválido en el almacén de cuentas".to_string())?;
+        let mut in_out = cipher.to_vec();
+        let plain = self
+            .key
+            .open_in_place(nonce, Aad::from(account_id.as_bytes()), &mut in_out)
+            .map_err(|_| {
+                format!("No se pudo descifrar token de la cuenta {account_id}; vuelve a iniciar sesión.")
+            })?;
+        String::from_utf8(plain.to_vec())
+            .map_err(|error| format!("Token descifrado inválido: {error}"))
+    }
+
+    /// Cifra un secreto que no es de una cuenta (p. ej. la contraseña del proxy) para
+    /// guardarlo como texto en base64; `label` hace de AAD.
+    pub(crate) fn seal_secret(&self, label: &str, plain: &str) -> Result<String, String> {
+        use base64::Engine;
+        let sealed = self.seal(label, plain)?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+    }
+
+    pub(crate) fn open_secret(&self, label: &str, sealed: &str) -> Result<String, String> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(sealed.trim())
+            .map_err(|error| format!("Secreto cifrado inválido ({label}): {error}"))?;
+        self.open(label, &bytes)
+    }
+
+    fn seal_optional(
+        &self,
+        account_id: &str,
+        value: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, String> {
+        value
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| self.seal(account_id, token))
+            .transpose()
+    }
+
+    pub(crate) fn upsert(&self, conn: &Connection, account: &StoredAccount) -> Result<(), String> {
+        let access_token = self.seal_optional(&account.id, account.access_token.as_deref())?;
+        let refresh_token = self.seal_optional(&account.id, account.refresh_token.as_deref())?;
+        let has_default = conn
+            .query_row(
+                "SELECT COUNT(*) FROM accounts WHERE is_default = 1 AND id != ?1",
+                params![account.id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|error| format!("No se pudo consultar cuenta por defecto: {error}"))?
+            > 0;
+        let is_default = account.is_default || !has_default;
+        if is_default {
+            conn.execute(
+                "UPDATE accounts SET is_default = 0 WHERE id != ?1",
+                params![account.id],
+            )
+            .map_err(|error| format!("No se pudo actualizar cuenta por defecto: {error}"))?;
+        }
+        conn.execute(
+            "INSERT INTO accounts (id, kind, username, uuid, access_token, refresh_token, expires_at, is_default, created_at, last_used_at, auth_server)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET
+                kind = excluded.kind,
+                username = excluded.username,
+                uuid = excluded.uuid,
+                access_token = excluded.access_token,
+                refresh_token = excluded.refresh_token,
+                expires_at = excluded.expires_at,
+                is_default = excluded.is_default,
+                last_used_at = excluded.last_used_at,
+                auth_server = excluded.auth_server",
+            params![
+                account.id,
+                account.kind,
+                account.username,
+                account.uuid,
+                access_token,
+                refresh_token,
+                account.expires_at.map(|value| value as i64),
+                is_default,
+                account.created_at as i64,
+                account.last_used_at.map(|value| value as i64),
+                account.auth_server,
+            ],
+        )
+        .map_err(|error| format!("No se pudo guardar la cuenta: {error}"))?;
+        Ok(())
+    }
+
+    pub(crate) fn get(
+        &self,
+        conn: &Connection,
+        account_id: &str,
+    ) -> Result<Option<StoredAccount>, String> {
+        let row = conn
+            .query_row(
+                "SELECT id, kind, username, uuid, access_token, refresh_token, expires_at, is_default, created_at, last_used_at, auth_server
+                 FROM accounts WHERE id = ?1",
+                params![account_id],
+                read_account_row,
+            )
+            .optional()
+            .map_err(|error| format!("No se pudo leer la cuenta {account_id}: {error}"))?;
+        row.map(|raw| self.decrypt_row(raw)).transpose()
+    }
+
+    pub(crate) fn default_account(
+        &self,
+        conn: &Connection,
+    ) -> Result<Option<StoredAccount>, String> {
+        let id = conn
+            .query_row(
+                "SELECT id FROM accounts ORDER BY is_default DESC, last_used_at DESC LIMIT 1",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|error| format!("No se pudo leer cuenta por defecto: {error}"))?;
+        match id {
+            Some(id) => self.get(conn, &id),
+            None => Ok(None),
+        }
+    }
+
+    /// Las filas que no se pueden leer o descifrar se omiten para no ocultar el resto.
+    pub(crate) fn list(&self, conn: &Connection) -> Result<Vec<StoredAccount>, String> {
+        let mut statement = conn
+            .prepare(
+                "SELECT id, kind, username, uuid, access_token, refresh_token, expires_at, is_default, created_at, last_used_at, auth_server
+                 FROM accounts ORDER BY is_default DESC, created_at ASC",
+            )
+            .map_err(|error| format!("No se pudo preparar consulta de cuentas: {error}"))?;
+        let rows = statement
+            .query_map([], read_account_row)
+            .map_err(|error| format!("No se pudo listar cuentas: {error}"))?;
+        let mut accounts = Vec::new();
+        for row in rows {
+            match row
+                .map_err(|error| format!("Cuenta inválida en base de datos: {error}"))
+                .and_then(|raw| self.decrypt_row(raw))
+            {
+                Ok(account) => accounts.push(account),
+                Err(error) => eprintln!("[accounts] {error}"),
+            }
+        }
+        Ok(accounts)
+    }
+
+    pub(crate) fn remove(&self, conn: &Connection, account_id: &str) -> Result<bool, String> {
+        let removed = conn
+            .execute("DELETE FROM accounts WHERE id = ?1", params![account_id])
+            .map_err(|error| format!("No se pudo eliminar la cuenta: {error}"))?;
+        let has_default = conn
+            .query_row(
+                "SELECT COUNT(*) FROM accounts WHERE is_default = 1",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|error| format!("No se pudo consultar cuenta por defecto: {error}"))?
+            > 0;
+        if !has_default {
+            conn.execute(
+                "UPDATE accounts SET is_default = 1 WHERE id = (SELECT id FROM accounts ORDER BY created_at ASC LIMIT 1)",
+                [],
+            )
+            .map_err(|error| format!("No se pudo reasignar cuenta por defecto: {error}"))?;
+        }
+        Ok(removed > 0)
+    }
+
+    pub(crate) fn set_default(&self, conn: &Connection, account_id: &str) -> Result<(), String> {
+        let exists = conn
+            .query_row(
+                "SELECT COUNT(*) FROM accounts WHERE id = ?1",
+                params![account_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|error| format!("No se pudo consultar la cuenta: {error}"))?
+            > 0;
+        if !exists {
+            return Err(format!("La cuenta {account_id} no existe."));
+        }
+        conn.execute(
+            "UPDATE accounts SET is_default = (id = ?1)",
+            params![account_id],
+        )
+        .map_err(|error| format!("No se pudo actualizar cuenta por defecto: {error}"))?;
+        Ok(())
+    }
+
+    pub(crate) fn touch(
+        &self,
+        conn: &Connection,
+        account_id: &str,
+        now_secs: u64,
+    ) -> Result<(), String> {
+        conn.execute(
+            "UPDATE accounts SET last_used_at = ?2 WHERE id = ?1",
+            params![account_id, now_secs as i64],
+        )
+        .map_err(|error| format!("No se pudo actualizar uso de la cuenta: {error}"))?;
+        Ok(())
+    }
+
+    fn decrypt_row(&self, raw: RawAccountRow) -> Result<StoredAccount, String> {
+        let access_token = raw
+            .access_token
+            .as_deref()
+            .map(|sealed| self.open(&raw.id, sealed))
+            .transpose()?;
+        let refresh_token = raw
+            .refresh_token
+            .as_deref()
+            .map(|sealed| self.open(&raw.id, sealed))
+            .transpose()?;
+        Ok(StoredAccount {
+            id: raw.id,
+            kind: raw.kind,
+            username: raw.username,
+            uuid: raw.uuid,
+            access_token,
+            refresh_token,
+            expires_at: raw.expires_at,
+            is_default: raw.is_default,
+            created_at: raw.created_at,
+            last_used_at: raw.last_used_at,
+            auth_server: raw.auth_server,
+        })
+    }
+}
+
+struct RawAccountRow {
+    id: String,
+    kind: String,
+    username: String,
+    uuid: String,
+    access_token: Option<Vec<u8>>,
+    refresh_token: Option<Vec<u8>>,
+    expires_at: Option<u64>,
+    is_default: bool,
+    created_at: u64,
+    last_used_at: Option<u64>,
+    auth_server: Option<String>,
+}
+
+fn read_account_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RawAccountRow> {
+    Ok(RawAccountRow {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        username: row.get(2)?,
+        uuid: row.get(3)?,
+        access_token: row.get(4)?,
+        refresh_token: row.get(5)?,
+        expires_at: row
+            .get::<_, Option<i64>>(6)?
+            .map(|value| value.max(0) as u64),
+        is_default: row.get::<_, i64>(7)? != 0,
+        created_at: row.get::<_, Option<i64>>(8)?.unwrap_or(0).max(0) as u64,
+        last_used_at: row
+            .get::<_, Option<i64>>(9)?
+            .map(|value| value.max(0) as u64),
+        auth_server: row.get(10)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::{
+        AccountVault, StoredAccount, ACCOUNTS_SCHEMA, ACCOUNT_KIND_MSA, ACCOUNT_KIND_OFFLINE,
+    };
+
+    fn vault_with_db() -> (AccountVault, Connection) {
+        let conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch(ACCOUNTS_SCHEMA).expect("schema");
+        (
+            AccountVault::from_key_bytes(&[7_u8; 32]).expect("key"),
+            conn,
+        )
+    }
+
+    fn msa_account(id: &str, expires_at: u64) -> StoredAccount {
+        StoredAccount {
+            id: id.to_string(),
+            kind: ACCOUNT_KIND_MSA.to_string(),
+            username: format!("user-{id}"),
+            uuid: "069a79f444e94726a5befca90e38aaf5".to_string(),
+            access_token: Some("secret-access".to_string()),
+            refresh_token: Some("secret-refresh".to_string()),
+            expires_at: Some(expires_at),
+            is_default: false,
+            created_at: 1,
+            last_used_at: None,
+            auth_server: None,
+        }
+    }
+
+    #[test]
+    fn tokens_are_encrypted_at_rest_and_roundtrip() {
+        let (vault, conn) = vault_with_db();
+        vault
+            .upsert(&conn, &msa_account("a", 10_000))
+            .expect("upsert");
+
+        let raw: Vec<u8> = conn
+            .query_row(
+                "SELECT access_token FROM accounts WHERE id = 'a'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("raw");
+        assert!(!String::from_utf8_lossy(&raw).contains("secret-access"));
+
+        let stored = vault.get(&conn, "a").expect("get").expect("account");
+        assert_eq!(stored.access_token.as_deref(), Some("secret-access"));
+        assert_eq!(stored.refresh_token.as_deref(), Some("secret-refresh"));
+        assert!(stored.is_default);
+
+        let sealed = vault
+            .seal_secret("proxy-password", "hunter2")
+            .expect("seal");
+        assert!(!sealed.contains("hunter2"));
+        assert_eq!(
+            vault.open_secret("proxy-password", &sealed).expect("open"),
+            "hunter2"
+        );
+        assert!(vault.open_secret("otro", &sealed).is_err());
+    }
+
+    #[test]
+    fn default_account_moves_on_set_and_remove() {
+        let (vault, conn) = vault_with_db();
+        vault.upsert(&conn, &msa_account("a", 10_000)).expect("a");
+        vault.upsert(&conn, &msa_account("b", 10_000)).expect("b");
+        assert_eq!(vault.default_account(&conn).unwrap().unwrap().id, "a");
+
+        vault.set_default(&conn, "b").expect("set default");
+        assert_eq!(vault.default_account(&conn).unwrap().unwrap().id, "b");
+        assert!(vault.set_default(&conn, "missing").is_err());
+        assert_eq!(vault.default_account(&conn).unwrap().unwrap().id, "b");
+
+        assert!(vault.remove(&conn, "b").expect("remove"));
+        assert_eq!(vault.default_account(&conn).unwrap().unwrap().id, "a");
+    }
+
+    #[test]
+    fn expiry_tracking_requests_refresh_near_deadline() {
+        let account = msa_account("a", 1_000);
+        assert!(!account.needs_refresh(100));
+        assert!(account.needs_refresh(900));
+        assert!(account.summary(1_000).expired);
+
+        let offline = StoredAccount {
+            kind: ACCOUNT_KIND_OFFLINE.to_string(),
+            access_token: None,
+            refresh_token: None,
+            expires_at: None,
+            ..account
+        };
+        assert!(!offline.needs_refresh(u64::MAX));
+        assert_eq!(offline.launch_auth().access_token, "0");
+        assert_eq!(offline.launch_auth().user_type, "offline");
+    }
+
+    #[test]
+    fn wrong_key_cannot_decrypt_tokens() {
+        let (vault, conn) = vault_with_db();
+        vault
+            .upsert(&conn, &msa_account("a", 10_000))
+            .expect("upsert");
+        let other = AccountVault::from_key_bytes(&[9_u8; 32]).expect("key");
+        assert!(other.get(&conn, "a").is_err());
+
+        vault.upsert(&conn, &msa_account("b", 10_000)).expect("b");
+        conn.execute(
+            "UPDATE accounts SET refresh_token = x'00010203' WHERE id = 'a'",
+            [],
+        )
+        .expect("corrupt");
+        let listed = vault.list(&conn).expect("list");
+        assert_eq!(
+            listed
+                .iter()
+                .map(|account| account.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b"]
+        );
+
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let key_path = std::env::temp_dir().join(format!("frutistudio-test-vault-key-{unique}"));
+        std::fs::write(&key_path, b"corta").expect("key");
+        assert!(AccountVault::open_or_create_key(&key_path).is_err());
+        assert_eq!(std::fs::read(&key_path).expect("intacta"), b"corta");
+        std::fs::remove_file(&key_path).expect("cleanup");
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use tokio::sync::Semaphore;
+use crate::core::downloader::{DownloadItem, DownloadPriority, DownloadRequest, Downloader};
 
 #[derive(Debug, Clone)]
 pub(crate) struct AssetDownloadTask {
@@ -12,7 +12,7 @@ pub(crate) struct AssetDownloadTask {
 
 pub(crate) async fn download_assets_parallel<F, Fut>(
     tasks: Vec<AssetDownloadTask>,
-    concurrency: usize,
+    group: Option<String>,
     downloader: F,
 ) -> Result<(), String>
 where
@@ -23,20 +23,23 @@ where
         return Ok(());
     }
 
-    let permit_count = concurrency.max(1);
-    let semaphore = Arc::new(Semaphore::new(permit_count));
     let downloader = Arc::new(downloader);
 
     let mut handles = Vec::with_capacity(tasks.len());
     for task in tasks {
-        let semaphore = Arc::clone(&semaphore);
         let downloader = Arc::clone(&downloader);
+        let request = DownloadRequest {
+            item: DownloadItem {
+                url: task.url_candidates.first().cloned().unwrap_or_default(),
+                destination: task.destination.to_string_lossy().to_string(),
+                sha1: task.expected_sha1.clone(),
+            },
+            label: format!("Asset {}", task.destination.display()),
+            group: group.clone(),
+            priority: DownloadPriority::Asset,
+        };
         handles.push(tokio::spawn(async move {
-            let _permit = semaphore
-                .acquire_owned()
-                .await
-                .map_err(|error| format!("No se pudo adquirir semáforo de assets: {error}"))?;
-            downloader(task).await
+            Downloader::global().run(request, downloader(task)).await
         }));
     }
 
@@ -0,0 +1,568 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{AuthProvider, LaunchAuth, MINECRAFT_SERVICES_BASE};
+
+pub const MICROSOFT_OAUTH_BASE: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0";
+pub const XBOX_USER_AUTH_BASE: &str = "https://user.auth.xboxlive.com";
+pub const XSTS_AUTH_BASE: &str = "https://xsts.auth.xboxlive.com";
+pub const MICROSOFT_OAUTH_SCOPE: &str = "XboxLive.signin offline_access";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrosoftAuthEndpoints {
+    pub oauth_base: String,
+    pub xbox_user_base: String,
+    pub xsts_base: String,
+    pub minecraft_services_base: String,
+}
+
+impl Default for MicrosoftAuthEndpoints {
+    fn default() -> Self {
+        Self {
+            oauth_base: MICROSOFT_OAUTH_BASE.to_string(),
+            xbox_user_base: XBOX_USER_AUTH_BASE.to_string(),
+            xsts_base: XSTS_AUTH_BASE.to_string(),
+            minecraft_services_base: MINECRAFT_SERVICES_BASE.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrosoftDeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub expires_in: u64,
+    pub interval: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrosoftSession {
+    pub auth: LaunchAuth,
+    pub refresh_token: Option<String>,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    #[serde(default)]
+    interval: Option<u64>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OAuthTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxTokenResponse {
+    token: String,
+    display_claims: XboxDisplayClaims,
+}
+
+#[derive(Debug, Deserialize)]
+struct XboxDisplayClaims {
+    xui: Vec<XboxUserClaim>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XboxUserClaim {
+    uhs: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftLoginResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftProfileResponse {
+    id: String,
+    name: String,
+}
+
+pub struct MicrosoftAuthProvider {
+    client: reqwest::Client,
+    client_id: String,
+    endpoints: MicrosoftAuthEndpoints,
+    device_code: Option<MicrosoftDeviceCode>,
+}
+
+impl MicrosoftAuthProvider {
+    pub fn new(
+        client: reqwest::Client,
+        client_id: impl Into<String>,
+        endpoints: MicrosoftAuthEndpoints,
+    ) -> Self {
+        Self {
+            client,
+            client_id: client_id.into(),
+            endpoints,
+            device_code: None,
+        }
+    }
+
+    pub fn with_device_code(mut self, device_code: MicrosoftDeviceCode) -> Self {
+        self.device_code = Some(device_code);
+        self
+    }
+
+    pub async fn request_device_code(&self) -> Result<MicrosoftDeviceCode, String> {
+        let url = format!("{}/devicecode", trim_base(&self.endpoints.oauth_base));
+        let response = self
+            .client
+            .post(&url)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("scope", MICROSOFT_OAUTH_SCOPE),
+            ])
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo solicitar código de dispositivo: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Microsoft rechazó la solicitud de código de dispositivo ({status}): {body}"
+            ));
+        }
+        let parsed = response
+            .json::<DeviceCodeResponse>()
+            .await
+            .map_err(|error| format!("Respuesta inválida de código de dispositivo: {error}"))?;
+        Ok(MicrosoftDeviceCode {
+            device_code: parsed.device_code,
+            user_code: parsed.user_code,
+            verification_uri: parsed.verification_uri,
+            expires_in: parsed.expires_in,
+            interval: parsed.interval.unwrap_or(5),
+            message: parsed.message,
+        })
+    }
+
+    pub async fn poll_device_token(
+        &self,
+        device_code: &MicrosoftDeviceCode,
+    ) -> Result<(String, Option<String>), String> {
+        let url = format!("{}/token", trim_base(&self.endpoints.oauth_base));
+        let deadline = unix_now_secs().saturating_add(device_code.expires_in.max(1));
+        let mut interval = device_code.interval.max(1);
+
+        loop {
+            let response = self
+                .client
+                .post(&url)
+                .form(&[
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                    ("client_id", self.client_id.as_str()),
+                    ("device_code", device_code.device_code.as_str()),
+                ])
+                .send()
+                .await
+                .map_err(|error| format!("No se pudo consultar token de Microsoft: {error}"))?;
+
+            if response.status().is_success() {
+                let token = response
+                    .json::<OAuthTokenResponse>()
+                    .await
+                    .map_err(|error| format!("Token de Microsoft inválido: {error}"))?;
+                return Ok((token.access_token, token.refresh_token));
+            }
+
+            let status = response.status();
+            let body = response.json::<Value>().await.unwrap_or(Value::Null);
+            let error_code = body.get("error").and_then(Value::as_str).unwrap_or("");
+            match error_code {
+                "authorization_pending" => {}
+                "slow_down" => interval = interval.saturating_add(5),
+                "authorization_declined" => {
+                    return Err("El usuario rechazó el inicio de sesión de Microsoft.".to_string())
+                }
+                "expired_token" => {
+                    return Err(
+                        "El código de dispositivo expiró; vuelve a iniciar sesión.".to_string()
+                    )
+                }
+                _ => {
+                    return Err(format!(
+                        "Error OAuth de Microsoft ({status}): {}",
+                        body.get("error_description")
+                            .and_then(Value::as_str)
+                            .unwrap_or(error_code)
+                    ))
+                }
+            }
+
+            if unix_now_secs() >= deadline {
+                return Err("Tiempo de espera agotado durante login Microsoft.".to_string());
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    }
+
+    pub async fn refresh_msa_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<(String, Option<String>), String> {
+        let url = format!("{}/token", trim_base(&self.endpoints.oauth_base));
+        let response = self
+            .client
+            .post(&url)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("client_id", self.client_id.as_str()),
+                ("refresh_token", refresh_token),
+                ("scope", MICROSOFT_OAUTH_SCOPE),
+            ])
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo refrescar token de Microsoft: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Microsoft rechazó el refresh token ({status}): {body}"
+            ));
+        }
+        let token = response
+            .json::<OAuthTokenResponse>()
+            .await
+            .map_err(|error| format!("Token refrescado de Microsoft inválido: {error}"))?;
+        Ok((token.access_token, token.refresh_token))
+    }
+
+    pub async fn login_with_msa_token(&self, msa_token: &str) -> Result<MicrosoftSession, String> {
+        let user_token = self
+            .post_xbox(
+                &format!(
+                    "{}/user/authenticate",
+                    trim_base(&self.endpoints.xbox_user_base)
+                ),
+                serde_json::json!({
+                    "Properties": {
+                        "AuthMethod": "RPS",
+                        "SiteName": "user.auth.xboxlive.com",
+                        "RpsTicket": format!("d={msa_token}"),
+                    },
+                    "RelyingParty": "http://auth.xboxlive.com",
+                    "TokenType": "JWT",
+                }),
+                "Xbox Live",
+            )
+            .await?;
+
+        let xsts = self
+            .post_xbox(
+                &format!("{}/xsts/authorize", trim_base(&self.endpoints.xsts_base)),
+                serde_json::json!({
+                    "Properties": {
+                        "SandboxId": "RETAIL",
+                        "UserTokens": [user_token.token],
+                    },
+                    "RelyingParty": "rp://api.minecraftservices.com/",
+                    "TokenType": "JWT",
+                }),
+                "XSTS",
+            )
+            .await?;
+        let uhs = xsts
+            .display_claims
+            .xui
+            .first()
+            .map(|claim| claim.uhs.clone())
+            .ok_or_else(|| "XSTS no devolvió user hash (uhs).".to_string())?;
+
+        let services_base = trim_base(&self.endpoints.minecraft_services_base);
+        let login_response = self
+            .client
+            .post(format!("{services_base}/authentication/login_with_xbox"))
+            .json(&serde_json::json!({
+                "identityToken": format!("XBL3.0 x={uhs};{}", xsts.token),
+            }))
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo iniciar sesión en Minecraft: {error}"))?;
+        let status = login_response.status();
+        if !status.is_success() {
+            let body = login_response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Minecraft services rechazó login_with_xbox ({status}): {body}"
+            ));
+        }
+        let minecraft = login_response
+            .json::<MinecraftLoginResponse>()
+            .await
+            .map_err(|error| format!("Respuesta inválida de login_with_xbox: {error}"))?;
+
+        let profile = self.fetch_profile(&minecraft.access_token).await?;
+
+        Ok(MicrosoftSession {
+            auth: LaunchAuth {
+                username: profile.name,
+                uuid: profile.id.replace('-', "").to_ascii_lowercase(),
+                access_token: minecraft.access_token,
+                user_type: "msa".to_string(),
+            },
+            refresh_token: None,
+            expires_at: unix_now_secs().saturating_add(minecraft.expires_in),
+        })
+    }
+
+    pub async fn complete_device_login(&self) -> Result<MicrosoftSession, String> {
+        let device_code = self.device_code.as_ref().ok_or_else(|| {
+            "No hay un código de dispositivo activo para completar el login.".to_string()
+        })?;
+        let (msa_token, refresh_token) = self.poll_device_token(device_code).await?;
+        let mut session = self.login_with_msa_token(&msa_token).await?;
+        session.refresh_token = refresh_token;
+        Ok(session)
+    }
+
+    async fn fetch_profile(
+        &self,
+        minecraft_token: &str,
+    ) -> Result<MinecraftProfileResponse, String> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/minecraft/profile",
+                trim_base(&self.endpoints.minecraft_services_base)
+            ))
+            .bearer_auth(minecraft_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo obtener perfil de Minecraft: {error}"))?;
+        match response.status() {
+            status if status.is_success() => response
+                .json::<MinecraftProfileResponse>()
+                .await
+                .map_err(|error| format!("Perfil de Minecraft inválido: {error}")),
+            reqwest::StatusCode::NOT_FOUND => {
+                Err("La cuenta autenticada no tiene perfil de Minecraft Java activo.".to_string())
+            }
+            reqwest::StatusCode::UNAUTHORIZED => Err(
+                "Token de Minecraft inválido o expirado al consultar /minecraft/profile."
+                    .to_string(),
+            ),
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                Err(format!(
+                    "No se pudo obtener perfil Minecraft ({status}): {body}"
+                ))
+            }
+        }
+    }
+
+    async fn post_xbox(
+        &self,
+        url: &str,
+        payload: Value,
+        label: &str,
+    ) -> Result<XboxTokenResponse, String> {
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo autenticar con {label}: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.json::<Value>().await.unwrap_or(Value::Null);
+            return Err(xbox_error_message(label, status, &body));
+        }
+        response
+            .json::<XboxTokenResponse>()
+            .await
+            .map_err(|error| format!("Respuesta inválida de {label}: {error}"))
+    }
+}
+
+impl AuthProvider for MicrosoftAuthProvider {
+    async fn resolve_launch_auth(&self) -> Result<LaunchAuth, String> {
+        self.complete_device_login()
+            .await
+            .map(|session| session.auth)
+    }
+}
+
+fn xbox_error_message(label: &str, status: reqwest::StatusCode, body: &Value) -> String {
+    let xerr = body.get("XErr").and_then(Value::as_u64);
+    match xerr {
+        Some(2148916233) => {
+            "La cuenta de Microsoft no tiene perfil de Xbox; créalo en xbox.com e inténtalo de nuevo."
+                .to_string()
+        }
+        Some(2148916235) => "Xbox Live no está disponible en el país de esta cuenta.".to_string(),
+        Some(2148916236) | Some(2148916237) => {
+            "La cuenta requiere verificación de edad adulta en Xbox.".to_string()
+        }
+        Some(2148916238) => {
+            "La cuenta es de un menor; debe añadirse a una familia de Microsoft.".to_string()
+        }
+        Some(code) => format!("{label} rechazó la autenticación ({status}, XErr {code})."),
+        None => format!("{label} rechazó la autenticación ({status}): {body}"),
+    }
+}
+
+fn trim_base(base: &str) -> &str {
+    base.trim().trim_end_matches('/')
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{AuthProvider, MicrosoftAuthEndpoints, MicrosoftAuthProvider, MicrosoftDeviceCode};
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    fn endpoints_for(base: &str) -> MicrosoftAuthEndpoints {
+        MicrosoftAuthEndpoints {
+            oauth_base: format!("{base}/oauth"),
+            xbox_user_base: format!("{base}/xbl"),
+            xsts_base: format!("{base}/xsts"),
+            minecraft_services_base: format!("{base}/mc"),
+        }
+    }
+
+    #[test]
+    fn device_code_chain_resolves_launch_auth_against_mock_server() {
+        let base = spawn_mock_server(|request| match request.path.as_str() {
+            "/oauth/devicecode" => MockResponse::json(
+                200,
+                json!({
+                    "device_code": "dev-123",
+                    "user_code": "ABCD-EFGH",
+                    "verification_uri": "https://microsoft.com/link",
+                    "expires_in": 900,
+                    "interval": 1
+                }),
+            ),
+            "/oauth/token" if request.body_text().contains("device_code=dev-123") => {
+                MockResponse::json(
+                    200,
+                    json!({"access_token": "msa-token", "refresh_token": "msa-refresh"}),
+                )
+            }
+            "/xbl/user/authenticate" if request.body_text().contains("d=msa-token") => {
+                MockResponse::json(
+                    200,
+                    json!({"Token": "xbl-token", "DisplayClaims": {"xui": [{"uhs": "uhs-1"}]}}),
+                )
+            }
+            "/xsts/xsts/authorize" if request.body_text().contains("xbl-token") => {
+                MockResponse::json(
+                    200,
+                    json!({"Token": "xsts-token", "DisplayClaims": {"xui": [{"uhs": "uhs-1"}]}}),
+                )
+            }
+            "/mc/authentication/login_with_xbox"
+                if request.body_text().contains("XBL3.0 x=uhs-1;xsts-token") =>
+            {
+                MockResponse::json(
+                    200,
+                    json!({"access_token": "mc-token", "expires_in": 86400}),
+                )
+            }
+            "/mc/minecraft/profile"
+                if request.header("authorization") == Some("Bearer mc-token") =>
+            {
+                MockResponse::json(
+                    200,
+                    json!({"id": "069A79F444E94726A5BEFCA90E38AAF5", "name": "Notch"}),
+                )
+            }
+            _ => MockResponse::json(404, json!({"error": "not_found"})),
+        });
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let provider =
+            MicrosoftAuthProvider::new(reqwest::Client::new(), "client", endpoints_for(&base));
+        let device_code = rt
+            .block_on(provider.request_device_code())
+            .expect("device code");
+        assert_eq!(device_code.user_code, "ABCD-EFGH");
+
+        let provider = provider.with_device_code(device_code);
+        let session = rt
+            .block_on(provider.complete_device_login())
+            .expect("session");
+        assert_eq!(session.refresh_token.as_deref(), Some("msa-refresh"));
+        assert_eq!(session.auth.username, "Notch");
+        assert_eq!(session.auth.uuid, "069a79f444e94726a5befca90e38aaf5");
+        assert_eq!(session.auth.access_token, "mc-token");
+        assert_eq!(session.auth.user_type, "msa");
+
+        let auth = rt
+            .block_on(provider.resolve_launch_auth())
+            .expect("launch auth");
+        assert_eq!(auth.username, "Notch");
+    }
+
+    #[test]
+    fn xsts_errors_are_reported_with_friendly_message() {
+        let base = spawn_mock_server(|request| match request.path.as_str() {
+            "/xbl/user/authenticate" => MockResponse::json(
+                200,
+                json!({"Token": "xbl-token", "DisplayClaims": {"xui": [{"uhs": "uhs-1"}]}}),
+            ),
+            _ => MockResponse::json(401, json!({"XErr": 2148916233_u64})),
+        });
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let provider =
+            MicrosoftAuthProvider::new(reqwest::Client::new(), "client", endpoints_for(&base));
+        let error = rt
+            .block_on(provider.login_with_msa_token("msa-token"))
+            .expect_err("xsts debe fallar");
+        assert!(error.contains("perfil de Xbox"));
+    }
+
+    #[test]
+    fn declined_device_login_stops_polling() {
+        let base = spawn_mock_server(|_| {
+            MockResponse::json(400, json!({"error": "authorization_declined"}))
+        });
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let provider =
+            MicrosoftAuthProvider::new(reqwest::Client::new(), "client", endpoints_for(&base))
+                .with_device_code(MicrosoftDeviceCode {
+                    device_code: "dev".to_string(),
+                    user_code: "CODE".to_string(),
+                    verification_uri: "https://microsoft.com/link".to_string(),
+                    expires_in: 30,
+                    interval: 1,
+                    message: None,
+                });
+        let error = rt
+            .block_on(provider.complete_device_login())
+            .expect_err("login rechazado");
+        assert!(error.contains("rechazó"));
+    }
+}
@@ -1,3 +1,8 @@
+pub mod microsoft;
+pub mod yggdrasil;
+
+use std::future::Future;
+
 use serde::{Deserialize, Serialize};
 
 pub const MINECRAFT_SERVICES_BASE: &str = "https://api.minecraftservices.com";
@@ -12,5 +17,5 @@ pub struct LaunchAuth {
 }
 
 pub trait AuthProvider {
-    fn resolve_launch_auth(&self) -> Result<LaunchAuth, String>;
+    fn resolve_launch_auth(&self) -> impl Future<Output = Result<LaunchAuth, String>> + Send;
 }
@@ -0,0 +1,558 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{AuthProvider, LaunchAuth};
+
+pub const AUTHLIB_INJECTOR_LATEST_URL: &str =
+    "https://authlib-injector.yushi.moe/artifact/latest.json";
+pub const AUTHLIB_INJECTOR_API_LOCATION_HEADER: &str = "x-authlib-injector-api-location";
+pub const YGGDRASIL_USER_TYPE: &str = "mojang";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YggdrasilSession {
+    pub auth: LaunchAuth,
+    pub client_token: String,
+    pub api_root: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthlibInjectorAgent {
+    pub jar_path: String,
+    pub api_root: String,
+    pub prefetched_metadata: Option<String>,
+}
+
+impl AuthlibInjectorAgent {
+    /// Inserta `-javaagent` y los metadatos precargados, sustituyendo cualquier agente previo.
+    pub fn apply_to_jvm_args(&self, java_args: &mut Vec<String>) {
+        java_args.retain(|arg| {
+            let previous_agent = arg.starts_with("-javaagent:") && arg.contains("authlib-injector");
+            let previous_metadata = arg.starts_with("-Dauthlibinjector.yggdrasil.prefetched=");
+            !previous_agent && !previous_metadata
+        });
+        let mut injected = vec![format!("-javaagent:{}={}", self.jar_path, self.api_root)];
+        if let Some(metadata) = &self.prefetched_metadata {
+            injected.push(format!("-Dauthlibinjector.yggdrasil.prefetched={metadata}"));
+        }
+        java_args.splice(0..0, injected);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YggdrasilAuthResponse {
+    access_token: String,
+    client_token: String,
+    #[serde(default)]
+    selected_profile: Option<YggdrasilProfile>,
+    #[serde(default)]
+    available_profiles: Vec<YggdrasilProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YggdrasilProfile {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthlibInjectorArtifact {
+    version: String,
+    download_url: String,
+    checksums: AuthlibInjectorChecksums,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthlibInjectorChecksums {
+    sha256: String,
+}
+
+pub struct YggdrasilAuthProvider {
+    client: reqwest::Client,
+    server_url: String,
+    session: Option<YggdrasilSession>,
+}
+
+impl YggdrasilAuthProvider {
+    pub fn new(client: reqwest::Client, server_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            server_url: server_url.into(),
+            session: None,
+        }
+    }
+
+    pub fn with_session(mut self, session: YggdrasilSession) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Resuelve la raíz de la API siguiendo la cabecera ALI de authlib-injector.
+    pub async fn resolve_api_root(&self) -> Result<String, String> {
+        let server_url = normalize_server_url(&self.server_url)?;
+        let response = self
+            .client
+            .get(&server_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo contactar el servidor Yggdrasil: {error}"))?;
+        let Some(location) = response
+            .headers()
+            .get(AUTHLIB_INJECTOR_API_LOCATION_HEADER)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Ok(trim_base(&server_url).to_string());
+        };
+        let resolved = reqwest::Url::parse(&server_url)
+            .and_then(|base| base.join(location))
+            .map_err(|error| format!("Cabecera ALI inválida en {server_url}: {error}"))?;
+        Ok(trim_base(resolved.as_str()).to_string())
+    }
+
+    pub async fn fetch_metadata(&self, api_root: &str) -> Result<Value, String> {
+        let response = self
+            .client
+            .get(format!("{}/", trim_base(api_root)))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo leer metadatos Yggdrasil: {error}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "El servidor Yggdrasil devolvió {} al pedir metadatos.",
+                response.status()
+            ));
+        }
+        response
+            .json::<Value>()
+            .await
+            .map_err(|error| format!("Metadatos Yggdrasil inválidos: {error}"))
+    }
+
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+        client_token: Option<&str>,
+    ) -> Result<YggdrasilSession, String> {
+        let api_root = self.resolve_api_root().await?;
+        let mut payload = serde_json::json!({
+            "agent": {"name": "Minecraft", "version": 1},
+            "username": username,
+            "password": password,
+            "requestUser": true
+        });
+        if let Some(client_token) = client_token {
+            payload["clientToken"] = Value::String(client_token.to_string());
+        }
+        let response = self
+            .post_authserver(&api_root, "authenticate", payload)
+            .await?;
+        session_from_response(response, &api_root, None)
+    }
+
+    pub async fn validate(&self, session: &YggdrasilSession) -> Result<bool, String> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/authserver/validate",
+                trim_base(&session.api_root)
+            ))
+            .json(&serde_json::json!({
+                "accessToken": session.auth.access_token,
+                "clientToken": session.client_token
+            }))
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo validar sesión Yggdrasil: {error}"))?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => Ok(false),
+            status => Err(format!(
+                "El servidor Yggdrasil devolvió {status} al validar la sesión."
+            )),
+        }
+    }
+
+    pub async fn refresh(&self, session: &YggdrasilSession) -> Result<YggdrasilSession, String> {
+        let response = self
+            .post_authserver(
+                &session.api_root,
+                "refresh",
+                serde_json::json!({
+                    "accessToken": session.auth.access_token,
+                    "clientToken": session.client_token,
+                    "requestUser": true
+                }),
+            )
+            .await?;
+        // Algunos servidores no repiten `selectedProfile` al refrescar.
+        session_from_response(response, &session.api_root, Some(&session.auth))
+    }
+
+    /// Valida la sesión guardada y sólo la refresca si el servidor la rechaza.
+    pub async fn ensure_valid_session(&self) -> Result<YggdrasilSession, String> {
+        let session = self
+            .session
+            .clone()
+            .ok_or_else(|| "No hay sesión Yggdrasil; inicia sesión de nuevo.".to_string())?;
+        if self.validate(&session).await? {
+            return Ok(session);
+        }
+        self.refresh(&session).await
+    }
+
+    async fn post_authserver(
+        &self,
+        api_root: &str,
+        endpoint: &str,
+        payload: Value,
+    ) -> Result<YggdrasilAuthResponse, String> {
+        let response = self
+            .client
+            .post(format!("{}/authserver/{endpoint}", trim_base(api_root)))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo contactar authserver/{endpoint}: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.json::<Value>().await.unwrap_or(Value::Null);
+            let message = body
+                .get("errorMessage")
+                .and_then(Value::as_str)
+                .unwrap_or("sin detalle");
+            return Err(format!(
+                "El servidor Yggdrasil rechazó {endpoint} ({status}): {message}"
+            ));
+        }
+        response
+            .json::<YggdrasilAuthResponse>()
+            .await
+            .map_err(|error| format!("Respuesta inválida de authserver/{endpoint}: {error}"))
+    }
+}
+
+impl AuthProvider for YggdrasilAuthProvider {
+    async fn resolve_launch_auth(&self) -> Result<LaunchAuth, String> {
+        self.ensure_valid_session()
+            .await
+            .map(|session| session.auth)
+    }
+}
+
+/// Descarga (o reutiliza) el jar de authlib-injector verificando su SHA-256.
+pub async fn ensure_authlib_injector_jar(
+    client: &reqwest::Client,
+    latest_url: &str,
+    cache_dir: &Path,
+) -> Result<PathBuf, String> {
+    let artifact = match client.get(latest_url).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<AuthlibInjectorArtifact>()
+            .await
+            .map_err(|error| format!("Metadatos de authlib-injector inválidos: {error}"))?,
+        Ok(response) => {
+            return cached_authlib_injector_jar(cache_dir).ok_or_else(|| {
+                format!(
+                    "No se pudo consultar authlib-injector ({}) y no hay copia en caché.",
+                    response.status()
+                )
+            })
+        }
+        Err(error) => {
+            return cached_authlib_injector_jar(cache_dir).ok_or_else(|| {
+                format!("No se pudo consultar authlib-injector y no hay copia en caché: {error}")
+            })
+        }
+    };
+
+    let jar_path = cache_dir.join(format!("authlib-injector-{}.jar", artifact.version));
+    let expected = artifact.checksums.sha256.to_ascii_lowercase();
+    if fs::read(&jar_path)
+        .map(|bytes| sha256_hex(&bytes) == expected)
+        .unwrap_or(false)
+    {
+        return Ok(jar_path);
+    }
+
+    let bytes = client
+        .get(&artifact.download_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| format!("No se pudo descargar authlib-injector: {error}"))?
+        .bytes()
+        .await
+        .map_err(|error| format!("No se pudo leer authlib-injector: {error}"))?;
+    let actual = sha256_hex(&bytes);
+    if actual != expected {
+        return Err(format!(
+            "SHA-256 de authlib-injector no coincide: esperado {expected}, obtenido {actual}"
+        ));
+    }
+
+    fs::create_dir_all(cache_dir)
+        .map_err(|error| format!("No se pudo crear caché de authlib-injector: {error}"))?;
+    let temp_path = jar_path.with_extension("jar.part");
+    fs::write(&temp_path, &bytes)
+        .map_err(|error| format!("No se pudo guardar authlib-injector: {error}"))?;
+    fs::rename(&temp_path, &jar_path)
+        .map_err(|error| format!("No se pudo mover authlib-injector a caché: {error}"))?;
+    Ok(jar_path)
+}
+
+fn cached_authlib_injector_jar(cache_dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(cache_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("authlib-injector-") && name.ends_with(".jar"))
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+}
+
+pub fn encode_prefetched_metadata(metadata: &Value) -> String {
+    base64::engine::general_purpose::STANDARD.encode(metadata.to_string())
+}
+
+fn session_from_response(
+    response: YggdrasilAuthResponse,
+    api_root: &str,
+    previous: Option<&LaunchAuth>,
+) -> Result<YggdrasilSession, String> {
+    let profile = response
+        .selected_profile
+        .or_else(|| {
+            if response.available_profiles.len() == 1 {
+                response.available_profiles.into_iter().next()
+            } else {
+                None
+            }
+        })
+        .or_else(|| {
+            previous.map(|auth| YggdrasilProfile {
+                id: auth.uuid.clone(),
+                name: auth.username.clone(),
+            })
+        })
+        .ok_or_else(|| {
+            "El servidor Yggdrasil no devolvió un perfil seleccionado para la cuenta.".to_string()
+        })?;
+    Ok(YggdrasilSession {
+        auth: LaunchAuth {
+            username: profile.name,
+            uuid: profile.id.replace('-', "").to_ascii_lowercase(),
+            access_token: response.access_token,
+            user_type: YGGDRASIL_USER_TYPE.to_string(),
+        },
+        client_token: response.client_token,
+        api_root: trim_base(api_root).to_string(),
+    })
+}
+
+fn normalize_server_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("Debes indicar la URL del servidor de autenticación.".to_string());
+    }
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{trimmed}")
+    };
+    reqwest::Url::parse(&with_scheme)
+        .map(|url| url.to_string())
+        .map_err(|error| format!("URL de servidor Yggdrasil inválida: {error}"))
+}
+
+fn trim_base(base: &str) -> &str {
+    base.trim_end_matches('/')
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde_json::json;
+
+    use super::{
+        encode_prefetched_metadata, ensure_authlib_injector_jar, sha256_hex, AuthProvider,
+        AuthlibInjectorAgent, YggdrasilAuthProvider,
+    };
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    fn yggdrasil_stub() -> String {
+        spawn_mock_server(
+            |request| match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/") => MockResponse {
+                    status: 200,
+                    headers: vec![(
+                        "X-Authlib-Injector-API-Location".to_string(),
+                        "/api/yggdrasil/".to_string(),
+                    )],
+                    body: b"<html></html>".to_vec(),
+                },
+                ("GET", "/api/yggdrasil/") => MockResponse::json(
+                    200,
+                    json!({"meta": {"serverName": "Stub"}, "skinDomains": ["localhost"]}),
+                ),
+                ("POST", "/api/yggdrasil/authserver/authenticate")
+                    if request.body_text().contains("\"password\":\"hunter2\"") =>
+                {
+                    MockResponse::json(
+                        200,
+                        json!({
+                            "accessToken": "ygg-access-1",
+                            "clientToken": "client-1",
+                            "selectedProfile": {"id": "0123456789ABCDEF0123456789ABCDEF", "name": "Alex"}
+                        }),
+                    )
+                }
+                ("POST", "/api/yggdrasil/authserver/authenticate") => MockResponse::json(
+                    403,
+                    json!({"error": "ForbiddenOperationException", "errorMessage": "Invalid credentials."}),
+                ),
+                ("POST", "/api/yggdrasil/authserver/validate")
+                    if request.body_text().contains("ygg-access-2") =>
+                {
+                    MockResponse {
+                        status: 204,
+                        headers: Vec::new(),
+                        body: Vec::new(),
+                    }
+                }
+                ("POST", "/api/yggdrasil/authserver/validate") => MockResponse::json(
+                    403,
+                    json!({"error": "ForbiddenOperationException", "errorMessage": "Invalid token."}),
+                ),
+                ("POST", "/api/yggdrasil/authserver/refresh")
+                    if request.body_text().contains("ygg-access-1") =>
+                {
+                    MockResponse::json(
+                        200,
+                        json!({
+                            "accessToken": "ygg-access-2",
+                            "clientToken": "client-1",
+                            "selectedProfile": {"id": "0123456789abcdef0123456789abcdef", "name": "Alex"}
+                        }),
+                    )
+                }
+                _ => MockResponse::json(404, json!({"error": "not_found"})),
+            },
+        )
+    }
+
+    #[test]
+    fn authenticates_and_refreshes_against_local_yggdrasil_stub() {
+        let base = yggdrasil_stub();
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let provider = YggdrasilAuthProvider::new(reqwest::Client::new(), format!("{base}/"));
+
+        let api_root = rt.block_on(provider.resolve_api_root()).expect("api root");
+        assert_eq!(api_root, format!("{base}/api/yggdrasil"));
+        let metadata = rt
+            .block_on(provider.fetch_metadata(&api_root))
+            .expect("metadata");
+        assert_eq!(metadata["meta"]["serverName"], "Stub");
+
+        let wrong = rt.block_on(provider.authenticate("alex@example.com", "nope", None));
+        assert!(wrong.unwrap_err().contains("Invalid credentials."));
+
+        let session = rt
+            .block_on(provider.authenticate("alex@example.com", "hunter2", None))
+            .expect("session");
+        assert_eq!(session.auth.uuid, "0123456789abcdef0123456789abcdef");
+        assert_eq!(session.auth.user_type, "mojang");
+        assert_eq!(session.client_token, "client-1");
+
+        let provider = provider.with_session(session);
+        let auth = rt
+            .block_on(provider.resolve_launch_auth())
+            .expect("refreshed auth");
+        assert_eq!(auth.access_token, "ygg-access-2");
+        assert_eq!(auth.username, "Alex");
+    }
+
+    #[test]
+    fn downloads_verified_authlib_injector_and_injects_jvm_args() {
+        let jar_bytes = b"PK\x03\x04fake-authlib".to_vec();
+        let checksum = sha256_hex(&jar_bytes);
+        let base = spawn_mock_server(move |request| match request.path.as_str() {
+            "/latest.json" => MockResponse::json(
+                200,
+                json!({
+                    "build_number": 53,
+                    "version": "1.2.5",
+                    "download_url": format!("http://{}/authlib-injector.jar", request.header("host").unwrap_or_default()),
+                    "checksums": {"sha256": checksum}
+                }),
+            ),
+            "/authlib-injector.jar" => MockResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: jar_bytes.clone(),
+            },
+            _ => MockResponse::json(404, json!({})),
+        });
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let cache_dir = std::env::temp_dir().join(format!("frutistudio-test-authlib-{unique}"));
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let jar = rt
+            .block_on(ensure_authlib_injector_jar(
+                &reqwest::Client::new(),
+                &format!("{base}/latest.json"),
+                &cache_dir,
+            ))
+            .expect("jar");
+        assert!(jar.ends_with("authlib-injector-1.2.5.jar"));
+        assert_eq!(
+            fs::read(&jar).expect("cached jar"),
+            b"PK\x03\x04fake-authlib"
+        );
+
+        let agent = AuthlibInjectorAgent {
+            jar_path: jar.to_string_lossy().to_string(),
+            api_root: "https://skins.example/api/yggdrasil".to_string(),
+            prefetched_metadata: Some(encode_prefetched_metadata(&json!({"meta": {}}))),
+        };
+        let mut java_args = vec![
+            "-javaagent:/old/authlib-injector-1.0.jar=https://old".to_string(),
+            "-Xmx2G".to_string(),
+        ];
+        agent.apply_to_jvm_args(&mut java_args);
+        assert_eq!(java_args.len(), 3);
+        assert!(java_args[0].starts_with("-javaagent:"));
+        assert!(java_args[0].ends_with("=https://skins.example/api/yggdrasil"));
+        assert_eq!(
+            java_args[1],
+            "-Dauthlibinjector.yggdrasil.prefetched=eyJtZXRhIjp7fX0="
+        );
+        assert_eq!(java_args[2], "-Xmx2G");
+
+        fs::remove_dir_all(cache_dir).expect("cleanup");
+    }
+}
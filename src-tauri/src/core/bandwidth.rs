@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::core::config::NetworkTuning;
+
+static GLOBAL_LIMITER: Lazy<BandwidthLimiter> = Lazy::new(BandwidthLimiter::default);
+
+/// Token bucket con ráfaga de un segundo. El saldo negativo es deuda que paga
+/// el siguiente que reserve, así el total respeta el límite aunque haya muchas descargas.
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_sec: u64,
+    available: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            available: bytes_per_sec as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn reserve(&mut self, bytes: u64, now: Instant) -> Duration {
+        let rate = self.bytes_per_sec as f64;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * rate).min(rate);
+        self.updated = now;
+        self.available -= bytes as f64;
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / rate)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    global: Option<TokenBucket>,
+    hosts: HashMap<String, TokenBucket>,
+}
+
+/// Límite de ancho de banda compartido por todas las descargas del proceso.
+#[derive(Debug, Default)]
+pub(crate) struct BandwidthLimiter {
+    state: Mutex<LimiterState>,
+}
+
+fn kib_to_bytes(kib_per_sec: u64) -> Option<u64> {
+    (kib_per_sec > 0).then(|| kib_per_sec.saturating_mul(1024))
+}
+
+fn host_matches(host: &str, pattern: &str) -> bool {
+    host == pattern
+        || host
+            .strip_suffix(pattern)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+impl BandwidthLimiter {
+    pub(crate) fn global() -> &'static BandwidthLimiter {
+        &GLOBAL_LIMITER
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Aplica los límites de `NetworkTuning`. Los buckets cuyo límite no cambió conservan su saldo.
+    pub(crate) fn configure(&self, tuning: &NetworkTuning) {
+        self.configure_limits(
+            tuning.download_limit_kib_per_sec.unwrap_or(0),
+            &tuning.host_download_limits_kib_per_sec,
+        );
+    }
+
+    fn configure_limits(&self, global_kib: u64, hosts: &BTreeMap<String, u64>) {
+        let mut state = self.lock();
+        state.global = match (kib_to_bytes(global_kib), state.global.take()) {
+            (Some(rate), Some(bucket)) if bucket.bytes_per_sec == rate => Some(bucket),
+            (Some(rate), _) => Some(TokenBucket::new(rate)),
+            (None, _) => None,
+        };
+
+        let mut previous = std::mem::take(&mut state.hosts);
+        for (host, kib) in hosts {
+            let host = host.trim().trim_start_matches('.').to_ascii_lowercase();
+            let Some(rate) = kib_to_bytes(*kib).filter(|_| !host.is_empty()) else {
+                continue;
+            };
+            let bucket = match previous.remove(&host) {
+                Some(bucket) if bucket.bytes_per_sec == rate => bucket,
+                _ => TokenBucket::new(rate),
+            };
+            state.hosts.insert(host, bucket);
+        }
+    }
+
+    /// `true` si hay un límite global o uno que afecte a `host`.
+    pub(crate) fn limits(&self, host: Option<&str>) -> bool {
+        let state = self.lock();
+        state.global.is_some()
+            || host.map(str::to_ascii_lowercase).is_some_and(|host| {
+                state
+                    .hosts
+                    .keys()
+                    .any(|pattern| host_matches(&host, pattern))
+            })
+    }
+
+    /// Reserva `bytes` y devuelve cuánto hay que esperar antes de seguir leyendo.
+    pub(crate) fn reserve(&self, host: Option<&str>, bytes: u64) -> Duration {
+        let mut state = self.lock();
+        let now = Instant::now();
+        let mut wait = state
+            .global
+            .as_mut()
+            .map(|bucket| bucket.reserve(bytes, now))
+            .unwrap_or_default();
+        if let Some(host) = host.map(str::to_ascii_lowercase) {
+            for (pattern, bucket) in state.hosts.iter_mut() {
+                if host_matches(&host, pattern) {
+                    wait = wait.max(bucket.reserve(bytes, now));
+                }
+            }
+        }
+        wait
+    }
+
+    pub(crate) async fn throttle(&self, host: Option<&str>, bytes: u64) {
+        let wait = self.reserve(host, bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use super::BandwidthLimiter;
+
+    #[test]
+    fn global_and_host_buckets_accumulate_debt() {
+        let limiter = BandwidthLimiter::default();
+        assert_eq!(
+            limiter.reserve(Some("example.com"), 1 << 20),
+            Duration::ZERO
+        );
+
+        limiter.configure_limits(2, &BTreeMap::new());
+        assert_eq!(limiter.reserve(None, 2048), Duration::ZERO);
+        let global_wait = limiter.reserve(None, 1024);
+        assert!(global_wait > Duration::from_millis(400));
+        assert!(global_wait <= Duration::from_millis(500));
+
+        let hosts = BTreeMap::from([("mojang.com".to_string(), 1)]);
+        limiter.configure_limits(0, &hosts);
+        let host_wait = limiter.reserve(Some("piston-data.MOJANG.com"), 2048);
+        assert!(host_wait > Duration::from_millis(900));
+        assert_eq!(limiter.reserve(Some("notmojang.com"), 2048), Duration::ZERO);
+        assert!(limiter.limits(Some("piston-data.mojang.com")));
+        assert!(!limiter.limits(Some("notmojang.com")));
+
+        limiter.configure_limits(0, &BTreeMap::new());
+        assert_eq!(limiter.reserve(Some("mojang.com"), 1 << 20), Duration::ZERO);
+        assert!(!limiter.limits(Some("mojang.com")));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -7,6 +9,36 @@ pub(crate) struct NetworkTuning {
     pub(crate) connect_timeout_secs: u64,
     pub(crate) request_timeout_secs: u64,
     pub(crate) retries: u8,
+    /// Límite global de descarga en KiB/s; `None` o 0 es sin límite.
+    #[serde(default)]
+    pub(crate) download_limit_kib_per_sec: Option<u64>,
+    /// Límites por host (también aplica a subdominios), en KiB/s.
+    #[serde(default)]
+    pub(crate) host_download_limits_kib_per_sec: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub(crate) proxy: Option<ProxyConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProxyConfig {
+    /// `http://`, `https://`, `socks5://` o `socks5h://` (DNS resuelto por el proxy).
+    #[serde(default)]
+    pub(crate) url: Option<String>,
+    #[serde(default)]
+    pub(crate) username: Option<String>,
+    /// Sólo llega desde la UI al guardar; nunca se escribe en config.json ni se devuelve.
+    #[serde(default, skip_serializing)]
+    pub(crate) password: Option<String>,
+    /// Contraseña cifrada con la clave del almacén de cuentas.
+    #[serde(default)]
+    pub(crate) password_sealed: Option<String>,
+    /// Hosts, dominios o rangos CIDR que se conectan sin proxy.
+    #[serde(default)]
+    pub(crate) no_proxy: Vec<String>,
+    /// Sin `url`, toma el proxy de las variables de entorno (por defecto sí).
+    #[serde(default)]
+    pub(crate) use_environment: Option<bool>,
 }
 
 impl Default for NetworkTuning {
@@ -15,10 +47,49 @@ impl Default for NetworkTuning {
             connect_timeout_secs: 12,
             request_timeout_secs: 120,
             retries: 4,
+            download_limit_kib_per_sec: None,
+            host_download_limits_kib_per_sec: BTreeMap::new(),
+            proxy: None,
         }
     }
 }
 
+/// Reescribe URLs que empiezan por `prefix` hacia `target` (base HTTP o directorio `file://`
+/// con la misma estructura que el repositorio original).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EndpointOverride {
+    pub(crate) prefix: String,
+    pub(crate) target: String,
+    /// Descarta la URL original en vez de dejarla como respaldo (mirrors offline).
+    #[serde(default)]
+    pub(crate) exclusive: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MirrorConfig {
+    #[serde(default)]
+    pub(crate) overrides: Vec<EndpointOverride>,
+}
+
+/// Caché HTTP de metadata JSON/XML. Los TTL van en segundos; `None` usa el de cada clase.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpCacheConfig {
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+    /// Manifiestos de versiones de Minecraft.
+    #[serde(default)]
+    pub(crate) manifest_ttl_secs: Option<u64>,
+    /// Listados que cambian: metadata de loaders, promociones de Forge, `maven-metadata.xml`.
+    #[serde(default)]
+    pub(crate) metadata_ttl_secs: Option<u64>,
+    /// Recursos direccionados por hash y POMs publicados.
+    #[serde(default)]
+    pub(crate) immutable_ttl_secs: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AppConfig {
@@ -50,6 +121,16 @@ pub(crate) struct AppConfig {
     pub(crate) skins_path: Option<String>,
     pub(crate) explorer_filters: Option<Value>,
     pub(crate) network_tuning: Option<NetworkTuning>,
+    /// Materializa archivos de la caché global SHA-1 como enlaces duros cuando se puede.
+    #[serde(default)]
+    pub(crate) download_cache_hard_links: Option<bool>,
+    #[serde(default)]
+    pub(crate) mirrors: Option<MirrorConfig>,
+    #[serde(default)]
+    pub(crate) http_cache: Option<HttpCacheConfig>,
+    /// Orden de proveedores de Java embebido (`mojang`, `adoptium`).
+    #[serde(default)]
+    pub(crate) java_runtime_providers: Option<Vec<String>>,
     pub(crate) pterodactyl_url: Option<String>,
     pub(crate) pterodactyl_api_key: Option<String>,
 }
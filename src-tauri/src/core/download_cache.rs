@@ -0,0 +1,321 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_sha1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum MaterializeMode {
+    HardLink,
+    Copy,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DownloadCacheStats {
+    pub(crate) root: String,
+    pub(crate) entries: u64,
+    pub(crate) total_bytes: u64,
+    pub(crate) hard_links_enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DownloadCachePrunePolicy {
+    #[serde(default)]
+    pub(crate) max_age_days: Option<u64>,
+    #[serde(default)]
+    pub(crate) max_total_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DownloadCachePruneReport {
+    pub(crate) removed_entries: u64,
+    pub(crate) freed_bytes: u64,
+    pub(crate) remaining_entries: u64,
+    pub(crate) remaining_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DownloadCacheVerifyReport {
+    pub(crate) checked: u64,
+    pub(crate) removed: Vec<String>,
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    sha1: String,
+    size: u64,
+    last_access: SystemTime,
+}
+
+fn is_sha1_name(name: &str) -> bool {
+    name.len() == 40 && name.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Marca el uso de una entrada. `atime` suele estar desactivado, así que el LRU usa `mtime`.
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Copia o enlaza `source` en `target`. El enlace duro solo funciona en el mismo volumen;
+/// si falla se copia. Sólo se enlaza desde la caché hacia las instancias.
+fn link_or_copy(
+    source: &Path,
+    target: &Path,
+    prefer_link: bool,
+) -> Result<MaterializeMode, String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("No se pudo crear {}: {error}", parent.display()))?;
+    }
+    if prefer_link {
+        let _ = fs::remove_file(target);
+        if fs::hard_link(source, target).is_ok() {
+            return Ok(MaterializeMode::HardLink);
+        }
+    }
+    fs::copy(source, target).map_err(|error| {
+        format!(
+            "No se pudo copiar {} a {}: {error}",
+            source.display(),
+            target.display()
+        )
+    })?;
+    Ok(MaterializeMode::Copy)
+}
+
+/// Caché global de binarios direccionada por SHA-1 (`<root>/<aa>/<sha1>`).
+pub(crate) struct GlobalDownloadCache {
+    root: PathBuf,
+    hard_links: bool,
+}
+
+impl GlobalDownloadCache {
+    /// Con `hard_links` las instancias reciben enlaces duros a las entradas en vez de copias.
+    pub(crate) fn new(root: PathBuf, hard_links: bool) -> Self {
+        Self { root, hard_links }
+    }
+
+    pub(crate) fn entry_path(&self, sha1: &str) -> PathBuf {
+        let prefix = sha1.get(0..2).unwrap_or("00");
+        self.root.join(prefix).join(sha1)
+    }
+
+    /// Lleva la entrada `sha1` a `target` y actualiza su marca de uso. Devuelve `None` si la
+    /// entrada no existe o su contenido no coincide con el hash; en ese caso se borra.
+    pub(crate) fn materialize(
+        &self,
+        sha1: &str,
+        target: &Path,
+    ) -> Result<Option<MaterializeMode>, String> {
+        let entry = self.entry_path(sha1);
+        if !entry.is_file() {
+            return Ok(None);
+        }
+        if !file_sha1(&entry).is_ok_and(|actual| actual.eq_ignore_ascii_case(sha1)) {
+            let _ = fs::remove_file(&entry);
+            return Ok(None);
+        }
+        touch(&entry);
+        link_or_copy(&entry, target, self.hard_links).map(Some)
+    }
+
+    /// Guarda una copia de `source` como entrada `sha1` si aún no existe. Nunca se enlaza:
+    /// una escritura en el archivo de la instancia alteraría la entrada compartida.
+    pub(crate) fn store(&self, sha1: &str, source: &Path) -> Result<(), String> {
+        let entry = self.entry_path(sha1);
+        if entry.exists() {
+            touch(&entry);
+            return Ok(());
+        }
+        let staging = entry.with_extension("tmp");
+        link_or_copy(source, &staging, false)?;
+        fs::rename(&staging, &entry).map_err(|error| {
+            let _ = fs::remove_file(&staging);
+            format!(
+                "No se pudo guardar {} en la caché: {error}",
+                entry.display()
+            )
+        })
+    }
+
+    fn entries(&self) -> Vec<CacheEntry> {
+        let mut entries = Vec::new();
+        let Ok(prefixes) = fs::read_dir(&self.root) else {
+            return entries;
+        };
+        for prefix in prefixes.flatten() {
+            let Ok(files) = fs::read_dir(prefix.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let Ok(meta) = file.metadata() else {
+                    continue;
+                };
+                if !meta.is_file() {
+                    continue;
+                }
+                entries.push(CacheEntry {
+                    path: file.path(),
+                    sha1: file.file_name().to_string_lossy().to_string(),
+                    size: meta.len(),
+                    last_access: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+        entries
+    }
+
+    pub(crate) fn stats(&self) -> DownloadCacheStats {
+        let entries = self.entries();
+        DownloadCacheStats {
+            root: self.root.to_string_lossy().to_string(),
+            entries: entries.len() as u64,
+            total_bytes: entries.iter().map(|entry| entry.size).sum(),
+            hard_links_enabled: self.hard_links,
+        }
+    }
+
+    /// Borra entradas más viejas que `max_age_days` y, si aún se supera el presupuesto,
+    /// las menos usadas primero.
+    pub(crate) fn prune(&self, policy: &DownloadCachePrunePolicy) -> DownloadCachePruneReport {
+        let mut entries = self.entries();
+        entries.sort_by_key(|entry| entry.last_access);
+        let mut remaining_bytes = entries.iter().map(|entry| entry.size).sum::<u64>();
+        let cutoff = policy.max_age_days.and_then(|days| {
+            SystemTime::now().checked_sub(Duration::from_secs(days.saturating_mul(86_400)))
+        });
+        let budget = policy
+            .max_total_mb
+            .map(|megabytes| megabytes.saturating_mul(1024 * 1024));
+
+        let mut report = DownloadCachePruneReport::default();
+        for entry in &entries {
+            let expired = cutoff.is_some_and(|cutoff| entry.last_access < cutoff);
+            let over_budget = budget.is_some_and(|budget| remaining_bytes > budget);
+            if !(expired || over_budget) || fs::remove_file(&entry.path).is_err() {
+                continue;
+            }
+            remaining_bytes = remaining_bytes.saturating_sub(entry.size);
+            report.removed_entries += 1;
+            report.freed_bytes += entry.size;
+        }
+        report.remaining_entries = entries.len() as u64 - report.removed_entries;
+        report.remaining_bytes = remaining_bytes;
+        report
+    }
+
+    /// Recalcula el SHA-1 de cada entrada y elimina las que no coinciden con su nombre.
+    pub(crate) fn verify(&self) -> DownloadCacheVerifyReport {
+        let mut report = DownloadCacheVerifyReport::default();
+        for entry in self.entries() {
+            report.checked += 1;
+            let valid = is_sha1_name(&entry.sha1)
+                && file_sha1(&entry.path)
+                    .is_ok_and(|actual| actual.eq_ignore_ascii_case(&entry.sha1));
+            if !valid && fs::remove_file(&entry.path).is_ok() {
+                report.removed.push(entry.sha1);
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use sha1::{Digest, Sha1};
+
+    use super::{DownloadCachePrunePolicy, GlobalDownloadCache, MaterializeMode};
+
+    fn temp_root(label: &str) -> std::path::PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!("frutistudio-test-download-cache-{label}-{unique}"))
+    }
+
+    fn store_bytes(cache: &GlobalDownloadCache, root: &std::path::Path, bytes: &[u8]) -> String {
+        let sha1 = format!("{:x}", Sha1::digest(bytes));
+        let source = root.join("sources").join(&sha1);
+        fs::create_dir_all(source.parent().expect("parent")).expect("sources");
+        fs::write(&source, bytes).expect("source");
+        cache.store(&sha1, &source).expect("store");
+        sha1
+    }
+
+    #[test]
+    fn prunes_least_recently_used_entries_and_verifies_hashes() {
+        let root = temp_root("prune");
+        let cache = GlobalDownloadCache::new(root.join("sha1"), false);
+        let old = store_bytes(&cache, &root, &[1_u8; 600 * 1024]);
+        let recent = store_bytes(&cache, &root, &[2_u8; 600 * 1024]);
+        let old_file = fs::File::options()
+            .write(true)
+            .open(cache.entry_path(&old))
+            .expect("old entry");
+        old_file
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * 86_400))
+            .expect("mtime");
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.total_bytes, 1200 * 1024);
+
+        let report = cache.prune(&DownloadCachePrunePolicy {
+            max_age_days: None,
+            max_total_mb: Some(1),
+        });
+        assert_eq!(report.removed_entries, 1);
+        assert!(!cache.entry_path(&old).exists());
+        assert!(cache.entry_path(&recent).exists());
+
+        fs::write(cache.entry_path(&recent), b"tampered").expect("tamper");
+        let verify = cache.verify();
+        assert_eq!(verify.checked, 1);
+        assert_eq!(verify.removed, vec![recent]);
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn materializes_as_hard_link_when_enabled() {
+        let root = temp_root("link");
+        let copying = GlobalDownloadCache::new(root.join("sha1"), false);
+        let linking = GlobalDownloadCache::new(root.join("sha1"), true);
+        let sha1 = store_bytes(&linking, &root, b"library");
+        let target = root.join("instance").join("lib.jar");
+
+        // La entrada es una copia: modificar el origen no la altera.
+        fs::write(root.join("sources").join(&sha1), b"parcheado").expect("patch source");
+        assert_eq!(
+            copying.materialize(&sha1, &target).expect("copy"),
+            Some(MaterializeMode::Copy)
+        );
+        assert_eq!(fs::read(&target).expect("target"), b"library");
+        assert_eq!(
+            linking.materialize(&sha1, &target).expect("link"),
+            Some(MaterializeMode::HardLink)
+        );
+        assert_eq!(fs::read(&target).expect("target"), b"library");
+
+        fs::write(linking.entry_path(&sha1), b"tampered").expect("tamper");
+        let other = root.join("other").join("lib.jar");
+        assert_eq!(linking.materialize(&sha1, &other).expect("tampered"), None);
+        assert!(!linking.entry_path(&sha1).exists());
+        assert!(!other.exists());
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+}
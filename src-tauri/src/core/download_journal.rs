@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const DOWNLOAD_JOURNAL_FILE: &str = "download-journal.json";
+
+/// Cada cuánto se vuelcan a disco las marcas de completado; un cierre brusco pierde como
+/// mucho este intervalo, que luego se revalida por hash.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JournalEntry {
+    #[serde(default)]
+    pub(crate) sha1: Option<String>,
+    #[serde(default)]
+    pub(crate) size: Option<u64>,
+    #[serde(default)]
+    pub(crate) completed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalFile {
+    instance_id: String,
+    started_at: u64,
+    updated_at: u64,
+    entries: BTreeMap<String, JournalEntry>,
+}
+
+struct JournalState {
+    file: JournalFile,
+    dirty: bool,
+    last_flush: Instant,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn journal_key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Diario persistente del bootstrap de una instancia: qué archivos se planificaron, con qué
+/// hash y cuáles terminaron. Permite retomar tras un cierre sin volver a hashear lo ya bajado.
+pub(crate) struct DownloadJournal {
+    path: PathBuf,
+    state: Mutex<JournalState>,
+}
+
+impl DownloadJournal {
+    /// Abre el diario de la instancia o empieza uno nuevo si no existe o es de otra instancia.
+    pub(crate) fn open(instance_root: &Path, instance_id: &str) -> Self {
+        let path = instance_root.join(DOWNLOAD_JOURNAL_FILE);
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<JournalFile>(&raw).ok())
+            .filter(|file| file.instance_id == instance_id)
+            .unwrap_or_else(|| JournalFile {
+                instance_id: instance_id.to_string(),
+                started_at: now_secs(),
+                ..JournalFile::default()
+            });
+        Self {
+            path,
+            state: Mutex::new(JournalState {
+                file,
+                dirty: false,
+                last_flush: Instant::now(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, JournalState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// `(completados, pendientes)` heredados de una ejecución anterior.
+    pub(crate) fn counts(&self) -> (usize, usize) {
+        let state = self.lock();
+        let completed = state
+            .file
+            .entries
+            .values()
+            .filter(|entry| entry.completed)
+            .count();
+        (completed, state.file.entries.len() - completed)
+    }
+
+    /// Registra un archivo previsto. Si ya estaba con el mismo hash conserva su estado.
+    pub(crate) fn plan(&self, path: &Path, sha1: Option<&str>, size: Option<u64>) {
+        let mut state = self.lock();
+        let sha1 = sha1.map(str::to_ascii_lowercase);
+        let entry = state.file.entries.entry(journal_key(path)).or_default();
+        if entry.sha1 != sha1 || (size.is_some() && entry.size != size) {
+            *entry = JournalEntry {
+                sha1,
+                size,
+                completed: false,
+            };
+        }
+        state.dirty = true;
+    }
+
+    /// Completado en el diario y todavía presente en disco con el tamaño registrado.
+    pub(crate) fn is_completed(&self, path: &Path, sha1: Option<&str>) -> bool {
+        let state = self.lock();
+        let Some(entry) = state.file.entries.get(&journal_key(path)) else {
+            return false;
+        };
+        let hash_matches = match (sha1, entry.sha1.as_deref()) {
+            (Some(expected), Some(recorded)) => expected.eq_ignore_ascii_case(recorded),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        entry.completed
+            && hash_matches
+            && fs::metadata(path).is_ok_and(|meta| {
+                meta.is_file() && entry.size.is_none_or(|size| size == meta.len())
+            })
+    }
+
+    pub(crate) fn mark_completed(&self, path: &Path) {
+        let size = fs::metadata(path).ok().map(|meta| meta.len());
+        let mut state = self.lock();
+        let entry = state.file.entries.entry(journal_key(path)).or_default();
+        entry.completed = true;
+        entry.size = entry.size.or(size);
+        state.dirty = true;
+        if state.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.write(&mut state);
+        }
+    }
+
+    /// Un `.part` solo se conserva si pertenece a un archivo planificado y sin terminar;
+    /// el resto se considera abandonado.
+    pub(crate) fn keeps_partial(&self, partial: &Path) -> bool {
+        let Some(target) = partial
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".part"))
+            .map(|name| partial.with_file_name(name))
+        else {
+            return false;
+        };
+        self.lock()
+            .file
+            .entries
+            .get(&journal_key(&target))
+            .is_some_and(|entry| !entry.completed)
+    }
+
+    fn write(&self, state: &mut JournalState) {
+        state.file.updated_at = now_secs();
+        let Ok(raw) = serde_json::to_string(&state.file) else {
+            return;
+        };
+        let temp = self.path.with_extension("json.tmp");
+        if fs::write(&temp, raw).is_ok() && fs::rename(&temp, &self.path).is_ok() {
+            state.dirty = false;
+            state.last_flush = Instant::now();
+        }
+    }
+
+    pub(crate) fn flush(&self) {
+        let mut state = self.lock();
+        if state.dirty {
+            self.write(&mut state);
+        }
+    }
+
+    /// El bootstrap terminó: el diario ya no hace falta.
+    pub(crate) fn finish(&self) {
+        let mut state = self.lock();
+        state.file.entries.clear();
+        state.dirty = false;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{DownloadJournal, DOWNLOAD_JOURNAL_FILE};
+
+    fn temp_root(label: &str) -> std::path::PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!(
+            "frutistudio-test-download-journal-{label}-{unique}"
+        ));
+        fs::create_dir_all(&root).expect("root");
+        root
+    }
+
+    #[test]
+    fn resumes_completed_items_and_keeps_only_pending_partials() {
+        let root = temp_root("resume");
+        let done = root.join("objects").join("aa").join("aa11");
+        let pending = root.join("objects").join("bb").join("bb22");
+        fs::create_dir_all(done.parent().expect("parent")).expect("dirs");
+        fs::write(&done, b"asset").expect("done");
+
+        let journal = DownloadJournal::open(&root, "instance-1");
+        journal.plan(&done, Some("AA11"), Some(5));
+        journal.plan(&pending, Some("bb22"), Some(9));
+        journal.mark_completed(&done);
+        journal.flush();
+        drop(journal);
+
+        let resumed = DownloadJournal::open(&root, "instance-1");
+        assert_eq!(resumed.counts(), (1, 1));
+        assert!(resumed.is_completed(&done, Some("aa11")));
+        assert!(!resumed.is_completed(&done, Some("ffff")));
+        assert!(!resumed.is_completed(&pending, Some("bb22")));
+        assert!(resumed.keeps_partial(&pending.with_file_name("bb22.part")));
+        assert!(!resumed.keeps_partial(&done.with_file_name("aa11.part")));
+        assert!(!resumed.keeps_partial(&root.join("orphan.jar.part")));
+
+        assert_eq!(DownloadJournal::open(&root, "instance-2").counts(), (0, 0));
+
+        resumed.finish();
+        assert!(!root.join(DOWNLOAD_JOURNAL_FILE).exists());
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn replanning_with_a_new_hash_or_a_truncated_file_invalidates_completion() {
+        let root = temp_root("replan");
+        let library = root.join("lib.jar");
+        fs::write(&library, b"library-v1").expect("library");
+
+        let journal = DownloadJournal::open(&root, "instance-1");
+        journal.plan(&library, Some("1111"), None);
+        journal.mark_completed(&library);
+        assert!(journal.is_completed(&library, Some("1111")));
+
+        fs::write(&library, b"trunc").expect("truncate");
+        assert!(!journal.is_completed(&library, Some("1111")));
+
+        journal.plan(&library, Some("2222"), None);
+        assert!(!journal.is_completed(&library, Some("2222")));
+        assert_eq!(journal.counts(), (0, 1));
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+}
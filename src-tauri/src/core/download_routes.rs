@@ -1,4 +1,14 @@
 use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use reqwest::Url;
+
+use crate::core::config::{EndpointOverride, MirrorConfig};
+
+static ENDPOINT_OVERRIDES: Lazy<RwLock<Vec<EndpointOverride>>> =
+    Lazy::new(|| RwLock::new(Vec::new()));
 
 #[derive(Debug, Clone)]
 pub(crate) struct LoaderCompatibilityRoute {
@@ -11,8 +21,93 @@ pub(crate) struct LoaderCompatibilityRoute {
 pub(crate) const MINECRAFT_MANIFEST_URLS: [&str; 1] =
     ["https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"];
 
+/// Sustituye las reglas de reescritura de endpoints configuradas en `AppConfig`.
+pub(crate) fn configure_endpoint_overrides(config: Option<&MirrorConfig>) {
+    let rules = config
+        .map(|config| {
+            config
+                .overrides
+                .iter()
+                .filter_map(|rule| {
+                    let prefix = rule.prefix.trim().trim_end_matches('/');
+                    let target = rule.target.trim().trim_end_matches('/');
+                    (!prefix.is_empty() && !target.is_empty()).then(|| EndpointOverride {
+                        prefix: prefix.to_string(),
+                        target: target.to_string(),
+                        exclusive: rule.exclusive,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    *ENDPOINT_OVERRIDES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = rules;
+}
+
+/// Aplica las reglas globales: las URLs reescritas van primero y las originales quedan
+/// como respaldo salvo que la regla sea exclusiva.
+pub(crate) fn apply_endpoint_overrides(urls: Vec<String>) -> Vec<String> {
+    let rules = ENDPOINT_OVERRIDES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if rules.is_empty() {
+        return urls;
+    }
+    rewrite_with_overrides(&rules, urls)
+}
+
+fn rewrite_with_overrides(rules: &[EndpointOverride], urls: Vec<String>) -> Vec<String> {
+    let mut rewritten = Vec::new();
+    let mut upstream = Vec::new();
+    for url in urls {
+        let mut exclusive = false;
+        for rule in rules {
+            let Some(rest) = url.strip_prefix(rule.prefix.as_str()) else {
+                continue;
+            };
+            if !(rest.is_empty() || rest.starts_with('/') || rest.starts_with('?')) {
+                continue;
+            }
+            rewritten.push(format!("{}{rest}", rule.target));
+            exclusive |= rule.exclusive;
+        }
+        if !exclusive {
+            upstream.push(url);
+        }
+    }
+    rewritten.extend(upstream);
+    dedupe(rewritten)
+}
+
+/// Ruta local de una URL `file://` de un mirror offline.
+pub(crate) fn local_mirror_path(url: &str) -> Option<PathBuf> {
+    if !url.starts_with("file:") {
+        return None;
+    }
+    Url::parse(url).ok()?.to_file_path().ok()
+}
+
+/// Lee una URL `file://`; `None` si la URL no es local.
+pub(crate) fn read_local_mirror(url: &str) -> Option<Result<Vec<u8>, String>> {
+    let path = local_mirror_path(url)?;
+    Some(
+        std::fs::read(&path)
+            .map_err(|error| format!("No se pudo leer mirror local {}: {error}", path.display())),
+    )
+}
+
+pub(crate) fn minecraft_manifest_urls() -> Vec<String> {
+    apply_endpoint_overrides(
+        MINECRAFT_MANIFEST_URLS
+            .iter()
+            .map(|url| (*url).to_string())
+            .collect(),
+    )
+}
+
 pub(crate) fn version_metadata_urls(primary_url: &str) -> Vec<String> {
-    dedupe(vec![primary_url.to_string()])
+    apply_endpoint_overrides(dedupe(vec![primary_url.to_string()]))
 }
 
 pub(crate) fn asset_index_urls(primary_url: &str) -> Vec<String> {
@@ -25,50 +120,50 @@ pub(crate) fn fabric_like_profile_urls(
     loader_version: &str,
 ) -> Vec<String> {
     if loader == "quilt" {
-        return vec![
+        return apply_endpoint_overrides(vec![
             format!(
                 "https://meta.quiltmc.org/v3/versions/loader/{minecraft_version}/{loader_version}/profile/json"
             ),
             format!(
                 "https://meta.quiltmc.org/v3/versions/loader/{minecraft_version}/latest/profile/json"
             ),
-        ];
+        ]);
     }
 
-    vec![
+    apply_endpoint_overrides(vec![
         format!(
             "https://meta.fabricmc.net/v2/versions/loader/{minecraft_version}/{loader_version}/profile/json"
         ),
         format!(
             "https://meta.fabricmc.net/v2/versions/loader/{minecraft_version}/stable/profile/json"
         ),
-    ]
+    ])
 }
 
 pub(crate) fn forge_promotions_urls() -> Vec<String> {
-    vec![
+    apply_endpoint_overrides(vec![
         "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json"
             .to_string(),
         "https://maven.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json"
             .to_string(),
-    ]
+    ])
 }
 
 pub(crate) fn forge_like_metadata_urls(loader: &str) -> Vec<String> {
     if loader == "neoforge" {
-        return vec![
+        return apply_endpoint_overrides(vec![
             "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge"
                 .to_string(),
             "https://maven.neoforged.net/releases/net/neoforged/neoforge/maven-metadata.xml"
                 .to_string(),
-        ];
+        ]);
     }
 
-    vec![
+    apply_endpoint_overrides(vec![
         "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml".to_string(),
         "https://files.minecraftforge.net/maven/net/minecraftforge/forge/maven-metadata.xml"
             .to_string(),
-    ]
+    ])
 }
 
 pub(crate) fn forge_like_installer_urls(loader: &str, resolved_version: &str) -> Vec<String> {
@@ -78,7 +173,7 @@ pub(crate) fn forge_like_installer_urls(loader: &str, resolved_version: &str) ->
         urls.push(format!(
             "https://maven.neoforged.net/releases/net/neoforged/neoforge/{resolved_version}/neoforge-{resolved_version}-installer.jar"
         ));
-        return urls;
+        return apply_endpoint_overrides(urls);
     }
 
     urls.push(format!(
@@ -87,7 +182,7 @@ pub(crate) fn forge_like_installer_urls(loader: &str, resolved_version: &str) ->
     urls.push(format!(
         "https://files.minecraftforge.net/maven/net/minecraftforge/forge/{resolved_version}/forge-{resolved_version}-installer.jar"
     ));
-    dedupe(urls)
+    apply_endpoint_overrides(dedupe(urls))
 }
 
 pub(crate) fn mirror_candidates_for_url(url: &str) -> Vec<String> {
@@ -217,12 +312,13 @@ pub(crate) fn mirror_candidates_for_url(url: &str) -> Vec<String> {
         }
     }
 
-    dedupe(urls)
+    apply_endpoint_overrides(dedupe(urls))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::mirror_candidates_for_url;
+    use super::{local_mirror_path, mirror_candidates_for_url, rewrite_with_overrides};
+    use crate::core::config::EndpointOverride;
 
     #[test]
     fn neoforged_library_url_prioritizes_neoforge_maven() {
@@ -345,6 +441,52 @@ mod tests {
                 == "https://repo.maven.apache.org/maven2/org/jetbrains/kotlin/kotlin-stdlib-common/2.1.0/kotlin-stdlib-common-2.1.0.jar"
         }));
     }
+
+    #[test]
+    fn endpoint_overrides_prepend_mirror_and_drop_upstream_when_exclusive() {
+        let rules = vec![
+            EndpointOverride {
+                prefix: "https://libraries.minecraft.net".to_string(),
+                target: "http://lan-mirror:8080/libraries".to_string(),
+                exclusive: false,
+            },
+            EndpointOverride {
+                prefix: "https://resources.download.minecraft.net".to_string(),
+                target: "file:///srv/mirror/assets".to_string(),
+                exclusive: true,
+            },
+        ];
+
+        let urls = rewrite_with_overrides(
+            &rules,
+            vec![
+                "https://repo1.maven.org/maven2/a/b/1/b-1.jar".to_string(),
+                "https://libraries.minecraft.net/a/b/1/b-1.jar".to_string(),
+                "https://libraries.minecraft.net.evil.example/a/b/1/b-1.jar".to_string(),
+            ],
+        );
+        assert_eq!(
+            urls,
+            vec![
+                "http://lan-mirror:8080/libraries/a/b/1/b-1.jar",
+                "https://repo1.maven.org/maven2/a/b/1/b-1.jar",
+                "https://libraries.minecraft.net/a/b/1/b-1.jar",
+                "https://libraries.minecraft.net.evil.example/a/b/1/b-1.jar",
+            ]
+        );
+
+        let assets = rewrite_with_overrides(
+            &rules,
+            vec!["https://resources.download.minecraft.net/ab/ab12".to_string()],
+        );
+        assert_eq!(assets, vec!["file:///srv/mirror/assets/ab/ab12"]);
+        #[cfg(unix)]
+        assert_eq!(
+            local_mirror_path(&assets[0]),
+            Some(std::path::PathBuf::from("/srv/mirror/assets/ab/ab12"))
+        );
+        assert_eq!(local_mirror_path("https://example.com/file"), None);
+    }
 }
 
 fn dedupe(urls: Vec<String>) -> Vec<String> {
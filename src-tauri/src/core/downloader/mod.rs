@@ -1,8 +1,25 @@
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::future::{AbortHandle, Abortable};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
 
 pub const MINECRAFT_LIBRARIES_BASE: &str = "https://libraries.minecraft.net";
 pub const MINECRAFT_ASSETS_BASE: &str = "https://resources.download.minecraft.net";
 
+static GLOBAL_DOWNLOADER: Lazy<Downloader> =
+    Lazy::new(|| Downloader::new(DownloadConfig::default()));
+
+tokio::task_local! {
+    /// Grupo del trabajo en ejecución, para que `wait_while_paused` sepa qué pausa aplicar.
+    static CURRENT_GROUP: Option<String>;
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadItem {
@@ -18,6 +35,481 @@ pub struct DownloadConfig {
     pub max_retries: u8,
 }
 
-pub trait Downloader {
-    fn download(&self, item: &DownloadItem) -> Result<(), String>;
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism()
+            .map(|value| value.get())
+            .unwrap_or(4);
+        Self {
+            max_parallel_downloads: workers.saturating_mul(16).clamp(48, 128),
+            max_retries: 3,
+        }
+    }
+}
+
+/// Orden de atención de la cola: primero el client jar, los assets al final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadPriority {
+    Critical,
+    Library,
+    Asset,
+    Background,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadJobState {
+    Queued,
+    Paused,
+    Running,
+}
+
+/// Descripción de un trabajo al encolarlo. `group` suele ser el id de la instancia.
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    pub item: DownloadItem,
+    pub label: String,
+    pub group: Option<String>,
+    pub priority: DownloadPriority,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobSnapshot {
+    pub id: u64,
+    pub label: String,
+    pub group: Option<String>,
+    pub priority: DownloadPriority,
+    pub state: DownloadJobState,
+    pub item: DownloadItem,
+    pub queued_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQueueSnapshot {
+    pub max_parallel_downloads: usize,
+    pub running: usize,
+    pub paused_all: bool,
+    pub paused_groups: Vec<String>,
+    pub jobs: Vec<DownloadJobSnapshot>,
+}
+
+struct JobEntry {
+    request: DownloadRequest,
+    abort: AbortHandle,
+    running: bool,
+    queued_at: u64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: BTreeMap<u64, JobEntry>,
+    waiting: BTreeMap<(DownloadPriority, u64), oneshot::Sender<()>>,
+    running: usize,
+    paused_all: bool,
+    paused_groups: HashSet<String>,
+}
+
+impl QueueState {
+    fn is_paused(&self, group: Option<&str>) -> bool {
+        self.paused_all || group.is_some_and(|group| self.paused_groups.contains(group))
+    }
+}
+
+/// Cola de descargas del proceso: límite global de concurrencia, prioridades,
+/// cancelación por trabajo y pausa por grupo.
+pub struct Downloader {
+    config: DownloadConfig,
+    next_id: AtomicU64,
+    state: Mutex<QueueState>,
+    resumed: Notify,
+}
+
+impl Downloader {
+    pub fn new(config: DownloadConfig) -> Self {
+        Self {
+            config,
+            next_id: AtomicU64::new(1),
+            state: Mutex::new(QueueState::default()),
+            resumed: Notify::new(),
+        }
+    }
+
+    pub fn global() -> &'static Downloader {
+        &GLOBAL_DOWNLOADER
+    }
+
+    pub fn config(&self) -> &DownloadConfig {
+        &self.config
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Encola `job` y lo ejecuta cuando haya cupo. Si se cancela devuelve error.
+    pub async fn run<T, Fut>(&self, request: DownloadRequest, job: Fut) -> Result<T, String>
+    where
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let label = request.label.clone();
+        let group = request.group.clone();
+        let cancelled_message = format!("Descarga cancelada: {label}");
+        let (abort, registration) = AbortHandle::new_pair();
+        let (slot_tx, slot_rx) = oneshot::channel();
+        {
+            let mut state = self.lock();
+            state.waiting.insert((request.priority, id), slot_tx);
+            state.jobs.insert(
+                id,
+                JobEntry {
+                    request,
+                    abort,
+                    running: false,
+                    queued_at: now_secs(),
+                },
+            );
+            self.dispatch(&mut state);
+        }
+        let _slot = JobSlot {
+            downloader: self,
+            id,
+        };
+
+        let outcome = Abortable::new(
+            async move {
+                slot_rx
+                    .await
+                    .map_err(|_| format!("La cola de descargas descartó {label}"))?;
+                CURRENT_GROUP.scope(group, job).await
+            },
+            registration,
+        )
+        .await;
+        outcome.unwrap_or(Err(cancelled_message))
+    }
+
+    fn dispatch(&self, state: &mut QueueState) {
+        while state.running < self.config.max_parallel_downloads.max(1) && !state.paused_all {
+            let next = state.waiting.keys().copied().find(|(_, id)| {
+                state
+                    .jobs
+                    .get(id)
+                    .is_some_and(|job| !state.is_paused(job.request.group.as_deref()))
+            });
+            let Some(key) = next else {
+                break;
+            };
+            let Some(sender) = state.waiting.remove(&key) else {
+                break;
+            };
+            if sender.send(()).is_ok() {
+                if let Some(job) = state.jobs.get_mut(&key.1) {
+                    job.running = true;
+                    state.running += 1;
+                }
+            }
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let mut state = self.lock();
+        if let Some(job) = state.jobs.remove(&id) {
+            state.waiting.remove(&(job.request.priority, id));
+            if job.running {
+                state.running = state.running.saturating_sub(1);
+            }
+        }
+        self.dispatch(&mut state);
+    }
+
+    pub fn cancel(&self, id: u64) -> bool {
+        let state = self.lock();
+        match state.jobs.get(&id) {
+            Some(job) => {
+                job.abort.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancela todos los trabajos del grupo y devuelve cuántos había.
+    pub fn cancel_group(&self, group: &str) -> usize {
+        let state = self.lock();
+        let mut cancelled = 0;
+        for job in state.jobs.values() {
+            if job.request.group.as_deref() == Some(group) {
+                job.abort.abort();
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    /// Pausa o reanuda un grupo (o toda la cola con `None`). Las descargas en curso se
+    /// detienen en su siguiente `wait_while_paused`.
+    pub fn set_paused(&self, group: Option<&str>, paused: bool) {
+        let mut state = self.lock();
+        match (group, paused) {
+            (None, paused) => state.paused_all = paused,
+            (Some(group), true) => {
+                state.paused_groups.insert(group.to_string());
+            }
+            (Some(group), false) => {
+                state.paused_groups.remove(group);
+            }
+        }
+        self.dispatch(&mut state);
+        drop(state);
+        if !paused {
+            self.resumed.notify_waiters();
+        }
+    }
+
+    /// Punto de pausa de un trabajo en curso: espera mientras su grupo (o toda la cola)
+    /// esté en pausa. Fuera de `run` no hace nada.
+    pub async fn wait_while_paused(&self) {
+        let Ok(group) = CURRENT_GROUP.try_with(Clone::clone) else {
+            return;
+        };
+        loop {
+            let resumed = self.resumed.notified();
+            if !self.lock().is_paused(group.as_deref()) {
+                return;
+            }
+            resumed.await;
+        }
+    }
+
+    pub fn snapshot(&self) -> DownloadQueueSnapshot {
+        let state = self.lock();
+        let mut jobs = state
+            .jobs
+            .iter()
+            .map(|(id, job)| DownloadJobSnapshot {
+                id: *id,
+                label: job.request.label.clone(),
+                group: job.request.group.clone(),
+                priority: job.request.priority,
+                state: if state.is_paused(job.request.group.as_deref()) {
+                    DownloadJobState::Paused
+                } else if job.running {
+                    DownloadJobState::Running
+                } else {
+                    DownloadJobState::Queued
+                },
+                item: job.request.item.clone(),
+                queued_at: job.queued_at,
+            })
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|job| (job.state != DownloadJobState::Running, job.priority, job.id));
+        let mut paused_groups = state.paused_groups.iter().cloned().collect::<Vec<_>>();
+        paused_groups.sort();
+        DownloadQueueSnapshot {
+            max_parallel_downloads: self.config.max_parallel_downloads,
+            running: state.running,
+            paused_all: state.paused_all,
+            paused_groups,
+            jobs,
+        }
+    }
+}
+
+/// Libera el cupo y quita el trabajo de la cola al terminar, fallar o cancelarse.
+struct JobSlot<'a> {
+    downloader: &'a Downloader,
+    id: u64,
+}
+
+impl Drop for JobSlot<'_> {
+    fn drop(&mut self) {
+        self.downloader.finish(self.id);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::oneshot;
+
+    use super::{DownloadConfig, DownloadItem, DownloadPriority, DownloadRequest, Downloader};
+
+    fn request(label: &str, group: &str, priority: DownloadPriority) -> DownloadRequest {
+        DownloadRequest {
+            item: DownloadItem {
+                url: format!("https://example.invalid/{label}"),
+                destination: format!("/tmp/{label}"),
+                sha1: None,
+            },
+            label: label.to_string(),
+            group: Some(group.to_string()),
+            priority,
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime")
+    }
+
+    async fn wait_for_jobs(downloader: &Downloader, expected: usize) {
+        for _ in 0..100 {
+            if downloader.snapshot().jobs.len() == expected {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("la cola no llegó a {expected} trabajos");
+    }
+
+    fn single_slot() -> Arc<Downloader> {
+        Arc::new(Downloader::new(DownloadConfig {
+            max_parallel_downloads: 1,
+            max_retries: 1,
+        }))
+    }
+
+    #[test]
+    fn runs_critical_jobs_before_queued_assets() {
+        runtime().block_on(async {
+            let downloader = single_slot();
+            let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let (release_tx, release_rx) = oneshot::channel::<()>();
+
+            let blocker = {
+                let downloader = downloader.clone();
+                tokio::spawn(async move {
+                    downloader
+                        .run(request("blocker", "a", DownloadPriority::Asset), async {
+                            release_rx.await.map_err(|error| error.to_string())
+                        })
+                        .await
+                })
+            };
+            wait_for_jobs(&downloader, 1).await;
+
+            let mut handles = Vec::new();
+            for (label, priority) in [
+                ("asset", DownloadPriority::Asset),
+                ("client", DownloadPriority::Critical),
+            ] {
+                let downloader = downloader.clone();
+                let order = order.clone();
+                handles.push(tokio::spawn(async move {
+                    downloader
+                        .run(request(label, "a", priority), async move {
+                            order.lock().expect("order").push(label);
+                            Ok(())
+                        })
+                        .await
+                }));
+            }
+
+            wait_for_jobs(&downloader, 3).await;
+            release_tx.send(()).expect("release");
+            blocker.await.expect("join").expect("blocker");
+            for handle in handles {
+                handle.await.expect("join").expect("job");
+            }
+            assert_eq!(*order.lock().expect("order"), vec!["client", "asset"]);
+            assert!(downloader.snapshot().jobs.is_empty());
+        });
+    }
+
+    #[test]
+    fn paused_groups_wait_and_cancelled_jobs_fail() {
+        runtime().block_on(async {
+            let downloader = single_slot();
+            downloader.set_paused(Some("instance-a"), true);
+
+            let paused = {
+                let downloader = downloader.clone();
+                tokio::spawn(async move {
+                    downloader
+                        .run(
+                            request("lib", "instance-a", DownloadPriority::Library),
+                            async { Ok(()) },
+                        )
+                        .await
+                })
+            };
+            wait_for_jobs(&downloader, 1).await;
+            let snapshot = downloader.snapshot();
+            assert_eq!(snapshot.running, 0);
+            assert_eq!(snapshot.jobs[0].state, super::DownloadJobState::Paused);
+
+            let other = downloader
+                .run(
+                    request("other", "instance-b", DownloadPriority::Asset),
+                    async { Ok(7) },
+                )
+                .await;
+            assert_eq!(other, Ok(7));
+
+            assert!(downloader.cancel(snapshot.jobs[0].id));
+            let cancelled = paused.await.expect("join");
+            assert!(cancelled.expect_err("cancelled").contains("cancelada"));
+            assert!(downloader.snapshot().jobs.is_empty());
+        });
+    }
+
+    #[test]
+    fn running_jobs_stop_at_pause_points_until_resumed() {
+        runtime().block_on(async {
+            let downloader = single_slot();
+            let chunks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let (paused_tx, paused_rx) = oneshot::channel::<()>();
+
+            let running = {
+                let downloader = downloader.clone();
+                let chunks = chunks.clone();
+                tokio::spawn(async move {
+                    let job = async {
+                        downloader.wait_while_paused().await;
+                        chunks.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        downloader.set_paused(Some("instance-a"), true);
+                        let _ = paused_tx.send(());
+                        downloader.wait_while_paused().await;
+                        chunks.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        Ok(())
+                    };
+                    downloader
+                        .run(
+                            request("jar", "instance-a", DownloadPriority::Critical),
+                            job,
+                        )
+                        .await
+                })
+            };
+
+            paused_rx.await.expect("paused");
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(chunks.load(std::sync::atomic::Ordering::SeqCst), 1);
+            assert_eq!(
+                downloader.snapshot().jobs[0].state,
+                super::DownloadJobState::Paused
+            );
+
+            downloader.set_paused(Some("instance-a"), false);
+            running.await.expect("join").expect("job");
+            assert_eq!(chunks.load(std::sync::atomic::Ordering::SeqCst), 2);
+        });
+    }
 }
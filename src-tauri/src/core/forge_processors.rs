@@ -0,0 +1,525 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use zip::ZipArchive;
+
+use crate::core::maven_loader::MavenCoordinate;
+
+const PROCESSOR_SIDE: &str = "client";
+
+static DATA_TOKEN_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{([A-Za-z0-9_]+)\}").expect("processor token regex"));
+
+/// Entrada de `processors` en el `install_profile.json` de Forge/NeoForge.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ForgeProcessor {
+    pub(crate) jar: String,
+    #[serde(default)]
+    pub(crate) classpath: Vec<String>,
+    #[serde(default)]
+    pub(crate) args: Vec<String>,
+    #[serde(default)]
+    pub(crate) outputs: HashMap<String, String>,
+    #[serde(default)]
+    pub(crate) sides: Option<Vec<String>>,
+}
+
+impl ForgeProcessor {
+    fn applies_to_client(&self) -> bool {
+        self.sides
+            .as_ref()
+            .is_none_or(|sides| sides.iter().any(|side| side == PROCESSOR_SIDE))
+    }
+}
+
+/// Rutas que el instalador oficial expone como tokens (`{MINECRAFT_JAR}`, `{ROOT}`...).
+#[derive(Debug, Clone)]
+pub(crate) struct ProcessorEnvironment {
+    pub(crate) libraries_dir: PathBuf,
+    pub(crate) minecraft_root: PathBuf,
+    pub(crate) minecraft_jar: PathBuf,
+    pub(crate) minecraft_version: String,
+    pub(crate) installer_path: PathBuf,
+    /// Carpeta donde se extraen las entradas `/data/...` del instalador.
+    pub(crate) work_dir: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProcessorRunReport {
+    pub(crate) executed: Vec<String>,
+    pub(crate) skipped: Vec<String>,
+}
+
+pub(crate) fn client_processors(install_profile: &Value) -> Vec<ForgeProcessor> {
+    install_profile
+        .get("processors")
+        .cloned()
+        .and_then(|value| serde_json::from_value::<Vec<ForgeProcessor>>(value).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(ForgeProcessor::applies_to_client)
+        .collect()
+}
+
+/// Coordenadas maven (jar + classpath) que deben existir antes de ejecutar los processors.
+pub(crate) fn processor_library_coordinates(processors: &[ForgeProcessor]) -> Vec<String> {
+    let mut coordinates = Vec::new();
+    for processor in processors {
+        for coordinate in std::iter::once(&processor.jar).chain(processor.classpath.iter()) {
+            if !coordinates.contains(coordinate) {
+                coordinates.push(coordinate.clone());
+            }
+        }
+    }
+    coordinates
+}
+
+pub(crate) fn library_path(libraries_dir: &Path, coordinate: &str) -> Option<PathBuf> {
+    MavenCoordinate::parse(coordinate).map(|parsed| libraries_dir.join(parsed.to_rel_path()))
+}
+
+fn extract_installer_entry(
+    installer_path: &Path,
+    entry_name: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let file = fs::File::open(installer_path).map_err(|error| {
+        format!(
+            "No se pudo abrir instalador {}: {error}",
+            installer_path.display()
+        )
+    })?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|error| format!("Instalador inválido {}: {error}", installer_path.display()))?;
+    let mut entry = archive
+        .by_name(entry_name)
+        .map_err(|_| format!("El instalador no contiene {entry_name}"))?;
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("No se pudo leer {entry_name} del instalador: {error}"))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("No se pudo crear {}: {error}", parent.display()))?;
+    }
+    fs::write(target, bytes).map_err(|error| format!("No se pudo extraer {entry_name}: {error}"))
+}
+
+/// Destino dentro de `work_dir` de una entrada del instalador; `None` si la ruta intenta
+/// salir del directorio (`..`, rutas absolutas o con unidad).
+fn installer_entry_target(work_dir: &Path, entry_name: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for segment in entry_name.split(['/', '\\']) {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            _ if segment.contains(':') => return None,
+            _ => relative.push(segment),
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(work_dir.join(relative))
+}
+
+/// Valores de `data` para el lado cliente más los tokens integrados del instalador.
+pub(crate) fn resolve_processor_data(
+    install_profile: &Value,
+    env: &ProcessorEnvironment,
+) -> Result<HashMap<String, String>, String> {
+    let mut data = HashMap::from([
+        ("SIDE".to_string(), PROCESSOR_SIDE.to_string()),
+        (
+            "MINECRAFT_JAR".to_string(),
+            env.minecraft_jar.to_string_lossy().to_string(),
+        ),
+        (
+            "MINECRAFT_VERSION".to_string(),
+            env.minecraft_version.clone(),
+        ),
+        (
+            "ROOT".to_string(),
+            env.minecraft_root.to_string_lossy().to_string(),
+        ),
+        (
+            "INSTALLER".to_string(),
+            env.installer_path.to_string_lossy().to_string(),
+        ),
+        (
+            "LIBRARY_DIR".to_string(),
+            env.libraries_dir.to_string_lossy().to_string(),
+        ),
+    ]);
+
+    let Some(entries) = install_profile.get("data").and_then(Value::as_object) else {
+        return Ok(data);
+    };
+    for (key, sides) in entries {
+        let Some(raw) = sides.get(PROCESSOR_SIDE).and_then(Value::as_str) else {
+            continue;
+        };
+        let value = if let Some(coordinate) = artifact_reference(raw) {
+            library_path(&env.libraries_dir, coordinate)
+                .ok_or_else(|| format!("Coordenada inválida en data.{key}: {raw}"))?
+                .to_string_lossy()
+                .to_string()
+        } else if let Some(literal) = literal_value(raw) {
+            literal.to_string()
+        } else if let Some(entry_name) = raw.strip_prefix('/') {
+            let target = installer_entry_target(&env.work_dir, entry_name)
+                .ok_or_else(|| format!("Ruta inválida en data.{key}: {raw}"))?;
+            extract_installer_entry(&env.installer_path, entry_name, &target)?;
+            target.to_string_lossy().to_string()
+        } else {
+            raw.to_string()
+        };
+        data.insert(key.clone(), value);
+    }
+    Ok(data)
+}
+
+fn artifact_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+}
+
+fn literal_value(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+}
+
+/// Expande un argumento u output: `[coords]`, `'literal'` o texto con `{TOKEN}`.
+pub(crate) fn resolve_processor_token(
+    value: &str,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> Result<String, String> {
+    if let Some(coordinate) = artifact_reference(value) {
+        return library_path(libraries_dir, coordinate)
+            .map(|path| path.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Coordenada inválida en processor: {value}"));
+    }
+    if let Some(literal) = literal_value(value) {
+        return Ok(literal.to_string());
+    }
+
+    let mut missing = None;
+    let expanded = DATA_TOKEN_PATTERN.replace_all(value, |caps: &regex::Captures<'_>| {
+        match data.get(&caps[1]) {
+            Some(resolved) => resolved.clone(),
+            None => {
+                missing = Some(caps[1].to_string());
+                caps[0].to_string()
+            }
+        }
+    });
+    match missing {
+        Some(token) => Err(format!(
+            "El processor usa el token {{{token}}} sin valor en data"
+        )),
+        None => Ok(expanded.into_owned()),
+    }
+}
+
+fn file_sha1(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    Some(format!("{:x}", Sha1::digest(bytes)))
+}
+
+fn resolved_outputs(
+    processor: &ForgeProcessor,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    processor
+        .outputs
+        .iter()
+        .map(|(path, sha1)| {
+            Ok((
+                PathBuf::from(resolve_processor_token(path, data, libraries_dir)?),
+                resolve_processor_token(sha1, data, libraries_dir)?,
+            ))
+        })
+        .collect()
+}
+
+/// Un processor sin `outputs` declarados siempre se ejecuta.
+fn outputs_match(outputs: &[(PathBuf, String)]) -> bool {
+    !outputs.is_empty()
+        && outputs.iter().all(|(path, expected)| {
+            file_sha1(path).is_some_and(|actual| actual.eq_ignore_ascii_case(expected))
+        })
+}
+
+pub(crate) fn jar_main_class(jar_path: &Path) -> Result<String, String> {
+    let file = fs::File::open(jar_path)
+        .map_err(|error| format!("No se pudo abrir {}: {error}", jar_path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|error| format!("Jar inválido {}: {error}", jar_path.display()))?;
+    let mut manifest = String::new();
+    archive
+        .by_name("META-INF/MANIFEST.MF")
+        .map_err(|_| format!("{} no tiene MANIFEST.MF", jar_path.display()))?
+        .read_to_string(&mut manifest)
+        .map_err(|error| format!("No se pudo leer MANIFEST.MF: {error}"))?;
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("{} no declara Main-Class", jar_path.display()))
+}
+
+fn processor_invocation(
+    processor: &ForgeProcessor,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> Result<Vec<String>, String> {
+    let jar_path = library_path(libraries_dir, &processor.jar)
+        .ok_or_else(|| format!("Coordenada de processor inválida: {}", processor.jar))?;
+    let main_class = jar_main_class(&jar_path)?;
+
+    let mut classpath = vec![jar_path];
+    for coordinate in &processor.classpath {
+        classpath.push(
+            library_path(libraries_dir, coordinate)
+                .ok_or_else(|| format!("Coordenada de classpath inválida: {coordinate}"))?,
+        );
+    }
+    let separator = if cfg!(target_os = "windows") {
+        ";"
+    } else {
+        ":"
+    };
+
+    let mut invocation = vec![
+        "-cp".to_string(),
+        classpath
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(separator),
+        main_class,
+    ];
+    for arg in &processor.args {
+        invocation.push(resolve_processor_token(arg, data, libraries_dir)?);
+    }
+    Ok(invocation)
+}
+
+fn output_tail(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let lines = text.lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(12)..].join("\n")
+}
+
+/// Ejecuta los processors de cliente en orden, saltando los que ya tienen sus outputs válidos.
+/// `on_processor_done` recibe la etiqueta de cada processor ejecutado o saltado.
+pub(crate) fn run_processors(
+    java_path: &Path,
+    install_profile: &Value,
+    env: &ProcessorEnvironment,
+    on_processor_done: impl Fn(&str),
+) -> Result<ProcessorRunReport, String> {
+    let processors = client_processors(install_profile);
+    let mut report = ProcessorRunReport::default();
+    if processors.is_empty() {
+        return Ok(report);
+    }
+    let data = resolve_processor_data(install_profile, env)?;
+
+    for (index, processor) in processors.iter().enumerate() {
+        let label = format!("{}#{index}", processor.jar);
+        let outputs = resolved_outputs(processor, &data, &env.libraries_dir)?;
+        if outputs_match(&outputs) {
+            on_processor_done(&label);
+            report.skipped.push(label);
+            continue;
+        }
+
+        let invocation = processor_invocation(processor, &data, &env.libraries_dir)?;
+        let output = Command::new(java_path)
+            .args(&invocation)
+            .current_dir(&env.work_dir)
+            .output()
+            .map_err(|error| format!("No se pudo ejecutar processor {label}: {error}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "El processor {label} terminó con {}: {}",
+                output.status,
+                output_tail(&output.stderr)
+            ));
+        }
+
+        for (path, expected) in &outputs {
+            let actual = file_sha1(path).unwrap_or_else(|| "ausente".to_string());
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(format!(
+                    "El processor {label} generó {} con SHA1 {actual}, se esperaba {expected}",
+                    path.display()
+                ));
+            }
+        }
+        on_processor_done(&label);
+        report.executed.push(label);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use sha1::{Digest, Sha1};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use super::{
+        client_processors, installer_entry_target, library_path, resolve_processor_data,
+        resolve_processor_token, run_processors, ProcessorEnvironment,
+    };
+
+    fn test_env(label: &str) -> ProcessorEnvironment {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root =
+            std::env::temp_dir().join(format!("frutistudio-test-processors-{label}-{unique}"));
+        fs::create_dir_all(&root).expect("root");
+        let installer_path = root.join("forge-installer.jar");
+        {
+            let mut zip = ZipWriter::new(fs::File::create(&installer_path).expect("installer"));
+            zip.start_file("data/client.lzma", SimpleFileOptions::default())
+                .expect("entry");
+            zip.write_all(b"patches").expect("write");
+            zip.finish().expect("finish");
+        }
+        ProcessorEnvironment {
+            libraries_dir: root.join("libraries"),
+            minecraft_root: root.join("minecraft"),
+            minecraft_jar: root.join("minecraft/versions/1.20.1/1.20.1.jar"),
+            minecraft_version: "1.20.1".to_string(),
+            installer_path,
+            work_dir: root.join("work"),
+        }
+    }
+
+    fn install_profile(output_sha1: &str) -> serde_json::Value {
+        serde_json::json!({
+            "data": {
+                "BINPATCH": {"client": "/data/client.lzma", "server": "/data/server.lzma"},
+                "MOJMAPS": {"client": "[net.minecraft:client:1.20.1:mappings@txt]", "server": "x"},
+                "PATCHED_SHA": {"client": format!("'{output_sha1}'"), "server": "'0'"}
+            },
+            "processors": [
+                {"sides": ["server"], "jar": "net.minecraftforge:installertools:1.3.0", "args": []},
+                {
+                    "jar": "net.minecraftforge:binarypatcher:1.1.1",
+                    "classpath": ["net.sf.jopt-simple:jopt-simple:5.0.4"],
+                    "args": ["--clean", "{MINECRAFT_JAR}", "--apply", "{BINPATCH}", "--output", "[net.minecraftforge:forge:1.20.1-47.3.0:client]"],
+                    "outputs": {"[net.minecraftforge:forge:1.20.1-47.3.0:client]": "{PATCHED_SHA}"}
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn resolves_data_tokens_from_installer_and_maven_coordinates() {
+        let env = test_env("tokens");
+        let profile = install_profile("abc");
+        assert_eq!(client_processors(&profile).len(), 1);
+
+        let data = resolve_processor_data(&profile, &env).expect("data");
+        assert_eq!(
+            fs::read(&data["BINPATCH"]).expect("extracted lzma"),
+            b"patches"
+        );
+        assert!(data["MOJMAPS"].ends_with("client-1.20.1-mappings.txt"));
+        assert_eq!(data["PATCHED_SHA"], "abc");
+        assert_eq!(
+            resolve_processor_token("{MINECRAFT_JAR}", &data, &env.libraries_dir).expect("jar"),
+            env.minecraft_jar.to_string_lossy()
+        );
+        assert!(resolve_processor_token("{UNKNOWN}", &data, &env.libraries_dir).is_err());
+
+        fs::remove_dir_all(env.work_dir.parent().expect("root")).expect("cleanup");
+    }
+
+    #[test]
+    fn rejects_data_entries_that_escape_the_work_dir() {
+        let work_dir = std::path::Path::new("/tmp/work");
+        assert_eq!(
+            installer_entry_target(work_dir, "data/./client.lzma"),
+            Some(work_dir.join("data/client.lzma"))
+        );
+        for entry in [
+            "../escape.jar",
+            "data/../../escape.jar",
+            "data\\..\\..\\x",
+            "C:/x",
+            "",
+        ] {
+            assert_eq!(installer_entry_target(work_dir, entry), None, "{entry}");
+        }
+
+        let env = test_env("escape");
+        let profile = serde_json::json!({
+            "data": {"BINPATCH": {"client": "/../../escape.lzma"}}
+        });
+        assert!(resolve_processor_data(&profile, &env).is_err());
+        assert!(!env.work_dir.join("../escape.lzma").exists());
+
+        fs::remove_dir_all(env.work_dir.parent().expect("root")).expect("cleanup");
+    }
+
+    #[test]
+    fn skips_processors_whose_outputs_already_match() {
+        let env = test_env("skip");
+        let output = library_path(
+            &env.libraries_dir,
+            "net.minecraftforge:forge:1.20.1-47.3.0:client",
+        )
+        .expect("output path");
+        fs::create_dir_all(output.parent().expect("parent")).expect("output dir");
+        fs::write(&output, b"patched client").expect("output");
+        let sha1 = format!("{:x}", Sha1::digest(b"patched client"));
+
+        let done = std::cell::RefCell::new(Vec::new());
+        let report = run_processors(
+            std::path::Path::new("/nonexistent/java"),
+            &install_profile(&sha1),
+            &env,
+            |label| done.borrow_mut().push(label.to_string()),
+        )
+        .expect("run");
+        assert!(report.executed.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(done.into_inner(), report.skipped);
+
+        let stale = run_processors(
+            std::path::Path::new("/nonexistent/java"),
+            &install_profile("0000"),
+            &env,
+            |_| {},
+        );
+        assert!(stale.is_err());
+
+        fs::remove_dir_all(env.work_dir.parent().expect("root")).expect("cleanup");
+    }
+}
@@ -0,0 +1,569 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::core::config::HttpCacheConfig;
+
+static GLOBAL_HTTP_CACHE: Lazy<HttpMetadataCache> = Lazy::new(HttpMetadataCache::default);
+
+const DEFAULT_MANIFEST_TTL_SECS: u64 = 10 * 60;
+const DEFAULT_METADATA_TTL_SECS: u64 = 60 * 60;
+const DEFAULT_IMMUTABLE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MetadataClass {
+    Manifest,
+    Metadata,
+    Immutable,
+}
+
+impl MetadataClass {
+    /// Clasifica por la ruta de la URL, que se conserva al reescribir hacia mirrors.
+    pub(crate) fn for_url(url: &str) -> Self {
+        let path = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(url)
+            .to_ascii_lowercase();
+        let content_addressed = path
+            .split('/')
+            .any(|segment| segment.len() == 40 && segment.bytes().all(|b| b.is_ascii_hexdigit()));
+        if path.contains("version_manifest") {
+            Self::Manifest
+        } else if path.contains("/products/") {
+            // `.../products/java-runtime/<hash>/all.json` cambia aunque lleve un hash en la ruta.
+            Self::Metadata
+        } else if content_addressed || path.ends_with(".pom") {
+            Self::Immutable
+        } else {
+            Self::Metadata
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedEntry {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    fetched_at: u64,
+    body: String,
+}
+
+#[derive(Debug, Default)]
+struct CacheSettings {
+    root: Option<PathBuf>,
+    config: HttpCacheConfig,
+}
+
+/// Caché en disco de respuestas de metadata por URL: guarda validadores y cuerpo, revalida
+/// con `If-None-Match`/`If-Modified-Since` al caducar el TTL y sirve la copia vieja si la
+/// red falla.
+#[derive(Debug, Default)]
+pub(crate) struct HttpMetadataCache {
+    settings: RwLock<CacheSettings>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn header_value(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Cómo se obtuvo el cuerpo devuelto por la caché.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FetchSource {
+    Network,
+    /// El servidor respondió 304 y se reutilizó la copia.
+    Revalidated,
+    /// Copia dentro del TTL, sin tocar la red.
+    Cache,
+    /// Copia caducada servida porque la red o el servidor fallaron.
+    Stale,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FetchedText {
+    pub(crate) body: String,
+    pub(crate) source: FetchSource,
+    pub(crate) fetched_at: u64,
+}
+
+/// `status` es `Some` cuando el servidor llegó a responder, aunque fuera con error.
+#[derive(Debug, Clone)]
+pub(crate) struct FetchError {
+    pub(crate) message: String,
+    pub(crate) status: Option<StatusCode>,
+}
+
+impl FetchError {
+    fn network(message: String) -> Self {
+        Self {
+            message,
+            status: None,
+        }
+    }
+}
+
+fn stale_or(cached: Option<CachedEntry>, error: FetchError) -> Result<FetchedText, FetchError> {
+    match cached {
+        Some(entry) => {
+            eprintln!(
+                "[http-cache] usando copia caducada de {}: {}",
+                entry.url, error.message
+            );
+            Ok(FetchedText {
+                body: entry.body,
+                source: FetchSource::Stale,
+                fetched_at: entry.fetched_at,
+            })
+        }
+        None => Err(error),
+    }
+}
+
+impl HttpMetadataCache {
+    pub(crate) fn global() -> &'static HttpMetadataCache {
+        &GLOBAL_HTTP_CACHE
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, CacheSettings> {
+        self.settings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn attach_store(&self, root: PathBuf) {
+        self.settings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .root = Some(root);
+    }
+
+    pub(crate) fn configure(&self, config: Option<&HttpCacheConfig>) {
+        self.settings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .config = config.cloned().unwrap_or_default();
+    }
+
+    fn ttl_secs(&self, class: MetadataClass) -> u64 {
+        let settings = self.read();
+        let config = &settings.config;
+        match class {
+            MetadataClass::Manifest => config
+                .manifest_ttl_secs
+                .unwrap_or(DEFAULT_MANIFEST_TTL_SECS),
+            MetadataClass::Metadata => config
+                .metadata_ttl_secs
+                .unwrap_or(DEFAULT_METADATA_TTL_SECS),
+            MetadataClass::Immutable => config
+                .immutable_ttl_secs
+                .unwrap_or(DEFAULT_IMMUTABLE_TTL_SECS),
+        }
+    }
+
+    pub(crate) fn has_store(&self) -> bool {
+        self.read().root.is_some()
+    }
+
+    fn enabled(&self) -> bool {
+        self.read().config.enabled.unwrap_or(true)
+    }
+
+    fn entry_path(&self, url: &str) -> Option<PathBuf> {
+        if !self.enabled() {
+            return None;
+        }
+        self.store_path(url)
+    }
+
+    /// Ruta de la copia de `url` aunque la caché esté desactivada.
+    fn store_path(&self, url: &str) -> Option<PathBuf> {
+        let key = format!("{:x}", Sha1::digest(url.as_bytes()));
+        Some(self.read().root.as_ref()?.join(format!("{key}.json")))
+    }
+
+    fn load(path: &Path, url: &str) -> Option<CachedEntry> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<CachedEntry>(&raw).ok())
+            .filter(|entry| entry.url == url)
+    }
+
+    fn store(path: &Path, entry: &CachedEntry) {
+        let Ok(raw) = serde_json::to_string(entry) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let temp = path.with_extension("json.tmp");
+        if fs::write(&temp, raw).is_ok() {
+            let _ = fs::rename(&temp, path);
+        }
+    }
+
+    /// Descarta la copia de `url`, p. ej. cuando su cuerpo ya no se puede interpretar.
+    pub(crate) fn invalidate(&self, url: &str) {
+        if let Some(path) = self.store_path(url) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Guarda `body` como copia de `url` si aún no hay ninguna. Sirve para importar cachés
+    /// de versiones anteriores.
+    pub(crate) fn seed(
+        &self,
+        url: &str,
+        body: String,
+        fetched_at: u64,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) -> bool {
+        let Some(path) = self.store_path(url) else {
+            return false;
+        };
+        if Self::load(&path, url).is_some() {
+            return false;
+        }
+        Self::store(
+            &path,
+            &CachedEntry {
+                url: url.to_string(),
+                etag,
+                last_modified,
+                fetched_at,
+                body,
+            },
+        );
+        true
+    }
+
+    /// Cuerpo de `url` usando la caché. `request` es la petición ya preparada (cliente,
+    /// cabeceras); aquí sólo se le añaden los validadores condicionales.
+    pub(crate) async fn fetch_text(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+    ) -> Result<String, String> {
+        self.fetch(request, url, false)
+            .await
+            .map(|fetched| fetched.body)
+            .map_err(|error| error.message)
+    }
+
+    /// Como `fetch_text`, pero informa de dónde salió el cuerpo y distingue un error HTTP
+    /// de la falta de red. Con `force_refresh` se revalida aunque la copia siga en TTL.
+    pub(crate) async fn fetch(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+        force_refresh: bool,
+    ) -> Result<FetchedText, FetchError> {
+        self.fetch_at(request, url, now_secs(), force_refresh).await
+    }
+
+    /// Como `fetch`, pero la copia se guarda aunque la caché esté desactivada: hay respuestas,
+    /// como el manifiesto de versiones, sin las que no se puede jugar sin red. Desactivada,
+    /// siempre se consulta la red y la copia sólo se usa si esta falla.
+    pub(crate) async fn fetch_persistent(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+        force_refresh: bool,
+    ) -> Result<FetchedText, FetchError> {
+        let force_refresh = force_refresh || !self.enabled();
+        self.fetch_into(
+            request,
+            url,
+            now_secs(),
+            force_refresh,
+            self.store_path(url),
+        )
+        .await
+    }
+
+    async fn fetch_at(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+        now: u64,
+        force_refresh: bool,
+    ) -> Result<FetchedText, FetchError> {
+        self.fetch_into(request, url, now, force_refresh, self.entry_path(url))
+            .await
+    }
+
+    async fn fetch_into(
+        &self,
+        mut request: reqwest::RequestBuilder,
+        url: &str,
+        now: u64,
+        force_refresh: bool,
+        path: Option<PathBuf>,
+    ) -> Result<FetchedText, FetchError> {
+        let Some(path) = path else {
+            let response = request.send().await.map_err(|error| {
+                FetchError::network(format!("No se pudo descargar {url}: {error}"))
+            })?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(FetchError {
+                    message: format!("{url} respondió {status}"),
+                    status: Some(status),
+                });
+            }
+            let body = response
+                .text()
+                .await
+                .map_err(|error| FetchError::network(format!("No se pudo leer {url}: {error}")))?;
+            return Ok(FetchedText {
+                body,
+                source: FetchSource::Network,
+                fetched_at: now,
+            });
+        };
+
+        let cached = Self::load(&path, url);
+        if let Some(entry) = cached.as_ref() {
+            let ttl = self.ttl_secs(MetadataClass::for_url(url));
+            if !force_refresh && now.saturating_sub(entry.fetched_at) < ttl {
+                return Ok(FetchedText {
+                    body: entry.body.clone(),
+                    source: FetchSource::Cache,
+                    fetched_at: entry.fetched_at,
+                });
+            }
+            if let Some(etag) = entry.etag.as_deref() {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = entry.last_modified.as_deref() {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(error) => {
+                return stale_or(
+                    cached,
+                    FetchError::network(format!("No se pudo descargar {url}: {error}")),
+                )
+            }
+        };
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            let Some(mut entry) = cached else {
+                return Err(FetchError {
+                    message: format!("{url} respondió 304 sin copia en caché"),
+                    status: Some(status),
+                });
+            };
+            entry.fetched_at = now;
+            Self::store(&path, &entry);
+            return Ok(FetchedText {
+                body: entry.body,
+                source: FetchSource::Revalidated,
+                fetched_at: now,
+            });
+        }
+        if !status.is_success() {
+            let error = FetchError {
+                message: format!("{url} respondió {status}"),
+                status: Some(status),
+            };
+            return if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                stale_or(cached, error)
+            } else {
+                Err(error)
+            };
+        }
+
+        let etag = header_value(&response, ETAG);
+        let last_modified = header_value(&response, LAST_MODIFIED);
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(error) => {
+                return stale_or(
+                    cached,
+                    FetchError::network(format!("No se pudo leer {url}: {error}")),
+                )
+            }
+        };
+        Self::store(
+            &path,
+            &CachedEntry {
+                url: url.to_string(),
+                etag,
+                last_modified,
+                fetched_at: now,
+                body: body.clone(),
+            },
+        );
+        Ok(FetchedText {
+            body,
+            source: FetchSource::Network,
+            fetched_at: now,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{FetchSource, HttpMetadataCache, MetadataClass};
+    use crate::core::config::HttpCacheConfig;
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    #[test]
+    fn classifies_endpoints_by_url_shape() {
+        assert_eq!(
+            MetadataClass::for_url(
+                "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"
+            ),
+            MetadataClass::Manifest
+        );
+        assert_eq!(
+            MetadataClass::for_url(
+                "https://piston-meta.mojang.com/v1/packages/0123456789abcdef0123456789abcdef01234567/1.20.1.json"
+            ),
+            MetadataClass::Immutable
+        );
+        assert_eq!(
+            MetadataClass::for_url("https://repo1.maven.org/maven2/a/b/1.0/b-1.0.pom"),
+            MetadataClass::Immutable
+        );
+        assert_eq!(
+            MetadataClass::for_url("https://meta.fabricmc.net/v2/versions/loader/1.20.1"),
+            MetadataClass::Metadata
+        );
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_serves_stale_copies_when_the_server_fails() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let served = hits.clone();
+        let base = spawn_mock_server(move |request| {
+            served.fetch_add(1, Ordering::SeqCst);
+            match (request.path.as_str(), request.header("if-none-match")) {
+                ("/meta.json", Some("\"v1\"")) => MockResponse {
+                    status: 304,
+                    headers: Vec::new(),
+                    body: Vec::new(),
+                },
+                ("/meta.json", _) => MockResponse {
+                    status: 200,
+                    headers: vec![("ETag".to_string(), "\"v1\"".to_string())],
+                    body: br#"{"version":1}"#.to_vec(),
+                },
+                (_, Some(_)) => MockResponse::json(503, serde_json::json!({})),
+                _ => MockResponse::json(200, serde_json::json!({"flaky": true})),
+            }
+        });
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-http-cache-{unique}"));
+        let cache = HttpMetadataCache::default();
+        cache.attach_store(root.clone());
+        cache.configure(Some(&HttpCacheConfig {
+            metadata_ttl_secs: Some(60),
+            ..HttpCacheConfig::default()
+        }));
+        let client = reqwest::Client::new();
+        let meta = format!("{base}/meta.json");
+        let flaky = format!("{base}/flaky.json");
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        rt.block_on(async {
+            let now = 1_000;
+            let first = cache
+                .fetch_at(client.get(&meta), &meta, now, false)
+                .await
+                .expect("first");
+            assert_eq!(first.body, r#"{"version":1}"#);
+            assert_eq!(first.source, FetchSource::Network);
+            let fresh = cache
+                .fetch_at(client.get(&meta), &meta, now + 30, false)
+                .await
+                .expect("fresh");
+            assert_eq!((fresh.source, fresh.fetched_at), (FetchSource::Cache, now));
+            assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+            let forced = cache
+                .fetch_at(client.get(&meta), &meta, now + 40, true)
+                .await
+                .expect("forced");
+            assert_eq!(forced.source, FetchSource::Revalidated);
+            assert_eq!(hits.load(Ordering::SeqCst), 2);
+            let revalidated = cache
+                .fetch_at(client.get(&meta), &meta, now + 120, false)
+                .await
+                .expect("304");
+            assert_eq!(revalidated.body, r#"{"version":1}"#);
+            assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+            cache
+                .fetch_at(client.get(&flaky), &flaky, now, false)
+                .await
+                .expect("flaky");
+            let stale = cache
+                .fetch_at(
+                    client.get(&flaky).header("If-None-Match", "\"x\""),
+                    &flaky,
+                    now + 120,
+                    false,
+                )
+                .await
+                .expect("stale");
+            assert!(stale.body.contains("flaky"));
+            assert_eq!(stale.source, FetchSource::Stale);
+
+            cache.invalidate(&flaky);
+            let missing = cache
+                .fetch_at(
+                    client.get(&flaky).header("If-None-Match", "\"x\""),
+                    &flaky,
+                    now + 120,
+                    false,
+                )
+                .await
+                .expect_err("sin copia");
+            assert!(missing.message.contains("503"));
+            assert_eq!(missing.status.map(|status| status.as_u16()), Some(503));
+
+            let unreachable = "http://127.0.0.1:9/meta.json";
+            let offline = cache
+                .fetch_at(client.get(unreachable), unreachable, now, false)
+                .await
+                .expect_err("sin red");
+            assert!(offline.status.is_none());
+        });
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
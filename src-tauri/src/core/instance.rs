@@ -20,6 +20,9 @@ pub(crate) struct InstanceRecord {
     pub(crate) java_mode: Option<String>,
     #[serde(default)]
     pub(crate) java_path: Option<String>,
+    /// `plain` (por defecto) o `xml` para la salida de consola de log4j.
+    #[serde(default)]
+    pub(crate) log_format: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -120,18 +123,25 @@ pub(crate) struct InstanceCommandArgs {
     pub(crate) instance_id: Option<String>,
     #[serde(alias = "playerName")]
     pub(crate) username: Option<String>,
+    /// En cuentas offline no se usa para lanzar: el UUID se deriva del nombre y éste sólo
+    /// identifica datos guardados con el UUID anterior de la cuenta.
     #[serde(alias = "playerUuid")]
     pub(crate) uuid: Option<String>,
     #[serde(alias = "access_token")]
     pub(crate) access_token: Option<String>,
     #[serde(alias = "user_type")]
     pub(crate) user_type: Option<String>,
+    #[serde(default, alias = "account_id")]
+    pub(crate) account_id: Option<String>,
     #[serde(default)]
     pub(crate) java_mode: Option<String>,
     #[serde(default)]
     pub(crate) java_path: Option<String>,
     #[serde(default)]
     pub(crate) repair_mode: Option<String>,
+    /// `Some(true)` fuerza el lanzamiento offline; `None` lo decide según haya red.
+    #[serde(default)]
+    pub(crate) offline: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
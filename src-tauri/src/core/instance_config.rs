@@ -110,6 +110,22 @@ pub(crate) fn resolve_instance_launch_config(
                 .map(str::to_string)
         });
 
+    let log_format = metadata
+        .get("logging")
+        .and_then(|value| value.get("format"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            instance
+                .log_format
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        });
+
     InstanceLaunchConfig {
         minecraft_version,
         modloader,
@@ -118,5 +134,6 @@ pub(crate) fn resolve_instance_launch_config(
         game_dir: instance_game_dir(instance_root),
         java_mode,
         java_path,
+        log_format,
     }
 }
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use rusqlite::Connection;
 use serde::Serialize;
 
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +14,7 @@ pub(crate) struct JavaRuntime {
     pub(crate) architecture: String,
     pub(crate) source: String,
     pub(crate) recommended: bool,
+    pub(crate) vendor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -26,4 +28,6 @@ pub(crate) struct JavaResolution {
 
 pub(crate) struct JavaManager {
     pub(crate) launcher_root: PathBuf,
+    /// Caché SQLite de sondeos; sin ella se sondea cada binario en cada llamada.
+    pub(crate) probe_cache: Option<Connection>,
 }
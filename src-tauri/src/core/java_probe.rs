@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::parse_java_version;
+
+/// Evento que se emite al terminar un reescaneo en segundo plano.
+pub(crate) const JAVA_RESCAN_EVENT: &str = "java-rescan-finished";
+
+pub(crate) const JAVA_PROBES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS java_probes (
+    path TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    mtime INTEGER NOT NULL,
+    valid INTEGER NOT NULL,
+    version TEXT,
+    major INTEGER,
+    architecture TEXT,
+    vendor TEXT,
+    probed_at INTEGER
+);";
+
+/// Un sondeo fallido se repite pasado este plazo aunque el binario no haya cambiado: el fallo
+/// puede deberse a un `release` aún sin escribir o a un error transitorio al ejecutarlo.
+const NEGATIVE_PROBE_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct JavaProbe {
+    pub(crate) version: String,
+    pub(crate) major: u32,
+    pub(crate) architecture: String,
+    pub(crate) vendor: Option<String>,
+}
+
+/// Ruta canónica más tamaño y mtime del binario; si cambia alguno se vuelve a sondear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BinaryFingerprint {
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    pub(crate) mtime_ms: i64,
+}
+
+impl BinaryFingerprint {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let canonical = fs::canonicalize(path).ok()?;
+        let meta = fs::metadata(&canonical).ok()?;
+        if !meta.is_file() {
+            return None;
+        }
+        let mtime_ms = meta
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or_default();
+        Some(Self {
+            path: canonical,
+            size: meta.len(),
+            mtime_ms,
+        })
+    }
+
+    fn key(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JavaRescanReport {
+    pub(crate) candidates: usize,
+    pub(crate) reused: usize,
+    pub(crate) probed: usize,
+    pub(crate) valid: usize,
+    pub(crate) removed: usize,
+}
+
+fn normalize_architecture(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "x86_64" | "amd64" | "x64" => "x64".to_string(),
+        "aarch64" | "arm64" => "arm64".to_string(),
+        "i386" | "i586" | "i686" | "x86" => "x86".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn major_from_version(version: &str) -> Option<u32> {
+    let version = version.strip_prefix("1.").unwrap_or(version);
+    version
+        .split(|ch: char| !ch.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Lee `JAVA_VERSION`, `IMPLEMENTOR` y `OS_ARCH` del archivo `release` de un JDK/JRE.
+pub(crate) fn parse_release_file(content: &str) -> Option<JavaProbe> {
+    let values = content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+        .collect::<HashMap<_, _>>();
+    let version = values
+        .get("JAVA_VERSION")
+        .filter(|value| !value.is_empty())?;
+    Some(JavaProbe {
+        major: major_from_version(version)?,
+        version: version.to_string(),
+        architecture: values
+            .get("OS_ARCH")
+            .filter(|value| !value.is_empty())
+            .map(|value| normalize_architecture(value))
+            .unwrap_or_else(|| std::env::consts::ARCH.to_string()),
+        vendor: values
+            .get("IMPLEMENTOR")
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+    })
+}
+
+/// `release` vive en la raíz del JDK, junto a `bin/`.
+fn release_file_probe(java: &Path) -> Option<JavaProbe> {
+    let home = java.parent()?.parent()?;
+    let content = fs::read_to_string(home.join("release")).ok()?;
+    parse_release_file(&content)
+}
+
+fn java_version_probe(java: &Path) -> Option<JavaProbe> {
+    let output = Command::new(java).arg("-version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let combined = if stderr.trim().is_empty() {
+        stdout
+    } else {
+        stderr
+    };
+    let (version, major) = parse_java_version(&combined)?;
+
+    let architecture = if combined.to_lowercase().contains("64-bit") {
+        "x64".to_string()
+    } else if combined.to_lowercase().contains("aarch64") {
+        "arm64".to_string()
+    } else {
+        std::env::consts::ARCH.to_string()
+    };
+
+    Some(JavaProbe {
+        version,
+        major,
+        architecture,
+        vendor: None,
+    })
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// `Some(None)` significa que el binario se sondeó hace menos de `NEGATIVE_PROBE_TTL_SECS` y
+/// no es un Java válido.
+fn cached_probe(conn: &Connection, fingerprint: &BinaryFingerprint) -> Option<Option<JavaProbe>> {
+    let row = conn
+        .query_row(
+            "SELECT valid, version, major, architecture, vendor, probed_at FROM java_probes
+             WHERE path = ?1 AND size = ?2 AND mtime = ?3",
+            params![
+                fingerprint.key(),
+                fingerprint.size as i64,
+                fingerprint.mtime_ms
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, bool>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<u32>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                ))
+            },
+        )
+        .optional()
+        .ok()??;
+    match row {
+        (true, Some(version), Some(major), architecture, vendor, _) => Some(Some(JavaProbe {
+            version,
+            major,
+            architecture: architecture.unwrap_or_else(|| std::env::consts::ARCH.to_string()),
+            vendor,
+        })),
+        (false, .., Some(probed_at)) if now_secs() - probed_at < NEGATIVE_PROBE_TTL_SECS => {
+            Some(None)
+        }
+        _ => None,
+    }
+}
+
+fn store_probe(
+    conn: &Connection,
+    fingerprint: &BinaryFingerprint,
+    probe: Option<&JavaProbe>,
+) -> Result<(), String> {
+    let probed_at = now_secs();
+    conn.execute(
+        "INSERT OR REPLACE INTO java_probes
+         (path, size, mtime, valid, version, major, architecture, vendor, probed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            fingerprint.key(),
+            fingerprint.size as i64,
+            fingerprint.mtime_ms,
+            probe.is_some(),
+            probe.map(|probe| probe.version.as_str()),
+            probe.map(|probe| probe.major),
+            probe.map(|probe| probe.architecture.as_str()),
+            probe.and_then(|probe| probe.vendor.as_deref()),
+            probed_at,
+        ],
+    )
+    .map_err(|error| format!("No se pudo guardar sondeo de Java: {error}"))?;
+    Ok(())
+}
+
+/// Devuelve `(sondeo, reutilizado)`. Sin caché siempre se sondea el binario: primero el
+/// archivo `release` y, si no existe, `java -version`.
+fn probe_with_cache(conn: Option<&Connection>, path: &Path) -> (Option<JavaProbe>, bool) {
+    let Some(fingerprint) = BinaryFingerprint::of(path) else {
+        return (None, false);
+    };
+    if let Some(cached) = conn.and_then(|conn| cached_probe(conn, &fingerprint)) {
+        return (cached, true);
+    }
+    let probe =
+        release_file_probe(&fingerprint.path).or_else(|| java_version_probe(&fingerprint.path));
+    if let Some(conn) = conn {
+        if let Err(error) = store_probe(conn, &fingerprint, probe.as_ref()) {
+            eprintln!("[java] {error}");
+        }
+    }
+    (probe, false)
+}
+
+pub(crate) fn probe_java(conn: Option<&Connection>, path: &Path) -> Option<JavaProbe> {
+    probe_with_cache(conn, path).0
+}
+
+/// Sondea sólo los candidatos nuevos o modificados y borra las entradas cuyo binario ya no
+/// existe.
+pub(crate) fn rescan_java_candidates(
+    conn: &Connection,
+    candidates: &[PathBuf],
+) -> JavaRescanReport {
+    let mut report = JavaRescanReport::default();
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        report.candidates += 1;
+        let (probe, reused) = probe_with_cache(Some(conn), candidate);
+        if reused {
+            report.reused += 1;
+        } else {
+            report.probed += 1;
+        }
+        if probe.is_some() {
+            report.valid += 1;
+        }
+    }
+
+    let stored = conn
+        .prepare("SELECT path FROM java_probes")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_default();
+    for path in stored {
+        if Path::new(&path).is_file() {
+            continue;
+        }
+        if conn
+            .execute("DELETE FROM java_probes WHERE path = ?1", params![path])
+            .is_ok()
+        {
+            report.removed += 1;
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use rusqlite::Connection;
+
+    use super::{parse_release_file, probe_java, rescan_java_candidates, JAVA_PROBES_SCHEMA};
+
+    #[test]
+    fn reads_release_files_and_reprobes_only_changed_binaries() {
+        let probe = parse_release_file(
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"1.8.0_382\"\nOS_ARCH=\"amd64\"\n",
+        )
+        .expect("release");
+        assert_eq!(probe.major, 8);
+        assert_eq!(probe.version, "1.8.0_382");
+        assert_eq!(probe.architecture, "x64");
+        assert_eq!(probe.vendor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(
+            parse_release_file("JAVA_VERSION=\"21.0.2+13\"").map(|probe| probe.major),
+            Some(21)
+        );
+        assert!(parse_release_file("IMPLEMENTOR=\"Azul\"").is_none());
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-java-probe-{unique}"));
+        let java = root.join("jdk").join("bin").join("java");
+        fs::create_dir_all(java.parent().expect("bin")).expect("bin");
+        fs::write(&java, b"no es un binario").expect("java");
+        let release = root.join("jdk").join("release");
+        fs::write(
+            &release,
+            "JAVA_VERSION=\"17.0.8\"\nIMPLEMENTOR=\"Eclipse Adoptium\"\nOS_ARCH=\"aarch64\"\n",
+        )
+        .expect("release");
+
+        let conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch(JAVA_PROBES_SCHEMA).expect("schema");
+        let first = rescan_java_candidates(&conn, std::slice::from_ref(&java));
+        assert_eq!((first.candidates, first.probed, first.valid), (1, 1, 1));
+
+        // Con el binario intacto se usa la caché aunque cambie el `release`.
+        fs::write(&release, "JAVA_VERSION=\"21.0.1\"\n").expect("release 21");
+        let cached = probe_java(Some(&conn), &java).expect("cached");
+        assert_eq!((cached.major, cached.architecture.as_str()), (17, "arm64"));
+        let second = rescan_java_candidates(&conn, std::slice::from_ref(&java));
+        assert_eq!((second.reused, second.probed), (1, 0));
+
+        fs::write(&java, b"binario actualizado").expect("update java");
+        assert_eq!(probe_java(Some(&conn), &java).expect("reprobed").major, 21);
+
+        // Un binario inválido se recuerda, pero sólo durante el plazo de los sondeos fallidos.
+        let pending = root.join("pendiente").join("bin").join("java");
+        fs::create_dir_all(pending.parent().expect("bin")).expect("bin");
+        fs::write(&pending, b"no es un binario").expect("pending");
+        assert!(probe_java(Some(&conn), &pending).is_none());
+        fs::write(
+            root.join("pendiente").join("release"),
+            "JAVA_VERSION=\"17\"\n",
+        )
+        .expect("release");
+        assert!(probe_java(Some(&conn), &pending).is_none());
+        conn.execute("UPDATE java_probes SET probed_at = 0 WHERE valid = 0", [])
+            .expect("expire");
+        assert_eq!(
+            probe_java(Some(&conn), &pending).expect("retried").major,
+            17
+        );
+
+        fs::remove_dir_all(&root).expect("cleanup");
+        let third = rescan_java_candidates(&conn, &[java, pending]);
+        assert_eq!((third.candidates, third.removed), (0, 2));
+    }
+}
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub(crate) use crate::core::auth::LaunchAuth;
+use crate::core::redaction::{redact_args, REDACTED_SECRET};
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LaunchInstanceResult {
@@ -84,17 +87,40 @@ pub(crate) struct LaunchPlan {
     pub(crate) resolved_java_major: u32,
     pub(crate) loader: String,
     pub(crate) loader_profile_resolved: bool,
+    /// Versiones con `minecraftArguments`: sólo se actualizan los flags que ya trae la plantilla.
+    #[serde(default)]
+    pub(crate) legacy_arguments: bool,
     pub(crate) auth: LaunchAuth,
     pub(crate) env: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct LaunchAuth {
-    pub(crate) username: String,
-    pub(crate) uuid: String,
-    pub(crate) access_token: String,
-    pub(crate) user_type: String,
+impl LaunchPlan {
+    /// Copia apta para persistir: el token sólo vive en memoria al lanzar.
+    pub(crate) fn redacted(&self) -> Self {
+        let mut plan = self.clone();
+        plan.game_args = redact_args(&self.game_args);
+        if !plan.auth.access_token.is_empty() && plan.auth.access_token != "0" {
+            plan.auth.access_token = REDACTED_SECRET.to_string();
+        }
+        plan
+    }
+
+    pub(crate) fn has_persisted_secrets(&self) -> bool {
+        let redacted = self.redacted();
+        redacted.game_args != self.game_args || redacted.auth.access_token != self.auth.access_token
+    }
+
+    /// Convierte los marcadores de un plan leído de disco en el token offline `0`.
+    pub(crate) fn clear_redaction_placeholders(&mut self) {
+        if self.auth.access_token == REDACTED_SECRET {
+            self.auth.access_token = "0".to_string();
+        }
+        for arg in &mut self.game_args {
+            if arg == REDACTED_SECRET {
+                *arg = "0".to_string();
+            }
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -139,4 +165,5 @@ pub(crate) struct InstanceLaunchConfig {
     pub(crate) game_dir: PathBuf,
     pub(crate) java_mode: Option<String>,
     pub(crate) java_path: Option<String>,
+    pub(crate) log_format: Option<String>,
 }
@@ -250,7 +250,11 @@ pub(crate) fn accepted_main_classes_for_loader(loader: &str) -> &'static [&'stat
         ],
         "quilt" => &["org.quiltmc.loader.impl.launch.knot.KnotClient"],
         "forge" | "neoforge" => &["cpw.mods.bootstraplauncher.BootstrapLauncher"],
-        "vanilla" => &["net.minecraft.client.main.Main"],
+        "vanilla" => &[
+            "net.minecraft.client.main.Main",
+            "net.minecraft.launchwrapper.Launch",
+            "net.minecraft.client.Minecraft",
+        ],
         _ => &[],
     }
 }
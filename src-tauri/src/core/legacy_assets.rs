@@ -0,0 +1,207 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+/// Distribución de assets que pide un asset index.
+/// `virtual` (1.6–1.7.2) y `map_to_resources` (pre-1.6) necesitan los archivos con su
+/// nombre real además de los objetos por hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssetLayout {
+    Hashed,
+    Virtual,
+    MapToResources,
+}
+
+impl AssetLayout {
+    pub(crate) fn from_index(index: &Value) -> Self {
+        let flag = |key: &str| index.get(key).and_then(Value::as_bool).unwrap_or(false);
+        if flag("map_to_resources") {
+            Self::MapToResources
+        } else if flag("virtual") {
+            Self::Virtual
+        } else {
+            Self::Hashed
+        }
+    }
+
+    pub(crate) fn is_legacy(self) -> bool {
+        self != Self::Hashed
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Hashed => "hashed",
+            Self::Virtual => "virtual",
+            Self::MapToResources => "map_to_resources",
+        }
+    }
+}
+
+/// Valor de `${game_assets}`: la carpeta donde el juego espera los assets por nombre.
+pub(crate) fn game_assets_dir(
+    minecraft_root: &Path,
+    asset_index_id: &str,
+    layout: AssetLayout,
+) -> PathBuf {
+    match layout {
+        AssetLayout::Hashed => minecraft_root.join("assets"),
+        AssetLayout::Virtual => minecraft_root
+            .join("assets")
+            .join("virtual")
+            .join(asset_index_id),
+        AssetLayout::MapToResources => minecraft_root.join("resources"),
+    }
+}
+
+fn is_safe_relative_path(name: &str) -> bool {
+    let path = Path::new(name);
+    !name.trim().is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Copia (o enlaza) cada objeto del index desde `objects_dir` a `target_dir/<nombre>`.
+/// Los archivos que ya existen con el tamaño esperado no se tocan.
+pub(crate) fn materialize_legacy_assets(
+    objects_dir: &Path,
+    index: &Value,
+    target_dir: &Path,
+) -> Result<u64, String> {
+    let Some(objects) = index.get("objects").and_then(Value::as_object) else {
+        return Ok(0);
+    };
+
+    let mut materialized = 0_u64;
+    for (name, object) in objects {
+        let Some(hash) = object.get("hash").and_then(Value::as_str) else {
+            continue;
+        };
+        if hash.len() < 2 || !is_safe_relative_path(name) {
+            continue;
+        }
+        let expected_size = object.get("size").and_then(Value::as_u64);
+        let source = objects_dir.join(&hash[0..2]).join(hash);
+        let destination = target_dir.join(name);
+
+        let up_to_date = fs::metadata(&destination)
+            .map(|meta| meta.is_file() && expected_size.is_none_or(|size| meta.len() == size))
+            .unwrap_or(false);
+        if up_to_date {
+            continue;
+        }
+        if !source.is_file() {
+            return Err(format!(
+                "Falta el asset {name} ({hash}) en {}",
+                objects_dir.display()
+            ));
+        }
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(|error| {
+                format!(
+                    "No se pudo crear carpeta de assets legacy {}: {error}",
+                    parent.display()
+                )
+            })?;
+        }
+        let _ = fs::remove_file(&destination);
+        if fs::hard_link(&source, &destination).is_err() {
+            fs::copy(&source, &destination).map_err(|error| {
+                format!(
+                    "No se pudo copiar asset legacy {name} a {}: {error}",
+                    destination.display()
+                )
+            })?;
+        }
+        materialized += 1;
+    }
+
+    Ok(materialized)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{game_assets_dir, materialize_legacy_assets, AssetLayout};
+
+    const LEGACY_INDEX: &str = r#"{
+        "virtual": true,
+        "objects": {
+            "sound/random/click.ogg": {"hash": "aa11223344556677889900aabbccddeeff001122", "size": 5},
+            "../escape.txt": {"hash": "bb11223344556677889900aabbccddeeff001122", "size": 1}
+        }
+    }"#;
+
+    const PRE_16_INDEX: &str = r#"{
+        "map_to_resources": true,
+        "objects": {
+            "music/calm1.ogg": {"hash": "cc11223344556677889900aabbccddeeff001122", "size": 3}
+        }
+    }"#;
+
+    #[test]
+    fn detects_layout_flags_and_game_assets_dir() {
+        let modern = serde_json::json!({"objects": {}});
+        let legacy: serde_json::Value = serde_json::from_str(LEGACY_INDEX).expect("legacy");
+        let pre_16: serde_json::Value = serde_json::from_str(PRE_16_INDEX).expect("pre-1.6");
+        let root = Path::new("/game");
+
+        assert_eq!(AssetLayout::from_index(&modern), AssetLayout::Hashed);
+        assert_eq!(AssetLayout::from_index(&legacy), AssetLayout::Virtual);
+        assert_eq!(
+            AssetLayout::from_index(&pre_16),
+            AssetLayout::MapToResources
+        );
+        assert_eq!(
+            game_assets_dir(root, "legacy", AssetLayout::Virtual),
+            root.join("assets").join("virtual").join("legacy")
+        );
+        assert_eq!(
+            game_assets_dir(root, "pre-1.6", AssetLayout::MapToResources),
+            root.join("resources")
+        );
+    }
+
+    #[test]
+    fn materializes_named_copies_and_skips_unsafe_names() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-legacy-assets-{unique}"));
+        let objects = root.join("assets").join("objects");
+        let hash = "aa11223344556677889900aabbccddeeff001122";
+        fs::create_dir_all(objects.join("aa")).expect("objects");
+        fs::write(objects.join("aa").join(hash), b"click").expect("object");
+
+        let index: serde_json::Value = serde_json::from_str(LEGACY_INDEX).expect("index");
+        let target = game_assets_dir(&root, "legacy", AssetLayout::from_index(&index));
+        assert_eq!(
+            materialize_legacy_assets(&objects, &index, &target).expect("materialize"),
+            1
+        );
+        assert_eq!(
+            fs::read(target.join("sound/random/click.ogg")).expect("named asset"),
+            b"click"
+        );
+        assert!(!root
+            .join("assets")
+            .join("virtual")
+            .join("escape.txt")
+            .exists());
+        assert_eq!(
+            materialize_legacy_assets(&objects, &index, &target).expect("second pass"),
+            0
+        );
+
+        let missing: serde_json::Value = serde_json::from_str(PRE_16_INDEX).expect("pre-1.6");
+        assert!(materialize_legacy_assets(&objects, &missing, &root.join("resources")).is_err());
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+}
@@ -0,0 +1,143 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+/// Layout de consola equivalente al del archivo `latest.log`, con lookups desactivados.
+const PLAIN_CONSOLE_LAYOUT: &str =
+    r#"<PatternLayout pattern="[%d{HH:mm:ss}] [%t/%level]: %msg{nolookups}%n" />"#;
+
+static XML_LAYOUT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<(?:Legacy)?XMLLayout\s*/>|<(?:Legacy)?XMLLayout\s*>.*?</(?:Legacy)?XMLLayout>")
+        .expect("xml layout regex")
+});
+
+/// Formato de la salida de consola del juego.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogFormat {
+    /// Eventos `log4j:Event` tal como los emite la configuración de Mojang.
+    Xml,
+    Plain,
+}
+
+impl LogFormat {
+    pub(crate) fn parse(value: Option<&str>) -> Self {
+        match value.map(|raw| raw.trim().to_ascii_lowercase()).as_deref() {
+            Some("xml") => Self::Xml,
+            _ => Self::Plain,
+        }
+    }
+}
+
+/// Entrada `logging.client` del version JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LoggingClientConfig {
+    pub(crate) argument: String,
+    pub(crate) file_id: String,
+    pub(crate) sha1: Option<String>,
+    pub(crate) url: String,
+}
+
+impl LoggingClientConfig {
+    pub(crate) fn from_version_json(version_json: &Value) -> Option<Self> {
+        let client = version_json.get("logging")?.get("client")?;
+        let argument = client.get("argument")?.as_str()?.trim();
+        let file = client.get("file")?;
+        let file_id = file.get("id")?.as_str()?.trim();
+        let url = file.get("url")?.as_str()?.trim();
+        if argument.is_empty() || url.is_empty() || !is_plain_file_name(file_id) {
+            return None;
+        }
+        Some(Self {
+            argument: argument.to_string(),
+            file_id: file_id.to_string(),
+            sha1: file
+                .get("sha1")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            url: url.to_string(),
+        })
+    }
+
+    /// Nombre del archivo derivado con salida de consola en texto plano.
+    pub(crate) fn plain_file_id(&self) -> String {
+        match self.file_id.rsplit_once('.') {
+            Some((stem, extension)) => format!("{stem}-plain.{extension}"),
+            None => format!("{}-plain", self.file_id),
+        }
+    }
+
+    pub(crate) fn jvm_argument(&self, config_path: &str) -> String {
+        self.argument.replace("${path}", config_path)
+    }
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && name != "." && name != ".."
+}
+
+/// Sustituye el layout XML de consola por un `PatternLayout` legible.
+pub(crate) fn plain_text_logging_config(xml_config: &str) -> String {
+    XML_LAYOUT_PATTERN
+        .replace_all(xml_config, PLAIN_CONSOLE_LAYOUT)
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plain_text_logging_config, LogFormat, LoggingClientConfig};
+
+    const CLIENT_1_12_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Configuration status="WARN">
+    <Appenders>
+        <Console name="SysOut" target="SYSTEM_OUT">
+            <XMLLayout />
+        </Console>
+        <RollingRandomAccessFile name="File" fileName="logs/latest.log" filePattern="logs/%d{yyyy-MM-dd}-%i.log.gz">
+            <PatternLayout pattern="[%d{HH:mm:ss}] [%t/%level]: %msg{nolookups}%n" />
+        </RollingRandomAccessFile>
+    </Appenders>
+</Configuration>"#;
+
+    #[test]
+    fn parses_logging_client_entry_and_expands_path() {
+        let version_json = serde_json::json!({
+            "logging": {"client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "file": {
+                    "id": "client-1.12.xml",
+                    "sha1": "bd65e7d2e3c237be76cfbef4c2405033d7f91521",
+                    "size": 888,
+                    "url": "https://piston-data.mojang.com/v1/objects/bd65e7d2e3c237be76cfbef4c2405033d7f91521/client-1.12.xml"
+                },
+                "type": "log4j2-xml"
+            }}
+        });
+        let config = LoggingClientConfig::from_version_json(&version_json).expect("logging");
+        assert_eq!(config.file_id, "client-1.12.xml");
+        assert_eq!(config.plain_file_id(), "client-1.12-plain.xml");
+        assert_eq!(
+            config.jvm_argument("/game/assets/log_configs/client-1.12.xml"),
+            "-Dlog4j.configurationFile=/game/assets/log_configs/client-1.12.xml"
+        );
+
+        let traversal = serde_json::json!({
+            "logging": {"client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "file": {"id": "../evil.xml", "url": "https://example.invalid/evil.xml"}
+            }}
+        });
+        assert!(LoggingClientConfig::from_version_json(&traversal).is_none());
+        assert!(LoggingClientConfig::from_version_json(&serde_json::json!({})).is_none());
+    }
+
+    #[test]
+    fn plain_variant_replaces_console_xml_layout_only() {
+        let plain = plain_text_logging_config(CLIENT_1_12_XML);
+        assert!(!plain.contains("XMLLayout"));
+        assert_eq!(plain.matches("%msg{nolookups}").count(), 2);
+        assert_eq!(LogFormat::parse(Some("XML")), LogFormat::Xml);
+        assert_eq!(LogFormat::parse(None), LogFormat::Plain);
+    }
+}
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 use regex::Regex;
 use serde_json::Value;
 
+use crate::core::download_routes::{apply_endpoint_overrides, read_local_mirror};
+use crate::core::http_cache::HttpMetadataCache;
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct MavenCoordinate {
     pub group: String,
@@ -25,23 +28,28 @@ impl MavenCoordinate {
             return None;
         }
 
-        let (classifier, extension) = match (fourth, fifth) {
-            (None, _) => (None, "jar".to_string()),
+        let (version, classifier, extension) = match (fourth, fifth) {
+            (None, _) => match version.split_once('@') {
+                Some((version, ext)) => (version.trim(), None, normalize_extension(ext)),
+                None => (version, None, "jar".to_string()),
+            },
             (Some(raw), None) => {
                 if let Some((classifier, ext)) = raw.split_once('@') {
                     let classifier = classifier.trim();
                     (
+                        version,
                         (!classifier.is_empty()).then_some(classifier.to_string()),
                         normalize_extension(ext),
                     )
                 } else {
                     match raw {
-                        "jar" | "zip" | "pom" => (None, raw.to_string()),
-                        _ => (Some(raw.to_string()), "jar".to_string()),
+                        "jar" | "zip" | "pom" => (version, None, raw.to_string()),
+                        _ => (version, Some(raw.to_string()), "jar".to_string()),
                     }
                 }
             }
             (Some(classifier), Some(ext)) => (
+                version,
                 (!classifier.is_empty()).then_some(classifier.to_string()),
                 normalize_extension(ext),
             ),
@@ -154,7 +162,7 @@ pub fn repositories_for_library(library: &Value, declared: &[String]) -> Vec<Str
         repos.retain(|repo| !is_experimental_jetbrains_repo(repo));
     }
 
-    repos
+    apply_endpoint_overrides(repos)
 }
 
 pub fn default_repositories() -> Vec<String> {
@@ -413,19 +421,25 @@ async fn fetch_pom_model(
 
     for repo in repositories {
         let url = format!("{}/{}", repo.trim_end_matches('/'), rel);
-        let Ok(resp) = client.get(&url).send().await else {
-            continue;
-        };
-        if !resp.status().is_success() {
+        if let Some(local) = read_local_mirror(&url) {
+            if let Ok(raw) = local {
+                let text = String::from_utf8_lossy(&raw).into_owned();
+                if text.contains("<project") {
+                    pom_raw = Some(text);
+                    break;
+                }
+            }
             continue;
         }
-        let Ok(text) = resp.text().await else {
+        let cache = HttpMetadataCache::global();
+        let Ok(text) = cache.fetch_text(client.get(&url), &url).await else {
             continue;
         };
         if text.contains("<project") {
             pom_raw = Some(text);
             break;
         }
+        cache.invalidate(&url);
     }
 
     let Some(raw) = pom_raw else {
@@ -592,7 +606,10 @@ fn substitute_properties(value: &str, properties: &HashMap<String, String>) -> S
 mod tests {
     use serde_json::json;
 
-    use super::{default_repositories, parse_pom_model, repositories_for_library, MavenCoordinate};
+    use super::{
+        default_repositories, fetch_pom_model, parse_pom_model, repositories_for_library,
+        MavenCoordinate,
+    };
 
     #[test]
     fn parse_dependencies_strips_xml_comments_in_versions() {
@@ -687,4 +704,39 @@ mod tests {
             .iter()
             .any(|repo| repo == "https://repo.maven.apache.org/maven2"));
     }
+
+    #[test]
+    fn fetch_pom_model_reads_file_mirrors_without_http() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let repo = std::env::temp_dir().join(format!("frutistudio-test-pom-mirror-{unique}"));
+        let coordinate = MavenCoordinate::parse("com.example:lib:1.0.0").unwrap();
+        let pom_path = repo.join(coordinate.pom_rel_path());
+        std::fs::create_dir_all(pom_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &pom_path,
+            r#"<project><packaging>jar</packaging><dependencies><dependency>
+                <groupId>com.example</groupId><artifactId>dep</artifactId><version>2.0.0</version>
+            </dependency></dependencies></project>"#,
+        )
+        .unwrap();
+
+        let repo_url = reqwest::Url::from_directory_path(&repo)
+            .unwrap()
+            .to_string();
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let pom = rt
+            .block_on(fetch_pom_model(
+                &reqwest::Client::new(),
+                &coordinate,
+                &[repo_url],
+            ))
+            .unwrap();
+        assert_eq!(pom.dependencies.len(), 1);
+        assert_eq!(pom.dependencies[0].artifact, "dep");
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
 }
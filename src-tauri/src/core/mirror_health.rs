@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+static GLOBAL_HEALTH: Lazy<MirrorHealth> = Lazy::new(MirrorHealth::default);
+
+/// Fallos seguidos a partir de los cuales el host queda en cortocircuito.
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 3;
+const CIRCUIT_BASE_SECS: u64 = 60;
+const CIRCUIT_MAX_SECS: u64 = 15 * 60;
+/// Peso de la última muestra en las medias móviles de latencia y throughput.
+const EWMA_WEIGHT: f64 = 0.3;
+const PERSIST_INTERVAL_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HostStats {
+    pub(crate) successes: u64,
+    pub(crate) failures: u64,
+    pub(crate) consecutive_failures: u32,
+    pub(crate) avg_latency_ms: Option<f64>,
+    pub(crate) avg_throughput_bps: Option<f64>,
+    pub(crate) last_success_at: Option<u64>,
+    pub(crate) last_failure_at: Option<u64>,
+    pub(crate) circuit_open_until: Option<u64>,
+}
+
+impl HostStats {
+    fn success_rate(&self) -> f64 {
+        (self.successes as f64 + 1.0) / ((self.successes + self.failures) as f64 + 2.0)
+    }
+
+    fn circuit_open(&self, now: u64) -> bool {
+        self.circuit_open_until.is_some_and(|until| until > now)
+    }
+
+    /// Puntuación 0–100 aprox.: tasa de éxito suavizada, penalizada por latencia
+    /// y con un pequeño bono por throughput.
+    fn score(&self) -> f64 {
+        let latency_penalty = self
+            .avg_latency_ms
+            .map(|latency| (latency / 50.0).min(30.0))
+            .unwrap_or(0.0);
+        let throughput_bonus = self
+            .avg_throughput_bps
+            .map(|bps| (bps / 1024.0).max(1.0).log2().min(15.0))
+            .unwrap_or(0.0);
+        self.success_rate() * 100.0 - latency_penalty + throughput_bonus
+    }
+}
+
+fn ewma(previous: Option<f64>, sample: f64) -> f64 {
+    previous.map_or(sample, |value| {
+        value * (1.0 - EWMA_WEIGHT) + sample * EWMA_WEIGHT
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MirrorHostReport {
+    pub(crate) host: String,
+    pub(crate) score: f64,
+    pub(crate) success_rate: f64,
+    pub(crate) circuit_open: bool,
+    #[serde(flatten)]
+    pub(crate) stats: HostStats,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    hosts: HashMap<String, HostStats>,
+    store: Option<PathBuf>,
+    last_persisted: u64,
+}
+
+/// Estadísticas por host de las descargas, persistidas entre ejecuciones, que
+/// reordenan los mirrors candidatos.
+#[derive(Debug, Default)]
+pub(crate) struct MirrorHealth {
+    state: Mutex<HealthState>,
+    /// Serializa las escrituras a disco, que se hacen fuera de `state`.
+    persist_lock: Mutex<()>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+pub(crate) fn url_host(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_ascii_lowercase))
+}
+
+impl MirrorHealth {
+    pub(crate) fn global() -> &'static MirrorHealth {
+        &GLOBAL_HEALTH
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HealthState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Asocia el archivo de persistencia y carga lo guardado en ejecuciones anteriores.
+    pub(crate) fn attach_store(&self, path: PathBuf) {
+        let loaded = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<HashMap<String, HostStats>>(&raw).ok())
+            .unwrap_or_default();
+        let mut state = self.lock();
+        for (host, stats) in loaded {
+            state.hosts.entry(host).or_insert(stats);
+        }
+        state.store = Some(path);
+    }
+
+    /// Serializa el estado si toca persistir. La escritura la hace `write_snapshot` ya sin
+    /// el lock, para no bloquear las descargas con E/S.
+    fn snapshot(state: &mut HealthState, now: u64, force: bool) -> Option<(PathBuf, String)> {
+        if !force && now.saturating_sub(state.last_persisted) < PERSIST_INTERVAL_SECS {
+            return None;
+        }
+        let path = state.store.clone()?;
+        let raw = serde_json::to_string_pretty(&state.hosts).ok()?;
+        state.last_persisted = now;
+        Some((path, raw))
+    }
+
+    fn write_snapshot(&self, snapshot: Option<(PathBuf, String)>) {
+        let Some((path, raw)) = snapshot else {
+            return;
+        };
+        let _guard = self
+            .persist_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let temp = path.with_extension("json.tmp");
+        if fs::write(&temp, raw).is_ok() {
+            let _ = fs::rename(&temp, &path);
+        }
+    }
+
+    pub(crate) fn flush(&self) {
+        let snapshot = Self::snapshot(&mut self.lock(), now_secs(), true);
+        self.write_snapshot(snapshot);
+    }
+
+    /// `latency` es el tiempo hasta las cabeceras; `elapsed` el de la transferencia completa.
+    pub(crate) fn record_success(
+        &self,
+        url: &str,
+        latency: Duration,
+        bytes: u64,
+        elapsed: Duration,
+    ) {
+        self.record_success_at(url, latency, bytes, elapsed, now_secs());
+    }
+
+    fn record_success_at(
+        &self,
+        url: &str,
+        latency: Duration,
+        bytes: u64,
+        elapsed: Duration,
+        now: u64,
+    ) {
+        let Some(host) = url_host(url) else {
+            return;
+        };
+        let mut state = self.lock();
+        let stats = state.hosts.entry(host).or_default();
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.circuit_open_until = None;
+        stats.last_success_at = Some(now);
+        stats.avg_latency_ms = Some(ewma(stats.avg_latency_ms, latency.as_secs_f64() * 1000.0));
+        if bytes > 0 && !elapsed.is_zero() {
+            stats.avg_throughput_bps = Some(ewma(
+                stats.avg_throughput_bps,
+                bytes as f64 / elapsed.as_secs_f64(),
+            ));
+        }
+        let snapshot = Self::snapshot(&mut state, now, false);
+        drop(state);
+        self.write_snapshot(snapshot);
+    }
+
+    pub(crate) fn record_failure(&self, url: &str) {
+        self.record_failure_at(url, now_secs());
+    }
+
+    fn record_failure_at(&self, url: &str, now: u64) {
+        let Some(host) = url_host(url) else {
+            return;
+        };
+        let mut state = self.lock();
+        let stats = state.hosts.entry(host).or_default();
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        stats.last_failure_at = Some(now);
+        if stats.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD {
+            let exponent = (stats.consecutive_failures - CIRCUIT_FAILURE_THRESHOLD).min(8);
+            let cooldown = (CIRCUIT_BASE_SECS << exponent).min(CIRCUIT_MAX_SECS);
+            stats.circuit_open_until = Some(now + cooldown);
+        }
+        let snapshot = Self::snapshot(&mut state, now, false);
+        drop(state);
+        self.write_snapshot(snapshot);
+    }
+
+    /// Reordena candidatos por puntuación. Los hosts sin datos mantienen el orden
+    /// estático y los que están en cortocircuito pasan al final sin descartarse.
+    pub(crate) fn order(&self, urls: &[String]) -> Vec<String> {
+        self.order_at(urls, now_secs())
+    }
+
+    fn order_at(&self, urls: &[String], now: u64) -> Vec<String> {
+        let state = self.lock();
+        let neutral = HostStats::default().score();
+        let mut ranked = urls
+            .iter()
+            .map(|url| {
+                let stats = url_host(url).and_then(|host| state.hosts.get(&host));
+                let open = stats.is_some_and(|stats| stats.circuit_open(now));
+                let score = stats.map_or(neutral, HostStats::score);
+                (open, score, url.clone())
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|left, right| {
+            left.0
+                .cmp(&right.0)
+                .then_with(|| right.1.total_cmp(&left.1))
+        });
+        ranked.into_iter().map(|(_, _, url)| url).collect()
+    }
+
+    pub(crate) fn report(&self) -> Vec<MirrorHostReport> {
+        let now = now_secs();
+        let state = self.lock();
+        let mut hosts = state
+            .hosts
+            .iter()
+            .map(|(host, stats)| MirrorHostReport {
+                host: host.clone(),
+                score: stats.score(),
+                success_rate: stats.success_rate(),
+                circuit_open: stats.circuit_open(now),
+                stats: stats.clone(),
+            })
+            .collect::<Vec<_>>();
+        hosts.sort_by(|left, right| right.score.total_cmp(&left.score));
+        hosts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::MirrorHealth;
+
+    const MOJANG: &str = "https://libraries.minecraft.net/a/b/1/b-1.jar";
+    const BMCLAPI: &str = "https://bmclapi2.bangbang93.com/maven/a/b/1/b-1.jar";
+    const CENTRAL: &str = "https://repo1.maven.org/maven2/a/b/1/b-1.jar";
+
+    fn candidates() -> Vec<String> {
+        vec![MOJANG.to_string(), BMCLAPI.to_string(), CENTRAL.to_string()]
+    }
+
+    #[test]
+    fn failing_hosts_are_circuit_broken_and_fast_hosts_move_first() {
+        let health = MirrorHealth::default();
+        let now = 1_000_000;
+        assert_eq!(health.order_at(&candidates(), now), candidates());
+
+        for _ in 0..3 {
+            health.record_failure_at(MOJANG, now);
+        }
+        health.record_success_at(
+            CENTRAL,
+            Duration::from_millis(40),
+            4 << 20,
+            Duration::from_secs(1),
+            now,
+        );
+        assert_eq!(
+            health.order_at(&candidates(), now),
+            vec![CENTRAL.to_string(), BMCLAPI.to_string(), MOJANG.to_string()]
+        );
+        let report = health.report();
+        let mojang = report
+            .iter()
+            .find(|entry| entry.host == "libraries.minecraft.net")
+            .expect("mojang");
+        assert_eq!(mojang.stats.circuit_open_until, Some(now + 60));
+
+        health.record_success_at(MOJANG, Duration::from_millis(30), 0, Duration::ZERO, now);
+        assert!(!health.report().iter().any(|entry| entry.circuit_open));
+        assert_eq!(health.order_at(&candidates(), now)[2], MOJANG);
+    }
+
+    #[test]
+    fn persists_stats_between_runs() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-mirror-health-{unique}"));
+        let store = root.join("mirror-health.json");
+
+        let first = MirrorHealth::default();
+        first.attach_store(store.clone());
+        first.record_failure(BMCLAPI);
+        first.flush();
+
+        let second = MirrorHealth::default();
+        second.attach_store(store);
+        let report = second.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].host, "bmclapi2.bangbang93.com");
+        assert_eq!(report[0].stats.failures, 1);
+
+        std::fs::remove_dir_all(root).expect("cleanup");
+    }
+}
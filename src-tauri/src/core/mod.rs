@@ -1,26 +1,46 @@
+pub mod accounts;
 pub mod asset_downloader;
 pub mod auth;
+pub mod bandwidth;
 pub mod config;
+pub mod download_cache;
+pub mod download_journal;
 pub mod download_routes;
 pub mod downloader;
 pub mod external_discovery;
+pub mod forge_processors;
+pub mod http_cache;
 pub mod instance;
 pub mod instance_config;
 pub mod instance_runner;
 pub mod java;
 pub mod java_manager;
+pub mod java_probe;
 pub mod java_resolver;
 pub mod launch_pipeline;
 pub mod launcher;
 pub mod launcher_discovery;
+pub mod legacy_assets;
 pub mod loader_normalizer;
 pub mod loaders;
+pub mod logging_config;
 pub mod maven_loader;
+pub mod mirror_health;
+#[cfg(test)]
+pub(crate) mod mock_http;
 pub mod modloader_resolver;
 pub mod mods;
+pub mod mojang_runtime;
 pub mod network;
+pub mod offline_launch;
+pub mod player_data;
+pub mod progress;
+pub mod proxy;
+pub mod redaction;
 pub mod repair;
 pub mod runtime_manager;
+pub mod segmented_download;
+pub mod skins;
 pub mod validator;
 pub mod version_resolver;
 
@@ -0,0 +1,430 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use futures::{StreamExt, TryStreamExt};
+use serde_json::Value;
+
+use crate::core::config::NetworkTuning;
+use crate::core::download_routes::apply_endpoint_overrides;
+use crate::{
+    active_network_tuning, download_with_retries, fetch_json_with_fallback, java_bin_name,
+    ExpectedDigest,
+};
+
+pub(crate) const MOJANG_JAVA_RUNTIME_MANIFEST: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+const PARALLEL_FILE_DOWNLOADS: usize = 8;
+
+/// Clave de plataforma del manifest `all.json`.
+pub(crate) fn mojang_platform_key() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "x86") => Some("windows-x86"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        ("macos", "x86_64") => Some("mac-os"),
+        ("macos", "aarch64") => Some("mac-os-arm64"),
+        ("linux", "x86_64") => Some("linux"),
+        ("linux", "x86") => Some("linux-i386"),
+        _ => None,
+    }
+}
+
+/// Componente que usa el launcher oficial para cada versión mayor de Java, para cuando el
+/// `version.json` no trae `javaVersion.component`.
+pub(crate) fn component_for_java_major(java_major: u32) -> Option<&'static str> {
+    match java_major {
+        8 => Some("jre-legacy"),
+        16 => Some("java-runtime-alpha"),
+        17 => Some("java-runtime-gamma"),
+        21 => Some("java-runtime-delta"),
+        25 => Some("java-runtime-epsilon"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ComponentRelease {
+    pub(crate) manifest_url: String,
+    pub(crate) manifest_sha1: Option<String>,
+    pub(crate) version: String,
+}
+
+pub(crate) fn select_component_release(
+    all: &Value,
+    platform: &str,
+    component: &str,
+) -> Option<ComponentRelease> {
+    let release = all.get(platform)?.get(component)?.as_array()?.first()?;
+    let manifest = release.get("manifest")?;
+    Some(ComponentRelease {
+        manifest_url: manifest.get("url")?.as_str()?.to_string(),
+        manifest_sha1: manifest
+            .get("sha1")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        version: release
+            .pointer("/version/name")
+            .and_then(Value::as_str)
+            .unwrap_or(component)
+            .to_string(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RuntimeEntry {
+    Directory,
+    File {
+        url: String,
+        sha1: String,
+        size: Option<u64>,
+        executable: bool,
+    },
+    Link {
+        target: String,
+    },
+}
+
+/// Rutas relativas seguras: sin `..`, raíces ni prefijos de unidad.
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| relative.to_path_buf())
+}
+
+/// Resuelve el destino de un enlace relativo a la carpeta del enlace. `None` si es absoluto
+/// o si sus `..` salen de la raíz del runtime.
+fn link_target_within(link: &Path, target: &str) -> Option<PathBuf> {
+    let mut resolved = link.parent().map(Path::to_path_buf).unwrap_or_default();
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Entradas del manifest de un componente, ordenadas para crear carpetas antes que archivos.
+pub(crate) fn parse_component_files(
+    manifest: &Value,
+) -> Result<Vec<(PathBuf, RuntimeEntry)>, String> {
+    let files = manifest
+        .get("files")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "El manifest del runtime no contiene files".to_string())?;
+    let mut entries = Vec::with_capacity(files.len());
+    for (path, entry) in files {
+        let relative = safe_relative_path(path)
+            .ok_or_else(|| format!("Ruta insegura en manifest del runtime: {path}"))?;
+        let parsed = match entry.get("type").and_then(Value::as_str) {
+            Some("directory") => RuntimeEntry::Directory,
+            Some("file") => {
+                let raw = entry
+                    .pointer("/downloads/raw")
+                    .ok_or_else(|| format!("El archivo {path} no trae descarga raw"))?;
+                RuntimeEntry::File {
+                    url: raw
+                        .get("url")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| format!("El archivo {path} no trae url"))?
+                        .to_string(),
+                    sha1: raw
+                        .get("sha1")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| format!("El archivo {path} no trae sha1"))?
+                        .to_string(),
+                    size: raw.get("size").and_then(Value::as_u64),
+                    executable: entry
+                        .get("executable")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                }
+            }
+            Some("link") => {
+                let target = entry
+                    .get("target")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("El enlace {path} no trae target"))?;
+                if link_target_within(&relative, target).is_none() {
+                    return Err(format!(
+                        "El enlace {path} apunta fuera del runtime: {target}"
+                    ));
+                }
+                RuntimeEntry::Link {
+                    target: target.to_string(),
+                }
+            }
+            other => {
+                return Err(format!(
+                    "Tipo de entrada desconocido en runtime ({path}): {}",
+                    other.unwrap_or("ninguno")
+                ))
+            }
+        };
+        entries.push((relative, parsed));
+    }
+    entries.sort_by_key(|(path, entry)| {
+        let rank = match entry {
+            RuntimeEntry::Directory => 0,
+            RuntimeEntry::File { .. } => 1,
+            RuntimeEntry::Link { .. } => 2,
+        };
+        (rank, path.components().count())
+    });
+    Ok(entries)
+}
+
+/// Binario Java dentro de un runtime de Mojang (en macOS vive en `jre.bundle`).
+pub(crate) fn mojang_java_path(root: &Path) -> PathBuf {
+    let bundled = root
+        .join("jre.bundle")
+        .join("Contents")
+        .join("Home")
+        .join("bin")
+        .join(java_bin_name());
+    if bundled.is_file() {
+        return bundled;
+    }
+    root.join("bin").join(java_bin_name())
+}
+
+fn set_executable(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = fs::metadata(path)
+            .map_err(|error| format!("No se pudo leer permisos de {}: {error}", path.display()))?
+            .permissions();
+        permissions.set_mode(permissions.mode() | 0o755);
+        fs::set_permissions(path, permissions)
+            .map_err(|error| format!("No se pudo marcar ejecutable {}: {error}", path.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+fn create_link(path: &Path, target: &str) -> Result<(), String> {
+    if fs::symlink_metadata(path).is_ok() {
+        let _ = fs::remove_file(path);
+    }
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, path)
+            .map_err(|error| format!("No se pudo crear enlace {}: {error}", path.display()))
+    }
+    #[cfg(not(unix))]
+    {
+        // Sin permisos de symlink en Windows se copia el destino ya descargado; un enlace a
+        // carpeta o a un archivo que falta dejaría el runtime incompleto.
+        let source = path.parent().unwrap_or(Path::new(".")).join(target);
+        if !source.is_file() {
+            return Err(format!(
+                "No se pudo copiar enlace {}: {} no es un archivo descargado",
+                path.display(),
+                source.display()
+            ));
+        }
+        fs::copy(&source, path)
+            .map(|_| ())
+            .map_err(|error| format!("No se pudo copiar enlace {}: {error}", path.display()))
+    }
+}
+
+/// Última publicación de `component` para esta plataforma.
+pub(crate) async fn resolve_mojang_release(component: &str) -> Result<ComponentRelease, String> {
+    let platform = mojang_platform_key().ok_or_else(|| {
+        format!(
+            "Plataforma sin runtimes de Mojang: {}-{}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let all = fetch_json_with_fallback(
+        &apply_endpoint_overrides(vec![MOJANG_JAVA_RUNTIME_MANIFEST.to_string()]),
+        "manifest java-runtime de Mojang",
+    )
+    .await?;
+    select_component_release(&all, platform, component)
+        .ok_or_else(|| format!("Mojang no publica el runtime {component} para {platform}"))
+}
+
+/// Descarga el manifest del componente verificando el SHA-1 que publica `all.json`; un
+/// mirror con otro contenido se descarta y se prueba el siguiente.
+async fn fetch_component_manifest(
+    release: &ComponentRelease,
+    destination: &Path,
+    tuning: &NetworkTuning,
+) -> Result<Value, String> {
+    let name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "runtime".to_string());
+    let manifest_path = destination.with_file_name(format!(".{name}.manifest.json"));
+    download_with_retries(
+        &apply_endpoint_overrides(vec![release.manifest_url.clone()]),
+        &manifest_path,
+        ExpectedDigest {
+            sha1: release.manifest_sha1.as_deref(),
+            ..ExpectedDigest::default()
+        },
+        3,
+        false,
+        tuning,
+        "runtime_mojang_manifest",
+    )
+    .await?;
+    let raw = fs::read(&manifest_path);
+    let _ = fs::remove_file(&manifest_path);
+    let raw = raw.map_err(|error| format!("No se pudo leer manifest del runtime: {error}"))?;
+    serde_json::from_slice(&raw).map_err(|error| format!("Manifest del runtime inválido: {error}"))
+}
+
+/// Instala una publicación del manifest `java-runtime` de Mojang archivo por archivo, con
+/// verificación SHA-1 de cada uno, bits de ejecución y enlaces. Devuelve la ruta de Java.
+pub(crate) async fn install_mojang_release(
+    release: &ComponentRelease,
+    destination: &Path,
+) -> Result<PathBuf, String> {
+    let tuning = active_network_tuning();
+    let manifest = fetch_component_manifest(release, destination, &tuning).await?;
+    let entries = parse_component_files(&manifest)?;
+
+    fs::create_dir_all(destination)
+        .map_err(|error| format!("No se pudo crear carpeta del runtime: {error}"))?;
+
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    for (relative, entry) in entries {
+        let path = destination.join(&relative);
+        match entry {
+            RuntimeEntry::Directory => fs::create_dir_all(&path)
+                .map_err(|error| format!("No se pudo crear carpeta {}: {error}", path.display()))?,
+            RuntimeEntry::File {
+                url,
+                sha1,
+                size,
+                executable,
+            } => files.push((path, url, sha1, size, executable)),
+            RuntimeEntry::Link { target } => links.push((path, target)),
+        }
+    }
+
+    futures::stream::iter(
+        files
+            .into_iter()
+            .map(|(path, url, sha1, size, executable)| {
+                let tuning = &tuning;
+                async move {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent).map_err(|error| {
+                            format!("No se pudo crear carpeta {}: {error}", parent.display())
+                        })?;
+                    }
+                    download_with_retries(
+                        &apply_endpoint_overrides(vec![url]),
+                        &path,
+                        ExpectedDigest {
+                            sha1: Some(&sha1),
+                            size,
+                            ..ExpectedDigest::default()
+                        },
+                        3,
+                        false,
+                        tuning,
+                        "runtime_mojang",
+                    )
+                    .await?;
+                    if executable {
+                        set_executable(&path)?;
+                    }
+                    Ok::<(), String>(())
+                }
+            }),
+    )
+    .buffer_unordered(PARALLEL_FILE_DOWNLOADS)
+    .try_collect::<Vec<_>>()
+    .await?;
+
+    for (path, target) in links {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| {
+                format!("No se pudo crear carpeta {}: {error}", parent.display())
+            })?;
+        }
+        create_link(&path, &target)?;
+    }
+
+    let java_path = mojang_java_path(destination);
+    if !java_path.is_file() {
+        return Err(format!(
+            "El runtime {} de Mojang no contiene {}",
+            release.version,
+            java_path.display()
+        ));
+    }
+    Ok(java_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        component_for_java_major, parse_component_files, select_component_release, RuntimeEntry,
+    };
+
+    #[test]
+    fn picks_the_component_release_and_orders_manifest_entries() {
+        assert_eq!(component_for_java_major(17), Some("java-runtime-gamma"));
+        assert_eq!(component_for_java_major(8), Some("jre-legacy"));
+        assert_eq!(component_for_java_major(11), None);
+
+        let all = serde_json::json!({
+            "linux": {
+                "java-runtime-gamma": [{
+                    "manifest": {"sha1": "abc", "url": "https://piston-meta.mojang.com/v1/packages/abc/manifest.json"},
+                    "version": {"name": "17.0.8"}
+                }],
+                "jre-legacy": []
+            }
+        });
+        let release = select_component_release(&all, "linux", "java-runtime-gamma").expect("gamma");
+        assert_eq!(release.version, "17.0.8");
+        assert_eq!(release.manifest_sha1.as_deref(), Some("abc"));
+        assert!(select_component_release(&all, "linux", "jre-legacy").is_none());
+        assert!(select_component_release(&all, "mac-os", "java-runtime-gamma").is_none());
+
+        let manifest = serde_json::json!({"files": {
+            "bin/java": {
+                "type": "file",
+                "executable": true,
+                "downloads": {"raw": {"sha1": "1111", "size": 3, "url": "https://piston-data.mojang.com/v1/objects/1111/java"}}
+            },
+            "lib/libjli.so": {"type": "link", "target": "../jli/libjli.so"},
+            "bin": {"type": "directory"}
+        }});
+        let entries = parse_component_files(&manifest).expect("entries");
+        assert_eq!(entries[0], (PathBuf::from("bin"), RuntimeEntry::Directory));
+        assert!(matches!(
+            &entries[1],
+            (path, RuntimeEntry::File { executable: true, .. }) if path == &PathBuf::from("bin/java")
+        ));
+        assert!(matches!(entries[2].1, RuntimeEntry::Link { .. }));
+
+        let escaping = serde_json::json!({"files": {"../evil": {"type": "directory"}}});
+        assert!(parse_component_files(&escaping).is_err());
+        for target in ["../../etc/passwd", "/etc/passwd"] {
+            let link =
+                serde_json::json!({"files": {"lib/evil": {"type": "link", "target": target}}});
+            assert!(parse_component_files(&link).is_err(), "{target}");
+        }
+    }
+}
@@ -112,17 +112,6 @@ pub(crate) struct CurseforgeFileData {
     pub(crate) is_available: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct MojangVersionManifest {
-    pub(crate) versions: Vec<MojangVersionEntry>,
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct MojangVersionEntry {
-    pub(crate) id: String,
-    pub(crate) url: String,
-}
-
 #[derive(Debug, Deserialize)]
 pub(crate) struct MojangVersionDetail {
     pub(crate) downloads: MojangVersionDownloads,
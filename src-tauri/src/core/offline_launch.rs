@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::core::launcher::LaunchPlan;
+
+/// Tiempo máximo por host al comprobar si hay red antes de lanzar.
+const CONNECTIVITY_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Lanzamientos seguidos reutilizan el último sondeo de red durante este tiempo.
+const CONNECTIVITY_CACHE_TTL: Duration = Duration::from_secs(30);
+/// Entradas que se detallan en el informe; el resto sólo se cuentan.
+const MAX_REPORTED_MISSING: usize = 200;
+const ASSET_OBJECTS_BASE: &str = "https://resources.download.minecraft.net";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MissingArtifact {
+    pub(crate) kind: String,
+    pub(crate) path: String,
+    pub(crate) url: Option<String>,
+}
+
+/// Resultado de preparar un lanzamiento sólo con lo que hay en disco.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OfflineLaunchReport {
+    pub(crate) ready: bool,
+    pub(crate) checked_files: usize,
+    pub(crate) missing_total: usize,
+    pub(crate) missing: Vec<MissingArtifact>,
+    pub(crate) errors: Vec<String>,
+}
+
+impl OfflineLaunchReport {
+    fn push_missing(&mut self, kind: &str, path: &Path, url: Option<String>) {
+        self.missing_total += 1;
+        if self.missing.len() < MAX_REPORTED_MISSING {
+            self.missing.push(MissingArtifact {
+                kind: kind.to_string(),
+                path: path.to_string_lossy().to_string(),
+                url,
+            });
+        }
+    }
+
+    pub(crate) fn add_error(&mut self, error: impl Into<String>) {
+        self.errors.push(error.into());
+        self.ready = false;
+    }
+
+    /// Mensaje para el usuario cuando el lanzamiento offline no es posible.
+    pub(crate) fn summary(&self) -> String {
+        let mut parts = self.errors.clone();
+        if self.missing_total > 0 {
+            let listed = self
+                .missing
+                .iter()
+                .take(10)
+                .map(|item| format!("{} {}", item.kind, item.path))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let rest = self.missing_total.saturating_sub(10);
+            parts.push(if rest > 0 {
+                format!(
+                    "faltan {} archivos que requieren conexión: {listed} y {rest} más",
+                    self.missing_total
+                )
+            } else {
+                format!(
+                    "faltan {} archivos que requieren conexión: {listed}",
+                    self.missing_total
+                )
+            });
+        }
+        format!("Modo offline: {}", parts.join("; "))
+    }
+}
+
+fn is_non_empty_file(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.len() > 0)
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// URLs conocidas de las librerías del `version.json` persistido, por ruta local.
+fn library_urls(version_json: &Value, libraries_dir: &Path) -> HashMap<PathBuf, String> {
+    version_json
+        .get("libraries")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|library| {
+            let artifact = library.get("downloads")?.get("artifact")?;
+            let path = artifact.get("path")?.as_str()?;
+            let url = artifact.get("url")?.as_str()?;
+            Some((libraries_dir.join(path), url.to_string()))
+        })
+        .collect()
+}
+
+/// `versions/<id>/<id>.jar`: el jar del cliente dentro del classpath.
+fn is_client_jar(path: &Path) -> bool {
+    let parent = path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .and_then(|name| name.to_str());
+    path.extension().is_some_and(|ext| ext == "jar")
+        && parent.is_some()
+        && parent == path.file_stem().and_then(|stem| stem.to_str())
+}
+
+/// Comprueba sólo en disco todo lo que el plan necesita para arrancar: Java, `version.json`,
+/// classpath, natives, índice de assets y objetos. No hashea; basta con existencia y tamaño.
+pub(crate) fn verify_offline_launch(plan: &LaunchPlan) -> OfflineLaunchReport {
+    let mut report = OfflineLaunchReport::default();
+
+    let java = Path::new(&plan.java_path);
+    if java.components().count() > 1 {
+        report.checked_files += 1;
+        if !java.is_file() {
+            report.push_missing("java", java, None);
+        }
+    }
+
+    let version_json_path = Path::new(&plan.version_json);
+    report.checked_files += 1;
+    let version_json = read_json(version_json_path);
+    if version_json.is_none() {
+        report.push_missing("version_json", version_json_path, None);
+    }
+    let version_json = version_json.unwrap_or(Value::Null);
+    let libraries = library_urls(&version_json, Path::new(&plan.libraries_dir));
+    let client_url = version_json
+        .pointer("/downloads/client/url")
+        .and_then(Value::as_str);
+
+    for entry in &plan.classpath_entries {
+        let path = Path::new(entry);
+        report.checked_files += 1;
+        if is_non_empty_file(path) {
+            continue;
+        }
+        match libraries.get(path) {
+            Some(url) => report.push_missing("library", path, Some(url.clone())),
+            None if is_client_jar(path) => {
+                report.push_missing("client_jar", path, client_url.map(str::to_string))
+            }
+            None => report.push_missing("library", path, None),
+        }
+    }
+
+    let natives = Path::new(&plan.natives_dir);
+    if !plan.natives_dir.trim().is_empty() && !natives.is_dir() {
+        report.push_missing("natives", natives, None);
+    }
+
+    if !plan.asset_index.trim().is_empty() {
+        let assets_dir = Path::new(&plan.assets_dir);
+        let index_path = assets_dir
+            .join("indexes")
+            .join(format!("{}.json", plan.asset_index));
+        report.checked_files += 1;
+        match read_json(&index_path) {
+            Some(index) => {
+                let objects = index
+                    .get("objects")
+                    .and_then(Value::as_object)
+                    .into_iter()
+                    .flatten();
+                for (_, object) in objects {
+                    let Some(hash) = object.get("hash").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    if hash.len() < 2 {
+                        continue;
+                    }
+                    let object_path = assets_dir.join("objects").join(&hash[..2]).join(hash);
+                    let size = object.get("size").and_then(Value::as_u64);
+                    report.checked_files += 1;
+                    let present = fs::metadata(&object_path).is_ok_and(|meta| {
+                        meta.is_file() && size.is_none_or(|size| size == meta.len())
+                    });
+                    if !present {
+                        report.push_missing(
+                            "asset",
+                            &object_path,
+                            Some(format!("{ASSET_OBJECTS_BASE}/{}/{hash}", &hash[..2])),
+                        );
+                    }
+                }
+            }
+            None => {
+                let url = version_json
+                    .pointer("/assetIndex/url")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                report.push_missing("asset_index", &index_path, url);
+            }
+        }
+    }
+
+    report.ready = report.missing_total == 0 && report.errors.is_empty();
+    report
+}
+
+static LAST_CONNECTIVITY: Lazy<Mutex<Option<(Instant, bool)>>> = Lazy::new(|| Mutex::new(None));
+
+/// `true` si algún endpoint HTTP responde, con cualquier código. Los mirrors `file://`
+/// no cuentan como red. Se sondean todos a la vez y gana el primero que conteste.
+pub(crate) async fn network_reachable(client: &reqwest::Client, urls: &[String]) -> bool {
+    let cached = *LAST_CONNECTIVITY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((checked_at, reachable)) = cached {
+        if checked_at.elapsed() < CONNECTIVITY_CACHE_TTL {
+            return reachable;
+        }
+    }
+
+    let probes = urls
+        .iter()
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .map(|url| {
+            Box::pin(async move {
+                client
+                    .head(url)
+                    .timeout(CONNECTIVITY_PROBE_TIMEOUT)
+                    .send()
+                    .await
+                    .map(|_| ())
+            })
+        })
+        .collect::<Vec<_>>();
+    let reachable = !probes.is_empty() && futures::future::select_ok(probes).await.is_ok();
+    *LAST_CONNECTIVITY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((Instant::now(), reachable));
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{network_reachable, verify_offline_launch};
+    use crate::core::launcher::{LaunchAuth, LaunchPlan};
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    #[test]
+    fn reports_missing_libraries_and_assets_with_their_download_urls() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-offline-launch-{unique}"));
+        let libraries = root.join("libraries");
+        let assets = root.join("assets");
+        let natives = root.join("natives");
+        let version_dir = root.join("versions").join("1.20.1");
+        fs::create_dir_all(libraries.join("org/present/1.0")).expect("libraries");
+        fs::create_dir_all(assets.join("indexes")).expect("indexes");
+        fs::create_dir_all(assets.join("objects").join("aa")).expect("objects");
+        fs::create_dir_all(&natives).expect("natives");
+        fs::create_dir_all(&version_dir).expect("version dir");
+
+        let present = libraries.join("org/present/1.0/present-1.0.jar");
+        let absent = libraries.join("org/absent/2.0/absent-2.0.jar");
+        let client = version_dir.join("1.20.1.jar");
+        fs::write(&present, b"jar").expect("present");
+        let version_json = root.join("version.json");
+        fs::write(
+            &version_json,
+            serde_json::json!({
+                "downloads": {"client": {"url": "https://piston-data.mojang.com/client.jar"}},
+                "libraries": [{
+                    "name": "org:absent:2.0",
+                    "downloads": {"artifact": {
+                        "path": "org/absent/2.0/absent-2.0.jar",
+                        "url": "https://libraries.minecraft.net/org/absent/2.0/absent-2.0.jar"
+                    }}
+                }]
+            })
+            .to_string(),
+        )
+        .expect("version json");
+        fs::write(
+            assets.join("indexes").join("5.json"),
+            serde_json::json!({"objects": {
+                "a": {"hash": "aa11", "size": 5},
+                "b": {"hash": "bb22", "size": 3}
+            }})
+            .to_string(),
+        )
+        .expect("index");
+        fs::write(assets.join("objects").join("aa").join("aa11"), b"asset").expect("asset");
+
+        let plan = LaunchPlan {
+            java_path: "java".to_string(),
+            java_args: Vec::new(),
+            game_args: Vec::new(),
+            main_class: "net.minecraft.client.main.Main".to_string(),
+            classpath_entries: vec![
+                present.to_string_lossy().to_string(),
+                absent.to_string_lossy().to_string(),
+                client.to_string_lossy().to_string(),
+            ],
+            classpath_separator: ":".to_string(),
+            game_dir: root.to_string_lossy().to_string(),
+            assets_dir: assets.to_string_lossy().to_string(),
+            libraries_dir: libraries.to_string_lossy().to_string(),
+            natives_dir: natives.to_string_lossy().to_string(),
+            version_json: version_json.to_string_lossy().to_string(),
+            asset_index: "5".to_string(),
+            required_java_major: 17,
+            resolved_java_major: 17,
+            loader: "vanilla".to_string(),
+            loader_profile_resolved: true,
+            legacy_arguments: false,
+            auth: LaunchAuth {
+                username: "Steve".to_string(),
+                uuid: "0".to_string(),
+                access_token: "0".to_string(),
+                user_type: "offline".to_string(),
+            },
+            env: HashMap::new(),
+        };
+
+        let report = verify_offline_launch(&plan);
+        assert!(!report.ready);
+        let kinds = report
+            .missing
+            .iter()
+            .map(|item| (item.kind.as_str(), item.url.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    "library",
+                    Some("https://libraries.minecraft.net/org/absent/2.0/absent-2.0.jar")
+                ),
+                (
+                    "client_jar",
+                    Some("https://piston-data.mojang.com/client.jar")
+                ),
+                (
+                    "asset",
+                    Some("https://resources.download.minecraft.net/bb/bb22")
+                ),
+            ]
+        );
+        assert!(report.summary().contains("faltan 3 archivos"));
+
+        fs::create_dir_all(absent.parent().expect("parent")).expect("absent dir");
+        fs::write(&absent, b"jar").expect("absent");
+        fs::write(&client, b"client").expect("client");
+        fs::create_dir_all(assets.join("objects").join("bb")).expect("objects bb");
+        fs::write(assets.join("objects").join("bb").join("bb22"), b"bbb").expect("asset bb");
+        assert!(verify_offline_launch(&plan).ready);
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn any_http_answer_counts_as_network_and_is_cached_briefly() {
+        let base = spawn_mock_server(|_| MockResponse {
+            status: 404,
+            headers: Vec::new(),
+            body: Vec::new(),
+        });
+        let client = reqwest::Client::new();
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        rt.block_on(async {
+            let urls = vec![
+                "file:///mirror".to_string(),
+                "http://127.0.0.1:9/cerrado".to_string(),
+                format!("{base}/manifest.json"),
+            ];
+            assert!(network_reachable(&client, &urls).await);
+            // Dentro del TTL no se vuelve a sondear.
+            assert!(network_reachable(&client, &["http://127.0.0.1:9/".to_string()]).await);
+        });
+    }
+}
@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+/// Carpetas por mundo donde vanilla guarda datos indexados por UUID del jugador.
+const PLAYER_DATA_DIRS: &[&str] = &["playerdata", "stats", "advancements"];
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PlayerDataMigrationReport {
+    pub(crate) username: String,
+    pub(crate) uuid: String,
+    pub(crate) renamed: Vec<String>,
+    pub(crate) skipped: Vec<String>,
+}
+
+/// UUID v3 de `OfflinePlayer:<name>`, el mismo que derivan vanilla y los servidores offline.
+pub(crate) fn offline_player_uuid(username: &str) -> String {
+    let mut hash = md5::compute(format!("OfflinePlayer:{username}")).0;
+    hash[6] = (hash[6] & 0x0f) | 0x30;
+    hash[8] = (hash[8] & 0x3f) | 0x80;
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub(crate) fn hyphenate_uuid(compact: &str) -> String {
+    if compact.len() != 32 {
+        return compact.to_string();
+    }
+    format!(
+        "{}-{}-{}-{}-{}",
+        &compact[0..8],
+        &compact[8..12],
+        &compact[12..16],
+        &compact[16..20],
+        &compact[20..32]
+    )
+}
+
+fn split_uuid_file_name(file_name: &str) -> Option<(String, &str)> {
+    let (stem, extension) = file_name.split_once('.')?;
+    let compact = stem.replace('-', "").to_ascii_lowercase();
+    if compact.len() == 32 && compact.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        Some((compact, extension))
+    } else {
+        None
+    }
+}
+
+fn modified_at(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Renombra los datos guardados con UUIDs offline antiguos al UUID estable.
+/// `is_legacy` decide qué UUIDs (en formato compacto) pertenecen al jugador.
+/// Si hay varios candidatos por extensión se conserva el más reciente; nunca se
+/// sobrescribe un archivo que ya exista con el UUID estable.
+pub(crate) fn migrate_player_data(
+    game_dir: &Path,
+    target_uuid: &str,
+    is_legacy: impl Fn(&str) -> bool,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut renamed = Vec::new();
+    let mut skipped = Vec::new();
+    let target_file_stem = hyphenate_uuid(target_uuid);
+
+    let saves_dir = game_dir.join("saves");
+    let Ok(worlds) = fs::read_dir(&saves_dir) else {
+        return Ok((renamed, skipped));
+    };
+
+    for world in worlds.flatten() {
+        for data_dir_name in PLAYER_DATA_DIRS {
+            let data_dir = world.path().join(data_dir_name);
+            let Ok(entries) = fs::read_dir(&data_dir) else {
+                continue;
+            };
+
+            let mut candidates: Vec<(String, PathBuf)> = Vec::new();
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let Some((compact, extension)) = split_uuid_file_name(&file_name) else {
+                    continue;
+                };
+                if compact == target_uuid || !is_legacy(&compact) {
+                    continue;
+                }
+                candidates.push((extension.to_string(), path));
+            }
+            candidates.sort_by_key(|(_, path)| std::cmp::Reverse(modified_at(path)));
+
+            for (extension, source) in candidates {
+                let destination = data_dir.join(format!("{target_file_stem}.{extension}"));
+                if destination.exists() {
+                    skipped.push(source.to_string_lossy().to_string());
+                    continue;
+                }
+                fs::rename(&source, &destination).map_err(|error| {
+                    format!(
+                        "No se pudo renombrar {} a {}: {error}",
+                        source.display(),
+                        destination.display()
+                    )
+                })?;
+                renamed.push(format!(
+                    "{} -> {}",
+                    source.to_string_lossy(),
+                    destination.to_string_lossy()
+                ));
+            }
+        }
+    }
+
+    Ok((renamed, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{hyphenate_uuid, migrate_player_data, offline_player_uuid};
+
+    #[test]
+    fn offline_uuid_matches_vanilla_name_based_uuid() {
+        assert_eq!(
+            hyphenate_uuid(&offline_player_uuid("Notch")),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_eq!(offline_player_uuid("Steve"), offline_player_uuid("Steve"));
+        assert_ne!(offline_player_uuid("Steve"), offline_player_uuid("steve"));
+    }
+
+    #[test]
+    fn migrates_legacy_files_without_overwriting_stable_ones() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let game_dir = std::env::temp_dir().join(format!("frutistudio-test-playerdata-{unique}"));
+        let world = game_dir.join("saves").join("Mundo");
+        fs::create_dir_all(world.join("playerdata")).expect("playerdata");
+        fs::create_dir_all(world.join("stats")).expect("stats");
+
+        let target = offline_player_uuid("Steve");
+        let legacy = "0123456789abcdef0000000000000001";
+        let unrelated = "ffffffffffffffffffffffffffffffff";
+        fs::write(
+            world
+                .join("playerdata")
+                .join(format!("{}.dat", hyphenate_uuid(legacy))),
+            b"inv",
+        )
+        .expect("legacy dat");
+        fs::write(
+            world
+                .join("playerdata")
+                .join(format!("{}.dat", hyphenate_uuid(unrelated))),
+            b"other",
+        )
+        .expect("unrelated dat");
+        fs::write(
+            world
+                .join("stats")
+                .join(format!("{}.json", hyphenate_uuid(legacy))),
+            b"{}",
+        )
+        .expect("legacy stats");
+        fs::write(
+            world
+                .join("stats")
+                .join(format!("{}.json", hyphenate_uuid(&target))),
+            b"{\"kept\":true}",
+        )
+        .expect("stable stats");
+
+        let (renamed, skipped) = migrate_player_data(&game_dir, &target, |uuid| {
+            uuid.starts_with("0123456789abcdef")
+        })
+        .expect("migrate");
+
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            fs::read(
+                world
+                    .join("playerdata")
+                    .join(format!("{}.dat", hyphenate_uuid(&target)))
+            )
+            .expect("migrated dat"),
+            b"inv"
+        );
+        assert!(world
+            .join("playerdata")
+            .join(format!("{}.dat", hyphenate_uuid(unrelated)))
+            .exists());
+        assert_eq!(
+            fs::read_to_string(
+                world
+                    .join("stats")
+                    .join(format!("{}.json", hyphenate_uuid(&target)))
+            )
+            .expect("stable stats"),
+            "{\"kept\":true}"
+        );
+
+        fs::remove_dir_all(game_dir).expect("cleanup");
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::core::LaunchOrchestrationOrder;
+
+/// Evento Tauri con el avance del bootstrap de una instancia.
+pub(crate) const BOOTSTRAP_PROGRESS_EVENT: &str = "instance-bootstrap-progress";
+
+/// Intervalo mínimo entre eventos para no saturar el puente IPC con miles de assets.
+const DEFAULT_EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BootstrapProgress {
+    pub(crate) instance_id: String,
+    pub(crate) step: String,
+    pub(crate) step_index: usize,
+    pub(crate) step_count: usize,
+    pub(crate) files_done: u64,
+    pub(crate) files_total: u64,
+    pub(crate) bytes_done: u64,
+    pub(crate) bytes_total: Option<u64>,
+    pub(crate) current_url: Option<String>,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) retries: u32,
+}
+
+pub(crate) type ProgressSink = Arc<dyn Fn(BootstrapProgress) + Send + Sync>;
+
+struct ReporterState {
+    progress: BootstrapProgress,
+    last_emit: Option<Instant>,
+}
+
+/// Acumula el progreso de una etapa y lo emite con throttling.
+/// Los cambios de etapa y el último archivo siempre se emiten.
+pub(crate) struct ProgressReporter {
+    sink: ProgressSink,
+    steps: Vec<String>,
+    interval: Duration,
+    state: Mutex<ReporterState>,
+}
+
+impl ProgressReporter {
+    pub(crate) fn new(instance_id: &str, sink: ProgressSink) -> Self {
+        let steps = LaunchOrchestrationOrder::default().steps;
+        Self {
+            sink,
+            state: Mutex::new(ReporterState {
+                progress: BootstrapProgress {
+                    instance_id: instance_id.to_string(),
+                    step_count: steps.len(),
+                    ..BootstrapProgress::default()
+                },
+                last_emit: None,
+            }),
+            steps,
+            interval: DEFAULT_EMIT_INTERVAL,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ReporterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn emit(&self, state: &mut ReporterState, force: bool) {
+        let due = state
+            .last_emit
+            .is_none_or(|last| last.elapsed() >= self.interval);
+        if force || due {
+            state.last_emit = Some(Instant::now());
+            (self.sink)(state.progress.clone());
+        }
+    }
+
+    /// Inicia un paso de `LaunchOrchestrationOrder` y reinicia los contadores.
+    pub(crate) fn begin_step(&self, step: &str, files_total: u64, bytes_total: Option<u64>) {
+        let mut state = self.lock();
+        let progress = &mut state.progress;
+        progress.step = step.to_string();
+        progress.step_index = self
+            .steps
+            .iter()
+            .position(|candidate| candidate == step)
+            .unwrap_or(progress.step_index);
+        progress.files_done = 0;
+        progress.files_total = files_total;
+        progress.bytes_done = 0;
+        progress.bytes_total = bytes_total.filter(|total| *total > 0);
+        progress.current_url = None;
+        progress.endpoint = None;
+        progress.retries = 0;
+        self.emit(&mut state, true);
+    }
+
+    /// Registra un intento contra `url`; a partir del segundo cuenta como reintento.
+    pub(crate) fn attempt(&self, url: &str, endpoint: &str, is_retry: bool) {
+        let mut state = self.lock();
+        state.progress.current_url = Some(url.to_string());
+        state.progress.endpoint = Some(endpoint.to_string());
+        if is_retry {
+            state.progress.retries += 1;
+        }
+        self.emit(&mut state, false);
+    }
+
+    pub(crate) fn add_bytes(&self, bytes: u64) {
+        let mut state = self.lock();
+        state.progress.bytes_done = state.progress.bytes_done.saturating_add(bytes);
+        self.emit(&mut state, false);
+    }
+
+    /// Descuenta bytes ya informados de un intento cuyo temporal se descartó.
+    pub(crate) fn discard_bytes(&self, bytes: u64) {
+        let mut state = self.lock();
+        state.progress.bytes_done = state.progress.bytes_done.saturating_sub(bytes);
+        self.emit(&mut state, false);
+    }
+
+    pub(crate) fn file_done(&self) {
+        let mut state = self.lock();
+        state.progress.files_done += 1;
+        let finished = state.progress.files_done >= state.progress.files_total;
+        self.emit(&mut state, finished);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{BootstrapProgress, ProgressReporter};
+
+    #[test]
+    fn throttles_byte_updates_but_always_emits_step_changes_and_completion() {
+        let events = Arc::new(Mutex::new(Vec::<BootstrapProgress>::new()));
+        let sink_events = events.clone();
+        let reporter = ProgressReporter::new(
+            "instance-1",
+            Arc::new(move |event| sink_events.lock().expect("events").push(event)),
+        )
+        .with_interval(Duration::from_secs(3600));
+
+        reporter.begin_step("download_assets", 2, Some(30));
+        reporter.attempt(
+            "https://resources.download.minecraft.net/aa/aa11",
+            "mojang",
+            false,
+        );
+        reporter.add_bytes(10);
+        reporter.add_bytes(7);
+        reporter.discard_bytes(7);
+        reporter.attempt(
+            "https://bmclapi2.bangbang93.com/assets/aa/aa11",
+            "bmclapi",
+            true,
+        );
+        reporter.add_bytes(20);
+        reporter.file_done();
+        assert_eq!(events.lock().expect("events").len(), 1);
+
+        reporter.file_done();
+        let events = events.lock().expect("events");
+        assert_eq!(events.len(), 2);
+        let last = events.last().expect("last");
+        assert_eq!(last.step, "download_assets");
+        assert_eq!(last.step_index, 6);
+        assert_eq!((last.files_done, last.files_total), (2, 2));
+        assert_eq!((last.bytes_done, last.bytes_total), (30, Some(30)));
+        assert_eq!(last.endpoint.as_deref(), Some("bmclapi"));
+        assert_eq!(last.retries, 1);
+    }
+}
@@ -0,0 +1,102 @@
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+
+use crate::core::config::ProxyConfig;
+
+const SUPPORTED_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn build_proxy(config: &ProxyConfig, url: &str) -> Result<Proxy, String> {
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase())
+        .ok_or_else(|| format!("URL de proxy sin esquema: {url}"))?;
+    if !SUPPORTED_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!(
+            "Esquema de proxy no soportado: {scheme} (usa http, https, socks5 o socks5h)"
+        ));
+    }
+
+    let mut proxy =
+        Proxy::all(url).map_err(|error| format!("URL de proxy inválida {url}: {error}"))?;
+    if let Some(username) = non_empty(config.username.as_deref()) {
+        proxy = proxy.basic_auth(username, config.password.as_deref().unwrap_or_default());
+    }
+    let no_proxy = config
+        .no_proxy
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(proxy.no_proxy(NoProxy::from_string(&no_proxy)))
+}
+
+/// Aplica el proxy configurado. Sin URL explícita, `reqwest` toma `HTTP(S)_PROXY`/`NO_PROXY`
+/// del entorno salvo que `use_environment` sea `false`.
+pub(crate) fn configure_client_proxy(
+    builder: ClientBuilder,
+    config: Option<&ProxyConfig>,
+) -> Result<ClientBuilder, String> {
+    let Some(config) = config else {
+        return Ok(builder);
+    };
+    match non_empty(config.url.as_deref()) {
+        Some(url) => Ok(builder.proxy(build_proxy(config, url)?)),
+        None if config.use_environment.unwrap_or(true) => Ok(builder),
+        None => Ok(builder.no_proxy()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::configure_client_proxy;
+    use crate::core::config::ProxyConfig;
+
+    fn proxy(url: &str) -> ProxyConfig {
+        ProxyConfig {
+            url: Some(url.to_string()),
+            username: Some("lan".to_string()),
+            password: Some("secret".to_string()),
+            password_sealed: None,
+            no_proxy: vec!["localhost".to_string(), "192.168.0.0/16".to_string()],
+            use_environment: None,
+        }
+    }
+
+    #[test]
+    fn accepts_http_and_socks_proxies_and_rejects_unknown_schemes() {
+        for url in [
+            "http://proxy.lan:3128",
+            "https://proxy.lan:3129",
+            "socks5://proxy.lan:1080",
+            "socks5h://proxy.lan:1080",
+        ] {
+            let builder = configure_client_proxy(reqwest::Client::builder(), Some(&proxy(url)))
+                .expect("proxy soportado");
+            builder.build().expect("cliente");
+        }
+
+        let error = configure_client_proxy(
+            reqwest::Client::builder(),
+            Some(&proxy("ftp://proxy.lan:21")),
+        )
+        .expect_err("esquema no soportado");
+        assert!(error.contains("ftp"));
+        assert!(
+            configure_client_proxy(reqwest::Client::builder(), Some(&proxy("proxy.lan:3128")))
+                .is_err()
+        );
+
+        let direct = ProxyConfig {
+            use_environment: Some(false),
+            ..ProxyConfig::default()
+        };
+        configure_client_proxy(reqwest::Client::builder(), Some(&direct))
+            .expect("sin proxy")
+            .build()
+            .expect("cliente");
+    }
+}
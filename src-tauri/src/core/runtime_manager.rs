@@ -1,106 +1,997 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tar::Archive;
 
-use crate::core::config::NetworkTuning;
-use crate::{download_with_retries, java_bin_name, launcher_root};
+use crate::core::download_routes::{apply_endpoint_overrides, read_local_mirror};
+use crate::core::java_probe::probe_java;
+use crate::core::mojang_runtime::{
+    component_for_java_major, install_mojang_release, resolve_mojang_release,
+};
+use crate::{
+    active_network_tuning, compare_numeric_versions, download_with_progress, file_sha1,
+    file_sha256, http_client_with_tuning, java_bin_name, launcher_root, ExpectedDigest,
+};
 
 const ADOPTIUM_RELEASES: &str = "https://api.adoptium.net/v3/assets/latest";
+/// Metadata de cada runtime gestionado, en la raíz de `runtime/<vendor>-<major>-<versión>`.
+pub(crate) const RUNTIME_METADATA_FILE: &str = "runtime.json";
+/// Las instalaciones se preparan aquí y se mueven a su carpeta final sólo si terminan bien.
+const RUNTIME_STAGING_DIR: &str = ".staging";
+/// Hash y tamaño de cada archivo instalado, para detectar runtimes alterados o incompletos.
+pub(crate) const RUNTIME_MANIFEST_FILE: &str = "runtime-manifest.json";
+/// Paquetes con checksum inválido y runtimes alterados se apartan aquí en vez de borrarse.
+const RUNTIME_QUARANTINE_DIR: &str = ".quarantine";
+/// Entradas más recientes que se conservan en cuarentena; las anteriores se borran.
+const RUNTIME_QUARANTINE_KEEP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RuntimeProvider {
+    Mojang,
+    Adoptium,
+}
+
+impl RuntimeProvider {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "mojang" => Some(Self::Mojang),
+            "adoptium" | "temurin" => Some(Self::Adoptium),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Mojang => "mojang",
+            Self::Adoptium => "adoptium",
+        }
+    }
+}
+
+const DEFAULT_RUNTIME_PROVIDERS: [RuntimeProvider; 2] =
+    [RuntimeProvider::Mojang, RuntimeProvider::Adoptium];
+
+static RUNTIME_PROVIDERS: Lazy<RwLock<Vec<RuntimeProvider>>> =
+    Lazy::new(|| RwLock::new(DEFAULT_RUNTIME_PROVIDERS.to_vec()));
+
+fn resolve_runtime_providers(order: Option<&[String]>) -> Vec<RuntimeProvider> {
+    let mut providers = Vec::new();
+    for provider in order
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| RuntimeProvider::parse(value))
+    {
+        if !providers.contains(&provider) {
+            providers.push(provider);
+        }
+    }
+    if providers.is_empty() {
+        return DEFAULT_RUNTIME_PROVIDERS.to_vec();
+    }
+    // Adoptium queda siempre como último recurso aunque no se haya listado.
+    if !providers.contains(&RuntimeProvider::Adoptium) {
+        providers.push(RuntimeProvider::Adoptium);
+    }
+    providers
+}
+
+/// Sustituye el orden de proveedores configurado en `AppConfig`.
+pub(crate) fn configure_runtime_providers(order: Option<&[String]>) {
+    *RUNTIME_PROVIDERS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = resolve_runtime_providers(order);
+}
+
+/// Un lock por id de runtime: dos instalaciones del mismo runtime compartirían `.staging/<id>`.
+static RUNTIME_INSTALL_LOCKS: Lazy<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn runtime_install_lock(runtime_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+    RUNTIME_INSTALL_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(runtime_id.to_string())
+        .or_default()
+        .clone()
+}
+
+fn runtime_providers() -> Vec<RuntimeProvider> {
+    RUNTIME_PROVIDERS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManagedRuntimeMetadata {
+    pub(crate) vendor: String,
+    pub(crate) major: u32,
+    pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) component: Option<String>,
+    /// Ruta de Java relativa a la carpeta del runtime.
+    pub(crate) java_path: String,
+    #[serde(default)]
+    pub(crate) installed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManagedRuntimeInfo {
+    pub(crate) id: String,
+    pub(crate) vendor: String,
+    pub(crate) major: u32,
+    pub(crate) version: String,
+    pub(crate) component: Option<String>,
+    pub(crate) path: String,
+    pub(crate) java_path: String,
+    pub(crate) size_bytes: u64,
+    /// Instancias cuyo plan de lanzamiento usa este runtime.
+    pub(crate) referenced_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuntimeUpdateInfo {
+    pub(crate) runtime_id: String,
+    pub(crate) major: u32,
+    pub(crate) current_version: String,
+    pub(crate) latest_version: String,
+    pub(crate) update_available: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManagedRuntimeArgs {
+    #[serde(default, alias = "runtime_id", alias = "id")]
+    pub(crate) runtime_id: Option<String>,
+    #[serde(default)]
+    pub(crate) major: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ManagedRuntimeIntegrity {
+    pub(crate) runtime_id: String,
+    pub(crate) checked_files: usize,
+    pub(crate) missing: Vec<String>,
+    pub(crate) modified: Vec<String>,
+    /// Carpeta de cuarentena a la que se movió el runtime si no coincidía con su manifest.
+    pub(crate) quarantined_to: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RuntimeFileEntry {
+    sha1: String,
+    size: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RuntimeFileManifest {
+    files: BTreeMap<String, RuntimeFileEntry>,
+}
+
+struct AdoptiumPackage {
+    link: String,
+    version: String,
+    /// SHA-256 del paquete tal como lo publica la API.
+    checksum: Option<String>,
+    size: Option<u64>,
+}
+
+/// Nombre de carpeta `<vendor>-<major>-<versión>` con la versión saneada para cualquier FS.
+pub(crate) fn runtime_dir_name(vendor: &str, major: u32, version: &str) -> String {
+    let version = version
+        .trim()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '+' | '-' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{vendor}-{major}-{version}")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn collect_runtime_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<String, RuntimeFileEntry>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|error| format!("No se pudo leer carpeta {}: {error}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Los enlaces simbólicos se recrean desde su destino; sólo se registran archivos reales.
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => collect_runtime_files(root, &path, files)?,
+            Ok(kind) if kind.is_file() => {
+                let Ok(relative) = path.strip_prefix(root) else {
+                    continue;
+                };
+                let relative = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if relative == RUNTIME_METADATA_FILE || relative == RUNTIME_MANIFEST_FILE {
+                    continue;
+                }
+                let size = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+                files.insert(
+                    relative,
+                    RuntimeFileEntry {
+                        sha1: file_sha1(&path)?,
+                        size,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn build_runtime_manifest(root: &Path) -> Result<RuntimeFileManifest, String> {
+    let mut manifest = RuntimeFileManifest::default();
+    collect_runtime_files(root, root, &mut manifest.files)?;
+    Ok(manifest)
+}
+
+fn read_runtime_manifest(root: &Path) -> Option<RuntimeFileManifest> {
+    let raw = fs::read_to_string(root.join(RUNTIME_MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Comprobación barata por existencia y tamaño. Los runtimes instalados antes de que
+/// existiera el manifest se dan por buenos.
+fn runtime_files_present(root: &Path) -> bool {
+    read_runtime_manifest(root).is_none_or(|manifest| {
+        manifest.files.iter().all(|(relative, entry)| {
+            fs::metadata(root.join(relative))
+                .is_ok_and(|meta| meta.is_file() && meta.len() == entry.size)
+        })
+    })
+}
+
+/// Rehashea cada archivo del manifest y devuelve `(faltantes, modificados)`.
+fn verify_runtime_files(root: &Path, manifest: &RuntimeFileManifest) -> (Vec<String>, Vec<String>) {
+    let mut missing = Vec::new();
+    let mut modified = Vec::new();
+    for (relative, entry) in &manifest.files {
+        let path = root.join(relative);
+        if !path.is_file() {
+            missing.push(relative.clone());
+            continue;
+        }
+        let intact = fs::metadata(&path).is_ok_and(|meta| meta.len() == entry.size)
+            && file_sha1(&path).is_ok_and(|hash| hash.eq_ignore_ascii_case(&entry.sha1));
+        if !intact {
+            modified.push(relative.clone());
+        }
+    }
+    (missing, modified)
+}
 
 pub(crate) struct RuntimeManager {
     runtime_root: PathBuf,
+    instances_root: PathBuf,
 }
 
 impl RuntimeManager {
     pub(crate) fn new(app: &tauri::AppHandle) -> Result<Self, String> {
-        Ok(Self {
-            runtime_root: launcher_root(app)?.join("runtime"),
-        })
+        let root = launcher_root(app)?;
+        Ok(Self::with_roots(
+            root.join("runtime"),
+            root.join("instances"),
+        ))
+    }
+
+    fn with_roots(runtime_root: PathBuf, instances_root: PathBuf) -> Self {
+        Self {
+            runtime_root,
+            instances_root,
+        }
     }
 
     pub(crate) async fn ensure_runtime_for_java_major(
         &self,
         java_major: u32,
     ) -> Result<PathBuf, String> {
-        let runtime_id = runtime_folder_name(java_major);
-        let destination_root = self.runtime_root.join(runtime_id);
-        let java_path = destination_root.join("bin").join(java_bin_name());
-        if java_path.is_file() {
+        self.ensure_runtime(java_major, None).await
+    }
+
+    /// `component` es el `javaVersion.component` del `version.json`; si falta se deduce de
+    /// la versión mayor. Primero se reutiliza un runtime ya instalado de esa versión mayor y
+    /// luego se prueban los proveedores en el orden configurado.
+    pub(crate) async fn ensure_runtime(
+        &self,
+        java_major: u32,
+        component: Option<&str>,
+    ) -> Result<PathBuf, String> {
+        let component = component
+            .map(str::trim)
+            .filter(|component| !component.is_empty())
+            .or_else(|| component_for_java_major(java_major));
+        let providers = runtime_providers();
+        if let Some(java_path) = self.installed_runtime(java_major, &providers) {
             return Ok(java_path);
         }
 
-        fs::create_dir_all(&self.runtime_root)
-            .map_err(|error| format!("No se pudo crear carpeta runtime: {error}"))?;
+        let mut errors = Vec::new();
+        for provider in providers {
+            let result = match (provider, component) {
+                (RuntimeProvider::Mojang, Some(component)) => {
+                    self.install_mojang_component(java_major, component).await
+                }
+                (RuntimeProvider::Mojang, None) => {
+                    Err(format!("Mojang no tiene componente para Java {java_major}"))
+                }
+                (RuntimeProvider::Adoptium, _) => self
+                    .install_adoptium_runtime(java_major)
+                    .await
+                    .map(|(_, java_path)| java_path),
+            };
+            match result {
+                Ok(java_path) => return Ok(java_path),
+                Err(error) => {
+                    eprintln!("[runtime] proveedor {} falló: {error}", provider.label());
+                    errors.push(format!("{}: {error}", provider.label()));
+                }
+            }
+        }
+        Err(format!(
+            "No se pudo preparar Java {java_major}: {}",
+            errors.join(" | ")
+        ))
+    }
+
+    /// Runtimes con metadata válida y binario Java presente.
+    fn scan(&self) -> Vec<(String, PathBuf, ManagedRuntimeMetadata)> {
+        self.migrate_legacy_runtimes();
+        let Ok(entries) = fs::read_dir(&self.runtime_root) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let id = entry.file_name().to_str()?.to_string();
+                let root = entry.path();
+                let raw = fs::read_to_string(root.join(RUNTIME_METADATA_FILE)).ok()?;
+                let metadata = serde_json::from_str::<ManagedRuntimeMetadata>(&raw).ok()?;
+                root.join(&metadata.java_path)
+                    .is_file()
+                    .then_some((id, root, metadata))
+            })
+            .collect()
+    }
+
+    /// Las versiones anteriores instalaban Adoptium en `runtime/java<major>` sin metadata. Se
+    /// sondea su Java, se escriben `runtime.json` y el manifest, se renombra al formato
+    /// `<vendor>-<major>-<versión>` y se actualizan los planes de lanzamiento que la usaban.
+    fn migrate_legacy_runtimes(&self) {
+        let Ok(entries) = fs::read_dir(&self.runtime_root) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let legacy = entry.path();
+            let is_legacy = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("java"))
+                .is_some_and(|major| {
+                    !major.is_empty() && major.bytes().all(|b| b.is_ascii_digit())
+                });
+            if !is_legacy || !legacy.is_dir() || legacy.join(RUNTIME_METADATA_FILE).exists() {
+                continue;
+            }
+            match self.migrate_legacy_runtime(&legacy) {
+                Ok(runtime_id) => {
+                    println!("[runtime] {} migrado a {runtime_id}", legacy.display())
+                }
+                Err(error) => {
+                    eprintln!("[runtime] No se pudo migrar {}: {error}", legacy.display())
+                }
+            }
+        }
+    }
+
+    fn migrate_legacy_runtime(&self, legacy: &Path) -> Result<String, String> {
+        let java_path =
+            find_java_in_runtime(legacy).ok_or_else(|| "no contiene binario Java".to_string())?;
+        let probe = probe_java(None, &java_path)
+            .ok_or_else(|| format!("{} no es un Java válido", java_path.display()))?;
+        let vendor = RuntimeProvider::Adoptium.label();
+        // Si ese build ya está instalado se conserva aparte para no romper instancias.
+        let runtime_id = [
+            runtime_dir_name(vendor, probe.major, &probe.version),
+            runtime_dir_name(vendor, probe.major, &format!("{}-legacy", probe.version)),
+        ]
+        .into_iter()
+        .find(|id| !self.runtime_root.join(id).exists())
+        .ok_or_else(|| format!("ya hay un runtime Java {} instalado", probe.version))?;
+        let relative = java_path
+            .strip_prefix(legacy)
+            .map_err(|_| format!("Java fuera del runtime: {}", java_path.display()))?
+            .to_path_buf();
+
+        let manifest = build_runtime_manifest(legacy)?;
+        fs::write(
+            legacy.join(RUNTIME_MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+        )
+        .map_err(|error| format!("No se pudo guardar manifest del runtime: {error}"))?;
+        let metadata = ManagedRuntimeMetadata {
+            vendor: vendor.to_string(),
+            major: probe.major,
+            version: probe.version,
+            component: None,
+            java_path: relative.to_string_lossy().to_string(),
+            installed_at: now_secs(),
+        };
+        fs::write(
+            legacy.join(RUNTIME_METADATA_FILE),
+            serde_json::to_string_pretty(&metadata).unwrap_or_default(),
+        )
+        .map_err(|error| format!("No se pudo guardar metadata del runtime: {error}"))?;
+
+        let destination = self.runtime_root.join(&runtime_id);
+        if let Err(error) = fs::rename(legacy, &destination) {
+            let _ = fs::remove_file(legacy.join(RUNTIME_METADATA_FILE));
+            return Err(format!("No se pudo renombrar a {runtime_id}: {error}"));
+        }
+        self.retarget_launch_plans(legacy, &destination);
+        Ok(runtime_id)
+    }
+
+    /// Reescribe `javaPath` en los `launch-plan.json` que apuntaban a `from`.
+    fn retarget_launch_plans(&self, from: &Path, to: &Path) {
+        let Ok(entries) = fs::read_dir(&self.instances_root) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let plan_path = entry.path().join("launch-plan.json");
+            let Some(mut plan) = fs::read_to_string(&plan_path)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+            else {
+                continue;
+            };
+            let Some(retargeted) = plan
+                .get("javaPath")
+                .and_then(Value::as_str)
+                .and_then(|java_path| Path::new(java_path).strip_prefix(from).ok())
+                .map(|relative| to.join(relative))
+            else {
+                continue;
+            };
+            plan["javaPath"] = Value::String(retargeted.to_string_lossy().to_string());
+            if let Err(error) = fs::write(
+                &plan_path,
+                serde_json::to_string_pretty(&plan).unwrap_or_default(),
+            ) {
+                eprintln!(
+                    "[runtime] No se pudo actualizar {}: {error}",
+                    plan_path.display()
+                );
+            }
+        }
+    }
+
+    /// El runtime instalado de `java_major` del proveedor preferido y con la versión más alta.
+    fn installed_runtime(&self, java_major: u32, providers: &[RuntimeProvider]) -> Option<PathBuf> {
+        let rank = |vendor: &str| {
+            providers
+                .iter()
+                .position(|provider| provider.label() == vendor)
+                .unwrap_or(providers.len())
+        };
+        self.scan()
+            .into_iter()
+            .filter(|(_, root, metadata)| {
+                metadata.major == java_major && runtime_files_present(root)
+            })
+            .min_by(|(_, _, left), (_, _, right)| {
+                rank(&left.vendor)
+                    .cmp(&rank(&right.vendor))
+                    .then_with(|| compare_numeric_versions(&right.version, &left.version))
+            })
+            .map(|(_, root, metadata)| root.join(metadata.java_path))
+    }
+
+    fn installed_runtime_by_id(&self, runtime_id: &str) -> Option<PathBuf> {
+        self.scan()
+            .into_iter()
+            .find(|(id, root, _)| id == runtime_id && runtime_files_present(root))
+            .map(|(_, root, metadata)| root.join(metadata.java_path))
+    }
+
+    /// Rutas de Java de cada instancia según su `launch-plan.json`.
+    fn instance_java_paths(&self) -> Vec<(String, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.instances_root) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let instance_id = entry.file_name().to_str()?.to_string();
+                let raw = fs::read_to_string(entry.path().join("launch-plan.json")).ok()?;
+                let plan = serde_json::from_str::<Value>(&raw).ok()?;
+                let java_path = plan.get("javaPath")?.as_str()?;
+                Some((instance_id, PathBuf::from(java_path)))
+            })
+            .collect()
+    }
+
+    pub(crate) fn list(&self) -> Vec<ManagedRuntimeInfo> {
+        // `scan` migra antes los runtimes antiguos y puede reescribir planes de lanzamiento.
+        let scanned = self.scan();
+        let java_paths = self.instance_java_paths();
+        let mut runtimes = scanned
+            .into_iter()
+            .map(|(id, root, metadata)| ManagedRuntimeInfo {
+                referenced_by: java_paths
+                    .iter()
+                    .filter(|(_, java_path)| java_path.starts_with(&root))
+                    .map(|(instance_id, _)| instance_id.clone())
+                    .collect(),
+                size_bytes: directory_size(&root),
+                java_path: root.join(&metadata.java_path).to_string_lossy().to_string(),
+                path: root.to_string_lossy().to_string(),
+                id,
+                vendor: metadata.vendor,
+                major: metadata.major,
+                version: metadata.version,
+                component: metadata.component,
+            })
+            .collect::<Vec<_>>();
+        runtimes.sort_by(|left, right| {
+            left.major
+                .cmp(&right.major)
+                .then_with(|| left.vendor.cmp(&right.vendor))
+                .then_with(|| compare_numeric_versions(&right.version, &left.version))
+        });
+        runtimes
+    }
+
+    /// Compara cada versión mayor instalada desde Adoptium con el último build publicado.
+    pub(crate) async fn check_updates(&self) -> Result<Vec<RuntimeUpdateInfo>, String> {
+        let mut newest = HashMap::<u32, ManagedRuntimeInfo>::new();
+        for runtime in self.list() {
+            if runtime.vendor != RuntimeProvider::Adoptium.label() {
+                continue;
+            }
+            let replace = newest.get(&runtime.major).is_none_or(|current| {
+                compare_numeric_versions(&runtime.version, &current.version).is_gt()
+            });
+            if replace {
+                newest.insert(runtime.major, runtime);
+            }
+        }
+
+        let mut updates = Vec::new();
+        for (major, runtime) in newest {
+            let latest = self.resolve_runtime_package(major).await?;
+            updates.push(RuntimeUpdateInfo {
+                update_available: compare_numeric_versions(&latest.version, &runtime.version)
+                    .is_gt(),
+                runtime_id: runtime.id,
+                major,
+                current_version: runtime.version,
+                latest_version: latest.version,
+            });
+        }
+        updates.sort_by_key(|update| update.major);
+        Ok(updates)
+    }
+
+    /// Instala el último build de Adoptium para `java_major` y retira las versiones
+    /// anteriores del mismo proveedor que ninguna instancia use.
+    pub(crate) async fn update(&self, java_major: u32) -> Result<ManagedRuntimeInfo, String> {
+        let (installed_id, _) = self.install_adoptium_runtime(java_major).await?;
+        let adoptium = RuntimeProvider::Adoptium.label();
+        for runtime in self.list() {
+            if runtime.id != installed_id
+                && runtime.vendor == adoptium
+                && runtime.major == java_major
+                && runtime.referenced_by.is_empty()
+            {
+                if let Err(error) = self.uninstall(&runtime.id) {
+                    eprintln!("[runtime] No se pudo retirar {}: {error}", runtime.id);
+                }
+            }
+        }
+        self.list()
+            .into_iter()
+            .find(|runtime| runtime.id == installed_id)
+            .ok_or_else(|| format!("El runtime {installed_id} no quedó instalado"))
+    }
 
-        let package_url = self.resolve_runtime_package(java_major).await?;
-        let archive_name = package_url
+    pub(crate) fn uninstall(&self, runtime_id: &str) -> Result<(), String> {
+        let runtime_id = runtime_id.trim();
+        let mut components = Path::new(runtime_id).components();
+        let plain_name =
+            matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+        if !plain_name || runtime_id.starts_with('.') {
+            return Err(format!("Identificador de runtime inválido: {runtime_id}"));
+        }
+        let runtime = self
+            .list()
+            .into_iter()
+            .find(|runtime| runtime.id == runtime_id)
+            .ok_or_else(|| format!("El runtime {runtime_id} no está instalado"))?;
+        if !runtime.referenced_by.is_empty() {
+            return Err(format!(
+                "El runtime {runtime_id} está en uso por: {}",
+                runtime.referenced_by.join(", ")
+            ));
+        }
+        fs::remove_dir_all(&runtime.path)
+            .map_err(|error| format!("No se pudo eliminar el runtime {runtime_id}: {error}"))
+    }
+
+    /// Comprueba cada runtime contra su manifest. Los que tienen archivos alterados o
+    /// ausentes pasan a cuarentena para que el siguiente `ensure_runtime` los reinstale.
+    pub(crate) fn verify_runtimes(&self) -> Vec<ManagedRuntimeIntegrity> {
+        let mut reports = self
+            .scan()
+            .into_iter()
+            .filter_map(|(runtime_id, root, _)| self.verify_runtime(runtime_id, &root))
+            .collect::<Vec<_>>();
+        reports.sort_by(|left, right| left.runtime_id.cmp(&right.runtime_id));
+        reports
+    }
+
+    /// Como `verify_runtimes`, pero sólo para el runtime gestionado que contiene `java_path`.
+    pub(crate) fn verify_runtime_of(&self, java_path: &Path) -> Option<ManagedRuntimeIntegrity> {
+        self.scan()
+            .into_iter()
+            .find(|(_, root, _)| java_path.starts_with(root))
+            .and_then(|(runtime_id, root, _)| self.verify_runtime(runtime_id, &root))
+    }
+
+    fn verify_runtime(&self, runtime_id: String, root: &Path) -> Option<ManagedRuntimeIntegrity> {
+        let manifest = read_runtime_manifest(root)?;
+        let (missing, modified) = verify_runtime_files(root, &manifest);
+        let quarantined_to = if missing.is_empty() && modified.is_empty() {
+            None
+        } else {
+            match self.quarantine(root, &runtime_id) {
+                Ok(path) => Some(path.to_string_lossy().to_string()),
+                Err(error) => {
+                    eprintln!("[runtime] {error}");
+                    None
+                }
+            }
+        };
+        Some(ManagedRuntimeIntegrity {
+            runtime_id,
+            checked_files: manifest.files.len(),
+            missing,
+            modified,
+            quarantined_to,
+        })
+    }
+
+    /// Mueve `path` a la cuarentena para poder inspeccionarlo. Si no se puede mover se deja
+    /// donde está y se devuelve el error.
+    fn quarantine(&self, path: &Path, name: &str) -> Result<PathBuf, String> {
+        let target = self
+            .runtime_root
+            .join(RUNTIME_QUARANTINE_DIR)
+            .join(format!("{}-{name}", now_secs()));
+        let moved = fs::create_dir_all(self.runtime_root.join(RUNTIME_QUARANTINE_DIR))
+            .and_then(|_| fs::rename(path, &target));
+        match moved {
+            Ok(()) => {
+                self.prune_quarantine();
+                Ok(target)
+            }
+            Err(error) => Err(format!(
+                "No se pudo poner en cuarentena {}: {error}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Deja sólo las `RUNTIME_QUARANTINE_KEEP` entradas más recientes; el nombre empieza por
+    /// el instante en que se apartaron.
+    fn prune_quarantine(&self) {
+        let Ok(entries) = fs::read_dir(self.runtime_root.join(RUNTIME_QUARANTINE_DIR)) else {
+            return;
+        };
+        let mut entries = entries
+            .flatten()
+            .map(|entry| {
+                let quarantined_at = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.split_once('-'))
+                    .and_then(|(secs, _)| secs.parse::<u64>().ok())
+                    .unwrap_or_default();
+                (quarantined_at, entry.path())
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|left, right| right.cmp(left));
+        for (_, path) in entries.into_iter().skip(RUNTIME_QUARANTINE_KEEP) {
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            if let Err(error) = removed {
+                eprintln!(
+                    "[runtime] No se pudo limpiar cuarentena {}: {error}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    /// Compara el paquete descargado con el SHA-256 publicado antes de extraerlo.
+    fn verify_runtime_archive(
+        &self,
+        archive: &Path,
+        expected: Option<&str>,
+        runtime_id: &str,
+    ) -> Result<(), String> {
+        let Some(expected) = expected.map(str::trim).filter(|value| !value.is_empty()) else {
+            eprintln!("[runtime] {runtime_id} no publica checksum; se extrae sin verificar");
+            return Ok(());
+        };
+        let actual = file_sha256(archive)?;
+        if actual.eq_ignore_ascii_case(expected) {
+            return Ok(());
+        }
+        let file_name = archive
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("runtime-download");
+        let location = match self.quarantine(archive, file_name) {
+            Ok(path) => format!("se movió a {}", path.display()),
+            Err(error) => error,
+        };
+        Err(format!(
+            "El paquete de {runtime_id} no coincide con su SHA-256 (esperado {expected}, obtenido {actual}); {location}"
+        ))
+    }
+
+    fn staging_path(&self, runtime_id: &str) -> Result<PathBuf, String> {
+        let staging = self.runtime_root.join(RUNTIME_STAGING_DIR).join(runtime_id);
+        if staging.exists() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        fs::create_dir_all(&staging)
+            .map_err(|error| format!("No se pudo preparar carpeta del runtime: {error}"))?;
+        Ok(staging)
+    }
+
+    /// Valida Java en la carpeta temporal, guarda manifest y metadata y la mueve a su sitio
+    /// final.
+    fn commit_staged_runtime(
+        &self,
+        runtime_id: &str,
+        staging: &Path,
+        java_path: &Path,
+        metadata: ManagedRuntimeMetadata,
+    ) -> Result<PathBuf, String> {
+        let cleanup = |error: String| {
+            let _ = fs::remove_dir_all(staging);
+            error
+        };
+        validate_java_runtime(java_path).map_err(cleanup)?;
+        let relative = java_path
+            .strip_prefix(staging)
+            .map_err(|_| cleanup(format!("Java fuera del runtime: {}", java_path.display())))?
+            .to_path_buf();
+        let manifest = build_runtime_manifest(staging).map_err(cleanup)?;
+        fs::write(
+            staging.join(RUNTIME_MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+        )
+        .map_err(|error| cleanup(format!("No se pudo guardar manifest del runtime: {error}")))?;
+        let metadata = ManagedRuntimeMetadata {
+            java_path: relative.to_string_lossy().to_string(),
+            ..metadata
+        };
+        fs::write(
+            staging.join(RUNTIME_METADATA_FILE),
+            serde_json::to_string_pretty(&metadata).unwrap_or_default(),
+        )
+        .map_err(|error| cleanup(format!("No se pudo guardar metadata del runtime: {error}")))?;
+
+        let destination = self.runtime_root.join(runtime_id);
+        if destination.exists() {
+            fs::remove_dir_all(&destination).map_err(|error| {
+                cleanup(format!(
+                    "No se pudo reemplazar runtime {runtime_id}: {error}"
+                ))
+            })?;
+        }
+        fs::rename(staging, &destination).map_err(|error| {
+            cleanup(format!("No se pudo activar runtime {runtime_id}: {error}"))
+        })?;
+        Ok(destination.join(relative))
+    }
+
+    async fn install_mojang_component(
+        &self,
+        java_major: u32,
+        component: &str,
+    ) -> Result<PathBuf, String> {
+        let release = resolve_mojang_release(component).await?;
+        let runtime_id = runtime_dir_name(
+            RuntimeProvider::Mojang.label(),
+            java_major,
+            &release.version,
+        );
+        let install_lock = runtime_install_lock(&runtime_id);
+        let _install_guard = install_lock.lock().await;
+        if let Some(java_path) = self.installed_runtime_by_id(&runtime_id) {
+            return Ok(java_path);
+        }
+        let staging = self.staging_path(&runtime_id)?;
+        let java_path = match install_mojang_release(&release, &staging).await {
+            Ok(java_path) => java_path,
+            Err(error) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(error);
+            }
+        };
+        self.commit_staged_runtime(
+            &runtime_id,
+            &staging,
+            &java_path,
+            ManagedRuntimeMetadata {
+                vendor: RuntimeProvider::Mojang.label().to_string(),
+                major: java_major,
+                version: release.version.clone(),
+                component: Some(component.to_string()),
+                java_path: String::new(),
+                installed_at: now_secs(),
+            },
+        )
+    }
+
+    /// Devuelve el id del runtime y la ruta de Java. Si ese build ya está instalado se reutiliza.
+    async fn install_adoptium_runtime(&self, java_major: u32) -> Result<(String, PathBuf), String> {
+        let package = self.resolve_runtime_package(java_major).await?;
+        let runtime_id = runtime_dir_name(
+            RuntimeProvider::Adoptium.label(),
+            java_major,
+            &package.version,
+        );
+        let install_lock = runtime_install_lock(&runtime_id);
+        let _install_guard = install_lock.lock().await;
+        if let Some(java_path) = self.installed_runtime_by_id(&runtime_id) {
+            return Ok((runtime_id, java_path));
+        }
+
+        let archive_name = package
+            .link
             .split('/')
             .next_back()
             .unwrap_or("runtime-download.tar.gz");
         let cache_path = self
             .runtime_root
             .join("downloads")
-            .join(runtime_id)
+            .join(&runtime_id)
             .join(archive_name);
-
         if let Some(parent) = cache_path.parent() {
             fs::create_dir_all(parent)
                 .map_err(|error| format!("No se pudo crear carpeta de caché runtime: {error}"))?;
         }
 
-        let tuning = NetworkTuning::default();
-        download_with_retries(
-            &[package_url],
-            &cache_path,
-            None,
-            3,
-            cache_path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip")),
-            &tuning,
-            "runtime_download",
-        )
-        .await?;
-
-        if destination_root.exists() {
-            let _ = fs::remove_dir_all(&destination_root);
+        // Tanto un paquete ya en caché como uno recién descargado pasan por
+        // `verify_runtime_archive`: si no coincide va a cuarentena y se prueba el siguiente
+        // mirror.
+        let checksum = package.checksum.as_deref();
+        let mut ready = false;
+        let mut errors = Vec::new();
+        if cache_path.is_file() {
+            match self.verify_runtime_archive(&cache_path, checksum, &runtime_id) {
+                Ok(()) => ready = true,
+                Err(error) => {
+                    eprintln!("[runtime] {error}; se descarga de nuevo");
+                    errors.push(error);
+                }
+            }
         }
-        fs::create_dir_all(&destination_root)
-            .map_err(|error| format!("No se pudo preparar carpeta del runtime: {error}"))?;
-
-        unpack_runtime_archive(&cache_path, &destination_root)?;
-        let resolved_java = find_java_in_runtime(&destination_root).ok_or_else(|| {
-            format!(
-                "No se encontró binario Java en {}",
-                destination_root.display()
+        let tuning = active_network_tuning();
+        for url in apply_endpoint_overrides(vec![package.link.clone()]) {
+            if ready || cache_path.exists() {
+                break;
+            }
+            let downloaded = download_with_progress(
+                std::slice::from_ref(&url),
+                &cache_path,
+                ExpectedDigest {
+                    size: package.size,
+                    ..ExpectedDigest::default()
+                },
+                cache_path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("zip")),
+                &tuning,
+                "runtime_download",
+                None,
             )
-        })?;
-
-        if resolved_java != java_path {
-            if let Some(parent) = java_path.parent() {
-                fs::create_dir_all(parent).map_err(|error| {
-                    format!("No se pudo crear carpeta bin del runtime: {error}")
-                })?;
+            .await
+            .and_then(|_| self.verify_runtime_archive(&cache_path, checksum, &runtime_id));
+            match downloaded {
+                Ok(()) => ready = true,
+                Err(error) => errors.push(format!("{url}: {error}")),
             }
-            fs::copy(&resolved_java, &java_path).map_err(|error| {
-                format!(
-                    "No se pudo normalizar ruta Java {} -> {}: {error}",
-                    resolved_java.display(),
-                    java_path.display()
-                )
-            })?;
+        }
+        if !ready {
+            return Err(format!(
+                "No se pudo descargar el runtime {runtime_id}: {}",
+                errors.join(" | ")
+            ));
         }
 
-        validate_java_runtime(&java_path)?;
-
-        Ok(java_path)
+        let staging = self.staging_path(&runtime_id)?;
+        let unpacked = unpack_runtime_archive(&cache_path, &staging).and_then(|_| {
+            find_java_in_runtime(&staging)
+                .ok_or_else(|| format!("No se encontró binario Java en {}", staging.display()))
+        });
+        let java_path = match unpacked {
+            Ok(java_path) => java_path,
+            Err(error) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(error);
+            }
+        };
+        let java_path = self.commit_staged_runtime(
+            &runtime_id,
+            &staging,
+            &java_path,
+            ManagedRuntimeMetadata {
+                vendor: RuntimeProvider::Adoptium.label().to_string(),
+                major: java_major,
+                version: package.version,
+                component: None,
+                java_path: String::new(),
+                installed_at: now_secs(),
+            },
+        )?;
+        let _ = fs::remove_file(&cache_path);
+        Ok((runtime_id, java_path))
     }
 
-    async fn resolve_runtime_package(&self, java_major: u32) -> Result<String, String> {
+    async fn resolve_runtime_package(&self, java_major: u32) -> Result<AdoptiumPackage, String> {
         let arch = match std::env::consts::ARCH {
             "x86_64" => "x64",
             "aarch64" => "aarch64",
@@ -124,26 +1015,72 @@ impl RuntimeManager {
         let url = format!(
             "{ADOPTIUM_RELEASES}/{java_major}/hotspot?architecture={arch}&heap_size=normal&image_type={image}&jvm_impl=hotspot&os={os}&project=jdk"
         );
-        let assets = reqwest::get(url)
-            .await
-            .map_err(|error| format!("No se pudo consultar runtimes de Adoptium: {error}"))?
-            .json::<Vec<Value>>()
-            .await
-            .map_err(|error| format!("No se pudo parsear respuesta de Adoptium: {error}"))?;
+        let assets = self.fetch_runtime_assets(&url).await?;
 
         let package = assets.into_iter().find_map(|asset| {
-            asset
-                .get("binary")
-                .and_then(|binary| binary.get("package"))
-                .and_then(|package| package.get("link"))
-                .and_then(|link| link.as_str())
+            let link = asset.pointer("/binary/package/link")?.as_str()?.to_string();
+            let version = asset
+                .pointer("/version/openjdk_version")
+                .or_else(|| asset.pointer("/version/semver"))
+                .and_then(Value::as_str)
                 .map(str::to_string)
+                .or_else(|| {
+                    asset
+                        .get("release_name")
+                        .and_then(Value::as_str)
+                        .map(|name| {
+                            name.trim_start_matches("jdk-")
+                                .trim_start_matches("jdk")
+                                .to_string()
+                        })
+                })
+                .unwrap_or_else(|| java_major.to_string());
+            let checksum = asset
+                .pointer("/binary/package/checksum")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            Some(AdoptiumPackage {
+                link,
+                version,
+                checksum,
+                size: asset
+                    .pointer("/binary/package/size")
+                    .and_then(Value::as_u64),
+            })
         });
 
         package.ok_or_else(|| {
             format!("No hay runtime Java {java_major} disponible para esta plataforma")
         })
     }
+
+    /// Consulta la API de Adoptium probando antes los mirrors configurados.
+    async fn fetch_runtime_assets(&self, url: &str) -> Result<Vec<Value>, String> {
+        let client = http_client_with_tuning(&active_network_tuning())?;
+        let mut last_error = None;
+        for candidate in apply_endpoint_overrides(vec![url.to_string()]) {
+            let assets = match read_local_mirror(&candidate) {
+                Some(local) => local.and_then(|raw| {
+                    serde_json::from_slice::<Vec<Value>>(&raw).map_err(|error| {
+                        format!("No se pudo parsear respuesta de Adoptium: {error}")
+                    })
+                }),
+                None => match client.get(&candidate).send().await {
+                    Ok(response) => response.json::<Vec<Value>>().await.map_err(|error| {
+                        format!("No se pudo parsear respuesta de Adoptium: {error}")
+                    }),
+                    Err(error) => Err(format!(
+                        "No se pudo consultar runtimes de Adoptium: {error}"
+                    )),
+                },
+            };
+            match assets {
+                Ok(assets) => return Ok(assets),
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| "Sin endpoints de Adoptium".to_string()))
+    }
 }
 
 fn validate_java_runtime(java_path: &Path) -> Result<(), String> {
@@ -171,14 +1108,6 @@ fn validate_java_runtime(java_path: &Path) -> Result<(), String> {
     ))
 }
 
-fn runtime_folder_name(java_major: u32) -> &'static str {
-    match java_major {
-        8 => "java8",
-        17 => "java17",
-        _ => "java21",
-    }
-}
-
 fn unpack_runtime_archive(archive_path: &Path, destination: &Path) -> Result<(), String> {
     let extension = archive_path
         .extension()
@@ -256,11 +1185,36 @@ fn trim_archive_root(path: &str) -> String {
     components.collect::<Vec<_>>().join("/")
 }
 
+/// Java de un runtime gestionado: el `javaPath` de `runtime.json` o, en carpetas sin
+/// metadata, el binario que se encuentre en los diseños conocidos.
+pub(crate) fn managed_runtime_java(root: &Path) -> Option<PathBuf> {
+    match fs::read_to_string(root.join(RUNTIME_METADATA_FILE)) {
+        Ok(raw) => {
+            let metadata = serde_json::from_str::<ManagedRuntimeMetadata>(&raw).ok()?;
+            let java = root.join(metadata.java_path);
+            java.is_file().then_some(java)
+        }
+        Err(_) => find_java_in_runtime(root),
+    }
+}
+
 fn find_java_in_runtime(root: &Path) -> Option<PathBuf> {
     let direct = root.join("bin").join(java_bin_name());
     if direct.is_file() {
         return Some(direct);
     }
+    // Los tar.gz de macOS traen `Contents/Home` bajo la carpeta raíz que ya se recorta y los
+    // componentes de Mojang lo anidan en `jre.bundle`.
+    for home in [root.to_path_buf(), root.join("jre.bundle")] {
+        let mac_home = home
+            .join("Contents")
+            .join("Home")
+            .join("bin")
+            .join(java_bin_name());
+        if mac_home.is_file() {
+            return Some(mac_home);
+        }
+    }
 
     let entries = fs::read_dir(root).ok()?;
     for entry in entries.flatten() {
@@ -276,3 +1230,277 @@ fn find_java_in_runtime(root: &Path) -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use sha2::{Digest, Sha256};
+
+    use super::{
+        build_runtime_manifest, managed_runtime_java, resolve_runtime_providers, runtime_dir_name,
+        runtime_files_present, RuntimeManager, RuntimeProvider, RUNTIME_MANIFEST_FILE,
+        RUNTIME_METADATA_FILE, RUNTIME_QUARANTINE_DIR, RUNTIME_QUARANTINE_KEEP,
+    };
+
+    #[test]
+    fn provider_order_is_configurable_and_keeps_adoptium_as_fallback() {
+        assert_eq!(
+            resolve_runtime_providers(None),
+            vec![RuntimeProvider::Mojang, RuntimeProvider::Adoptium]
+        );
+        assert_eq!(
+            resolve_runtime_providers(Some(&["Adoptium".to_string(), "mojang".to_string()])),
+            vec![RuntimeProvider::Adoptium, RuntimeProvider::Mojang]
+        );
+        assert_eq!(
+            resolve_runtime_providers(Some(&["mojang".to_string(), "mojang".to_string()])),
+            vec![RuntimeProvider::Mojang, RuntimeProvider::Adoptium]
+        );
+        assert_eq!(
+            resolve_runtime_providers(Some(&["desconocido".to_string()])),
+            vec![RuntimeProvider::Mojang, RuntimeProvider::Adoptium]
+        );
+    }
+
+    #[test]
+    fn finds_java_through_metadata_and_macos_layouts() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-runtime-java-{unique}"));
+        let java = crate::java_bin_name();
+
+        let described = root.join("mojang-17");
+        let bundled = described.join("jre.bundle/Contents/Home/bin");
+        fs::create_dir_all(&bundled).expect("bundle");
+        fs::write(bundled.join(java), b"java").expect("java");
+        fs::write(
+            described.join(RUNTIME_METADATA_FILE),
+            serde_json::json!({
+                "vendor": "mojang",
+                "major": 17,
+                "version": "17.0.8",
+                "javaPath": format!("jre.bundle/Contents/Home/bin/{java}")
+            })
+            .to_string(),
+        )
+        .expect("metadata");
+        assert_eq!(managed_runtime_java(&described), Some(bundled.join(java)));
+
+        let legacy_mac = root.join("java8").join("Contents/Home/bin");
+        fs::create_dir_all(&legacy_mac).expect("legacy");
+        fs::write(legacy_mac.join(java), b"java").expect("legacy java");
+        assert_eq!(
+            managed_runtime_java(&root.join("java8")),
+            Some(legacy_mac.join(java))
+        );
+
+        // Con metadata que apunta a un binario ausente no se adivina otra ruta.
+        fs::remove_file(bundled.join(java)).expect("remove java");
+        fs::create_dir_all(described.join("bin")).expect("bin");
+        fs::write(described.join("bin").join(java), b"java").expect("stray java");
+        assert_eq!(managed_runtime_java(&described), None);
+
+        fs::remove_dir_all(&root).expect("cleanup");
+    }
+
+    #[test]
+    fn lists_runtimes_by_folder_and_refuses_to_uninstall_referenced_ones() {
+        assert_eq!(
+            runtime_dir_name("adoptium", 17, "17.0.9+9"),
+            "adoptium-17-17.0.9+9"
+        );
+        assert_eq!(
+            runtime_dir_name("mojang", 8, "8u51 b/16"),
+            "mojang-8-8u51_b_16"
+        );
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-runtimes-{unique}"));
+        let manager = RuntimeManager::with_roots(root.join("runtime"), root.join("instances"));
+        for (vendor, major, version) in [
+            ("adoptium", 17, "17.0.8+7"),
+            ("adoptium", 17, "17.0.10+7"),
+            ("mojang", 16, "16.0.1.9.1"),
+        ] {
+            let id = runtime_dir_name(vendor, major, version);
+            let runtime = root.join("runtime").join(&id);
+            fs::create_dir_all(runtime.join("bin")).expect("bin");
+            fs::write(runtime.join("bin").join("java"), b"java").expect("java");
+            fs::write(
+                runtime.join(RUNTIME_METADATA_FILE),
+                serde_json::json!({
+                    "vendor": vendor,
+                    "major": major,
+                    "version": version,
+                    "javaPath": "bin/java"
+                })
+                .to_string(),
+            )
+            .expect("metadata");
+        }
+        // Runtime de una versión anterior, sin metadata: se migra al listar.
+        let legacy = root.join("runtime").join("java21");
+        fs::create_dir_all(legacy.join("bin")).expect("legacy");
+        fs::write(legacy.join("bin").join("java"), b"java").expect("legacy java");
+        fs::write(legacy.join("release"), "JAVA_VERSION=\"21.0.2\"\n").expect("release");
+        let modded = root.join("instances").join("modded");
+        fs::create_dir_all(&modded).expect("modded");
+        fs::write(
+            modded.join("launch-plan.json"),
+            serde_json::json!({"javaPath": legacy.join("bin").join("java")}).to_string(),
+        )
+        .expect("legacy plan");
+
+        let in_use = root.join("runtime").join("adoptium-17-17.0.8+7");
+        let instance = root.join("instances").join("survival");
+        fs::create_dir_all(&instance).expect("instance");
+        fs::write(
+            instance.join("launch-plan.json"),
+            serde_json::json!({"javaPath": in_use.join("bin").join("java")}).to_string(),
+        )
+        .expect("plan");
+
+        let listed = manager.list();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|runtime| runtime.id.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "mojang-16-16.0.1.9.1",
+                "adoptium-17-17.0.10+7",
+                "adoptium-17-17.0.8+7",
+                "adoptium-21-21.0.2"
+            ]
+        );
+        assert_eq!(listed[2].referenced_by, vec!["survival".to_string()]);
+        assert_eq!(listed[3].referenced_by, vec!["modded".to_string()]);
+        assert!(listed[1].size_bytes > 0);
+        assert!(!legacy.exists());
+        let migrated = root.join("runtime").join("adoptium-21-21.0.2");
+        assert!(migrated.join(RUNTIME_MANIFEST_FILE).is_file());
+        let plan = fs::read_to_string(modded.join("launch-plan.json")).expect("plan");
+        assert!(plan.contains("adoptium-21-21.0.2"));
+
+        let preferred = manager
+            .installed_runtime(17, &[RuntimeProvider::Mojang, RuntimeProvider::Adoptium])
+            .expect("java 17");
+        assert!(preferred.starts_with(root.join("runtime").join("adoptium-17-17.0.10+7")));
+        assert!(manager
+            .installed_runtime(21, &[RuntimeProvider::Adoptium])
+            .expect("java 21 migrado")
+            .starts_with(&migrated));
+
+        let error = manager
+            .uninstall("adoptium-17-17.0.8+7")
+            .expect_err("en uso");
+        assert!(error.contains("survival"));
+        assert!(manager.uninstall("../instances").is_err());
+        manager
+            .uninstall("adoptium-17-17.0.10+7")
+            .expect("sin referencias");
+        assert_eq!(manager.list().len(), 3);
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn quarantines_archives_with_bad_checksums_and_tampered_runtimes() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-runtime-hash-{unique}"));
+        let manager = RuntimeManager::with_roots(root.join("runtime"), root.join("instances"));
+        let quarantine = root.join("runtime").join(RUNTIME_QUARANTINE_DIR);
+
+        let archive = root.join("runtime").join("downloads").join("jre.tar.gz");
+        fs::create_dir_all(archive.parent().expect("parent")).expect("downloads");
+        fs::write(&archive, b"paquete").expect("archive");
+        let checksum = format!("{:x}", Sha256::digest(b"paquete"));
+        manager
+            .verify_runtime_archive(&archive, Some(&checksum.to_uppercase()), "adoptium-21-21")
+            .expect("checksum válido");
+        let error = manager
+            .verify_runtime_archive(&archive, Some(&"0".repeat(64)), "adoptium-21-21")
+            .expect_err("checksum inválido");
+        assert!(error.contains("SHA-256"));
+        assert!(!archive.exists());
+        assert_eq!(fs::read_dir(&quarantine).expect("cuarentena").count(), 1);
+        for secs in 1..=4 {
+            fs::write(quarantine.join(format!("{secs}-viejo.tar.gz")), b"x").expect("viejo");
+        }
+        manager.prune_quarantine();
+        let mut kept = fs::read_dir(&quarantine)
+            .expect("cuarentena")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        kept.sort();
+        assert_eq!(kept.len(), RUNTIME_QUARANTINE_KEEP);
+        assert!(!kept.contains(&"1-viejo.tar.gz".to_string()));
+        assert!(kept.iter().any(|name| name.ends_with("jre.tar.gz")));
+
+        let runtime = root.join("runtime").join("adoptium-21-21.0.1+12");
+        fs::create_dir_all(runtime.join("bin")).expect("bin");
+        fs::create_dir_all(runtime.join("lib")).expect("lib");
+        fs::write(runtime.join("bin").join("java"), b"java").expect("java");
+        fs::write(runtime.join("lib").join("modules"), b"modules").expect("modules");
+        let manifest = build_runtime_manifest(&runtime).expect("manifest");
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            vec!["bin/java", "lib/modules"]
+        );
+        fs::write(
+            runtime.join(RUNTIME_MANIFEST_FILE),
+            serde_json::to_string(&manifest).expect("json"),
+        )
+        .expect("manifest file");
+        fs::write(
+            runtime.join(RUNTIME_METADATA_FILE),
+            serde_json::json!({
+                "vendor": "adoptium",
+                "major": 21,
+                "version": "21.0.1+12",
+                "javaPath": "bin/java"
+            })
+            .to_string(),
+        )
+        .expect("metadata");
+        assert!(runtime_files_present(&runtime));
+        assert!(manager.verify_runtimes()[0].quarantined_to.is_none());
+
+        // Mismo tamaño, contenido distinto: sólo lo detecta el hash.
+        fs::write(runtime.join("lib").join("modules"), b"MODULES").expect("tamper");
+        assert!(runtime_files_present(&runtime));
+        assert!(manager
+            .verify_runtime_of(&root.join("otro").join("bin").join("java"))
+            .is_none());
+        assert!(runtime.exists());
+        let report = manager
+            .verify_runtime_of(&runtime.join("bin").join("java"))
+            .expect("report");
+        assert_eq!(report.modified, vec!["lib/modules".to_string()]);
+        assert!(report.quarantined_to.is_some());
+        assert!(!runtime.exists());
+        assert!(manager
+            .installed_runtime(21, &[RuntimeProvider::Adoptium])
+            .is_none());
+
+        // Si no se puede mover a cuarentena, el runtime se queda donde está.
+        fs::remove_dir_all(&quarantine).expect("quarantine");
+        fs::write(&quarantine, b"no es carpeta").expect("blocker");
+        fs::create_dir_all(runtime.join("bin")).expect("runtime");
+        assert!(manager.quarantine(&runtime, "adoptium-21").is_err());
+        assert!(runtime.join("bin").is_dir());
+
+        fs::remove_dir_all(root).expect("cleanup");
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::SeekFrom;
+use std::path::Path;
+
+use futures::StreamExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::core::bandwidth::BandwidthLimiter;
+use crate::core::downloader::Downloader;
+use crate::core::mirror_health::url_host;
+
+/// Tamaño esperado a partir del cual se intenta descargar en segmentos paralelos.
+pub(crate) const SEGMENTED_DOWNLOAD_THRESHOLD: u64 = 16 * 1024 * 1024;
+const MIN_SEGMENT_SIZE: u64 = 4 * 1024 * 1024;
+const MAX_SEGMENTS: u64 = 8;
+
+#[derive(Debug)]
+pub(crate) enum SegmentedDownloadError {
+    /// El servidor ignoró `Range`; hay que volver a una descarga de un solo flujo.
+    RangesUnsupported,
+    Failed(String),
+}
+
+/// Rangos inclusivos `(inicio, fin)` que cubren `total` bytes.
+pub(crate) fn segment_ranges(total: u64, min_segment: u64, max_segments: u64) -> Vec<(u64, u64)> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let count = (total / min_segment.max(1)).clamp(1, max_segments.max(1));
+    let base = total / count;
+    (0..count)
+        .map(|index| {
+            let start = index * base;
+            let end = if index + 1 == count {
+                total - 1
+            } else {
+                start + base - 1
+            };
+            (start, end)
+        })
+        .collect()
+}
+
+async fn download_segment(
+    client: &reqwest::Client,
+    url: &str,
+    destination: &Path,
+    (start, end): (u64, u64),
+    total: u64,
+    on_bytes: &(dyn Fn(u64) + Send + Sync),
+) -> Result<(), SegmentedDownloadError> {
+    let failed = SegmentedDownloadError::Failed;
+    let response = client
+        .get(url)
+        .header(reqwest::header::RANGE, format!("bytes={start}-{end}"))
+        .send()
+        .await
+        .map_err(|error| failed(format!("No se pudo descargar segmento de {url}: {error}")))?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        if response.status().is_success() {
+            return Err(SegmentedDownloadError::RangesUnsupported);
+        }
+        return Err(failed(format!(
+            "{url} respondió {} a un segmento",
+            response.status()
+        )));
+    }
+    let expected_range = format!("bytes {start}-{end}/{total}");
+    let content_range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if content_range.trim() != expected_range {
+        return Err(SegmentedDownloadError::RangesUnsupported);
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(destination)
+        .await
+        .map_err(|error| {
+            failed(format!(
+                "No se pudo abrir {}: {error}",
+                destination.display()
+            ))
+        })?;
+    file.seek(SeekFrom::Start(start))
+        .await
+        .map_err(|error| failed(format!("No se pudo posicionar segmento: {error}")))?;
+
+    let host = url_host(url);
+    let mut received = 0_u64;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|error| failed(format!("No se pudo leer segmento de {url}: {error}")))?;
+        received += chunk.len() as u64;
+        if received > end - start + 1 {
+            return Err(failed(format!("{url} envió más bytes de los pedidos")));
+        }
+        Downloader::global().wait_while_paused().await;
+        BandwidthLimiter::global()
+            .throttle(host.as_deref(), chunk.len() as u64)
+            .await;
+        on_bytes(chunk.len() as u64);
+        file.write_all(&chunk)
+            .await
+            .map_err(|error| failed(format!("No se pudo escribir segmento: {error}")))?;
+    }
+    file.flush()
+        .await
+        .map_err(|error| failed(format!("No se pudo vaciar segmento: {error}")))?;
+
+    if received != end - start + 1 {
+        return Err(failed(format!(
+            "Segmento {start}-{end} de {url} incompleto ({received} bytes)"
+        )));
+    }
+    Ok(())
+}
+
+/// Descarga `url` en segmentos concurrentes sobre `destination`, preasignado a `total` bytes.
+/// La verificación de hash queda para quien llama, igual que en la descarga de un flujo.
+pub(crate) async fn download_segmented(
+    client: &reqwest::Client,
+    url: &str,
+    destination: &Path,
+    total: u64,
+    on_bytes: &(dyn Fn(u64) + Send + Sync),
+) -> Result<(), SegmentedDownloadError> {
+    let file = tokio::fs::File::create(destination)
+        .await
+        .map_err(|error| {
+            SegmentedDownloadError::Failed(format!(
+                "No se pudo crear {}: {error}",
+                destination.display()
+            ))
+        })?;
+    file.set_len(total).await.map_err(|error| {
+        SegmentedDownloadError::Failed(format!("No se pudo reservar {total} bytes: {error}"))
+    })?;
+    drop(file);
+
+    let segments = segment_ranges(total, MIN_SEGMENT_SIZE, MAX_SEGMENTS)
+        .into_iter()
+        .map(|range| download_segment(client, url, destination, range, total, on_bytes));
+    futures::future::try_join_all(segments).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{download_segmented, segment_ranges, SegmentedDownloadError};
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    #[test]
+    fn splits_into_contiguous_ranges() {
+        assert_eq!(segment_ranges(10, 4, 8), vec![(0, 4), (5, 9)]);
+        assert_eq!(segment_ranges(3, 4, 8), vec![(0, 2)]);
+        let ranges = segment_ranges(100, 1, 8);
+        assert_eq!(ranges.len(), 8);
+        assert_eq!(ranges.first().map(|range| range.0), Some(0));
+        assert_eq!(ranges.last().map(|range| range.1), Some(99));
+        assert!(ranges.windows(2).all(|pair| pair[0].1 + 1 == pair[1].0));
+    }
+
+    #[test]
+    fn reassembles_ranges_and_detects_servers_that_ignore_them() {
+        let payload = (0..(9 * 1024 * 1024_u32))
+            .map(|value| (value % 251) as u8)
+            .collect::<Vec<_>>();
+        let served = payload.clone();
+        let base = spawn_mock_server(move |request| {
+            let range = request
+                .header("range")
+                .and_then(|value| value.strip_prefix("bytes="))
+                .and_then(|value| value.split_once('-'))
+                .and_then(|(start, end)| {
+                    Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?))
+                });
+            match (request.path.as_str(), range) {
+                ("/ranged.bin", Some((start, end))) => MockResponse {
+                    status: 206,
+                    headers: vec![(
+                        "Content-Range".to_string(),
+                        format!("bytes {start}-{end}/{}", served.len()),
+                    )],
+                    body: served[start..=end].to_vec(),
+                },
+                _ => MockResponse {
+                    status: 200,
+                    headers: Vec::new(),
+                    body: served.clone(),
+                },
+            }
+        });
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let target = std::env::temp_dir().join(format!("frutistudio-test-segmented-{unique}.part"));
+        let client = reqwest::Client::new();
+        let counted = AtomicU64::new(0);
+        let on_bytes = |bytes: u64| {
+            counted.fetch_add(bytes, Ordering::Relaxed);
+        };
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        rt.block_on(async {
+            download_segmented(
+                &client,
+                &format!("{base}/ranged.bin"),
+                &target,
+                payload.len() as u64,
+                &on_bytes,
+            )
+            .await
+            .expect("segmented");
+            assert_eq!(std::fs::read(&target).expect("target"), payload);
+            assert_eq!(counted.load(Ordering::Relaxed), payload.len() as u64);
+
+            let ignored = download_segmented(
+                &client,
+                &format!("{base}/plain.bin"),
+                &target,
+                payload.len() as u64,
+                &on_bytes,
+            )
+            .await;
+            assert!(matches!(
+                ignored,
+                Err(SegmentedDownloadError::RangesUnsupported)
+            ));
+        });
+
+        let _ = std::fs::remove_file(target);
+    }
+}
@@ -0,0 +1,432 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkinVariant {
+    Classic,
+    Slim,
+}
+
+impl SkinVariant {
+    pub fn parse(raw: Option<&str>) -> Result<Self, String> {
+        match raw
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("classic") | Some("default") | Some("steve") => {
+                Ok(Self::Classic)
+            }
+            Some("slim") | Some("alex") => Ok(Self::Slim),
+            Some(other) => Err(format!("Variante de skin no soportada: {other}")),
+        }
+    }
+
+    pub fn as_api_str(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Slim => "slim",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinCommandArgs {
+    #[serde(default, alias = "account_id")]
+    pub account_id: Option<String>,
+    #[serde(default, alias = "file_path")]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default, alias = "cape_id")]
+    pub cape_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileTexture {
+    pub id: String,
+    pub state: String,
+    pub url: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub texture_hash: Option<String>,
+    #[serde(default)]
+    pub cached_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub skins: Vec<ProfileTexture>,
+    #[serde(default)]
+    pub capes: Vec<ProfileTexture>,
+}
+
+/// Cliente de la API de perfiles de Minecraft para skins y capas.
+pub struct SkinService {
+    client: reqwest::Client,
+    services_base: String,
+    access_token: String,
+    cache_dir: PathBuf,
+}
+
+impl SkinService {
+    pub fn new(
+        client: reqwest::Client,
+        services_base: impl Into<String>,
+        access_token: impl Into<String>,
+        cache_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            client,
+            services_base: services_base.into().trim_end_matches('/').to_string(),
+            access_token: access_token.into(),
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    pub async fn profile(&self) -> Result<SkinProfile, String> {
+        let request = self
+            .client
+            .get(format!("{}/minecraft/profile", self.services_base));
+        self.send_profile_request(request, "consultar perfil").await
+    }
+
+    pub async fn upload_skin(
+        &self,
+        png: Vec<u8>,
+        variant: SkinVariant,
+    ) -> Result<SkinProfile, String> {
+        validate_skin_png(&png)?;
+        let part = reqwest::multipart::Part::bytes(png)
+            .file_name("skin.png")
+            .mime_str("image/png")
+            .map_err(|error| format!("No se pudo preparar la skin: {error}"))?;
+        let form = reqwest::multipart::Form::new()
+            .text("variant", variant.as_api_str())
+            .part("file", part);
+        let request = self
+            .client
+            .post(format!("{}/minecraft/profile/skins", self.services_base))
+            .multipart(form);
+        self.send_profile_request(request, "subir skin").await
+    }
+
+    pub async fn set_skin_url(
+        &self,
+        url: &str,
+        variant: SkinVariant,
+    ) -> Result<SkinProfile, String> {
+        let parsed = reqwest::Url::parse(url.trim())
+            .map_err(|error| format!("URL de skin inválida: {error}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("La URL de la skin debe ser http o https.".to_string());
+        }
+        let request = self
+            .client
+            .post(format!("{}/minecraft/profile/skins", self.services_base))
+            .json(&serde_json::json!({"variant": variant.as_api_str(), "url": parsed.as_str()}));
+        self.send_profile_request(request, "cambiar skin por URL")
+            .await
+    }
+
+    pub async fn reset_skin(&self) -> Result<SkinProfile, String> {
+        let request = self.client.delete(format!(
+            "{}/minecraft/profile/skins/active",
+            self.services_base
+        ));
+        self.send_profile_request(request, "restablecer skin").await
+    }
+
+    pub async fn set_active_cape(&self, cape_id: Option<&str>) -> Result<SkinProfile, String> {
+        let url = format!("{}/minecraft/profile/capes/active", self.services_base);
+        match cape_id.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(cape_id) => {
+                let request = self
+                    .client
+                    .put(url)
+                    .json(&serde_json::json!({"capeId": cape_id}));
+                self.send_profile_request(request, "activar capa").await
+            }
+            None => {
+                let request = self.client.delete(url);
+                self.send_profile_request(request, "ocultar capa").await
+            }
+        }
+    }
+
+    async fn send_profile_request(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<SkinProfile, String> {
+        let response = request
+            .bearer_auth(&self.access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("No se pudo {action}: {error}"))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(format!(
+                "No se pudo {action}: el token de Minecraft expiró; refresca la cuenta."
+            ));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("No se pudo {action} ({status}): {body}"));
+        }
+        let mut profile = response
+            .json::<SkinProfile>()
+            .await
+            .map_err(|error| format!("Perfil de skins inválido: {error}"))?;
+        self.cache_textures(&mut profile).await;
+        if let Err(error) = self.store_profile(&profile) {
+            eprintln!("[skins] {error}");
+        }
+        Ok(profile)
+    }
+
+    fn store_profile(&self, profile: &SkinProfile) -> Result<(), String> {
+        fs::create_dir_all(&self.cache_dir)
+            .map_err(|error| format!("No se pudo crear caché de texturas: {error}"))?;
+        let raw = serde_json::to_vec_pretty(profile)
+            .map_err(|error| format!("No se pudo serializar perfil de skins: {error}"))?;
+        fs::write(profile_cache_path(&self.cache_dir, &profile.id), raw)
+            .map_err(|error| format!("No se pudo guardar perfil de skins: {error}"))
+    }
+
+    /// Guarda cada textura por hash para poder pintar avatares sin conexión.
+    async fn cache_textures(&self, profile: &mut SkinProfile) {
+        for texture in profile.skins.iter_mut().chain(profile.capes.iter_mut()) {
+            let Some(hash) = texture_hash_from_url(&texture.url) else {
+                continue;
+            };
+            let target = self.cache_dir.join(format!("{hash}.png"));
+            if !target.is_file() {
+                if let Err(error) = self.download_texture(&texture.url, &target).await {
+                    eprintln!("[skins] {error}");
+                }
+            }
+            if target.is_file() {
+                texture.cached_path = Some(target.to_string_lossy().to_string());
+            }
+            texture.texture_hash = Some(hash);
+        }
+    }
+
+    async fn download_texture(&self, url: &str, target: &Path) -> Result<(), String> {
+        let bytes = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| format!("No se pudo descargar textura {url}: {error}"))?
+            .bytes()
+            .await
+            .map_err(|error| format!("No se pudo leer textura {url}: {error}"))?;
+        if !bytes.starts_with(PNG_SIGNATURE) {
+            return Err(format!("La textura {url} no es un PNG válido."));
+        }
+        fs::create_dir_all(&self.cache_dir)
+            .map_err(|error| format!("No se pudo crear caché de texturas: {error}"))?;
+        let temp = target.with_extension("png.part");
+        fs::write(&temp, &bytes).map_err(|error| format!("No se pudo guardar textura: {error}"))?;
+        fs::rename(&temp, target).map_err(|error| format!("No se pudo mover textura: {error}"))
+    }
+}
+
+fn profile_cache_path(cache_dir: &Path, profile_id: &str) -> PathBuf {
+    let id = profile_id.replace('-', "").to_ascii_lowercase();
+    cache_dir.join(format!("profile-{id}.json"))
+}
+
+/// Último perfil consultado con éxito; sólo conserva `cached_path` de texturas que
+/// siguen en disco.
+pub fn cached_skin_profile(cache_dir: &Path, profile_id: &str) -> Option<SkinProfile> {
+    let raw = fs::read(profile_cache_path(cache_dir, profile_id)).ok()?;
+    let mut profile = serde_json::from_slice::<SkinProfile>(&raw).ok()?;
+    for texture in profile.skins.iter_mut().chain(profile.capes.iter_mut()) {
+        texture.cached_path = texture
+            .texture_hash
+            .as_deref()
+            .map(|hash| cache_dir.join(format!("{hash}.png")))
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().to_string());
+    }
+    Some(profile)
+}
+
+pub fn texture_hash_from_url(url: &str) -> Option<String> {
+    let hash = url
+        .split(['?', '#'])
+        .next()?
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()?
+        .to_ascii_lowercase();
+    if hash.len() >= 32 && hash.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        Some(hash)
+    } else {
+        None
+    }
+}
+
+/// Comprueba firma PNG y dimensiones de la cabecera IHDR (64x64 moderna o 64x32 legacy).
+pub fn validate_skin_png(bytes: &[u8]) -> Result<(u32, u32), String> {
+    if bytes.len() < 24 || !bytes.starts_with(PNG_SIGNATURE) || &bytes[12..16] != b"IHDR" {
+        return Err("El archivo de skin no es un PNG válido.".to_string());
+    }
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    match (width, height) {
+        (64, 64) | (64, 32) => Ok((width, height)),
+        _ => Err(format!(
+            "Dimensiones de skin no soportadas: {width}x{height} (se admite 64x64 o 64x32)."
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde_json::json;
+
+    use super::{
+        cached_skin_profile, texture_hash_from_url, validate_skin_png, SkinService, SkinVariant,
+    };
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn validates_skin_png_dimensions() {
+        assert_eq!(validate_skin_png(&png_header(64, 64)), Ok((64, 64)));
+        assert_eq!(validate_skin_png(&png_header(64, 32)), Ok((64, 32)));
+        assert!(validate_skin_png(&png_header(128, 128)).is_err());
+        assert!(validate_skin_png(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn parses_variants_and_texture_hashes() {
+        assert_eq!(SkinVariant::parse(Some("Slim")), Ok(SkinVariant::Slim));
+        assert_eq!(SkinVariant::parse(None), Ok(SkinVariant::Classic));
+        assert!(SkinVariant::parse(Some("wide")).is_err());
+        assert_eq!(
+            texture_hash_from_url(
+                "http://textures.minecraft.net/texture/1a4af718455d4aab528e7a61f86fa25e6a369d1768dcb13f7df319a713eb810b"
+            )
+            .as_deref(),
+            Some("1a4af718455d4aab528e7a61f86fa25e6a369d1768dcb13f7df319a713eb810b")
+        );
+        assert_eq!(texture_hash_from_url("https://example.com/skin.png"), None);
+    }
+
+    #[test]
+    fn uploads_skin_and_caches_textures_by_hash() {
+        let hash = "a".repeat(64);
+        let texture = png_header(64, 64);
+        let texture_for_server = texture.clone();
+        let base = spawn_mock_server(move |request| {
+            let host = request.header("host").unwrap_or_default().to_string();
+            let profile = json!({
+                "id": "069a79f444e94726a5befca90e38aaf5",
+                "name": "Notch",
+                "skins": [{
+                    "id": "skin-1",
+                    "state": "ACTIVE",
+                    "url": format!("http://{host}/texture/{}", "a".repeat(64)),
+                    "variant": "SLIM"
+                }],
+                "capes": [{"id": "cape-1", "state": "INACTIVE", "url": format!("http://{host}/texture/nothex"), "alias": "Migrator"}]
+            });
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path)
+                    if request.header("authorization") != Some("Bearer mc-token")
+                        && !path.starts_with("/texture/") =>
+                {
+                    MockResponse::json(401, json!({}))
+                }
+                ("POST", "/minecraft/profile/skins")
+                    if request.body_text().contains("name=\"variant\"")
+                        && request.body_text().contains("slim") =>
+                {
+                    MockResponse::json(200, profile)
+                }
+                ("GET", path) if path.starts_with("/texture/") => MockResponse {
+                    status: 200,
+                    headers: Vec::new(),
+                    body: texture_for_server.clone(),
+                },
+                _ => MockResponse::json(404, json!({})),
+            }
+        });
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let cache_dir = std::env::temp_dir().join(format!("frutistudio-test-skins-{unique}"));
+        let service = SkinService::new(reqwest::Client::new(), &base, "mc-token", &cache_dir);
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+
+        assert!(rt
+            .block_on(service.upload_skin(png_header(32, 32), SkinVariant::Slim))
+            .is_err());
+        let profile = rt
+            .block_on(service.upload_skin(texture.clone(), SkinVariant::Slim))
+            .expect("profile");
+
+        assert_eq!(
+            profile.skins[0].texture_hash.as_deref(),
+            Some(hash.as_str())
+        );
+        let cached = cache_dir.join(format!("{hash}.png"));
+        assert_eq!(
+            profile.skins[0].cached_path.as_deref(),
+            Some(cached.to_string_lossy().as_ref())
+        );
+        assert_eq!(fs::read(&cached).expect("cached texture"), texture);
+        assert!(profile.capes[0].cached_path.is_none());
+
+        let offline = cached_skin_profile(&cache_dir, "069a79f4-44e9-4726-a5be-fca90e38aaf5")
+            .expect("cached profile");
+        assert_eq!(offline.name, "Notch");
+        assert_eq!(offline.skins[0].cached_path, profile.skins[0].cached_path);
+        fs::remove_file(&cached).expect("remove texture");
+        let offline = cached_skin_profile(&cache_dir, "069a79f444e94726a5befca90e38aaf5")
+            .expect("cached profile");
+        assert!(offline.skins[0].cached_path.is_none());
+
+        let expired = SkinService::new(reqwest::Client::new(), &base, "stale", &cache_dir);
+        assert!(rt
+            .block_on(expired.reset_skin())
+            .unwrap_err()
+            .contains("expiró"));
+
+        fs::remove_dir_all(cache_dir).expect("cleanup");
+    }
+}
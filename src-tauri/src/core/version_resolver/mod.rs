@@ -1,14 +1,31 @@
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use serde::{Deserialize, Serialize};
 
+use crate::core::download_routes::read_local_mirror;
+use crate::core::http_cache::{FetchSource, HttpMetadataCache};
+
 pub const MOJANG_VERSION_MANIFEST: &str =
     "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 
+/// Archivos de la caché propia del manifiesto en versiones anteriores, en `metadata/`.
+const LEGACY_MANIFEST_CACHE_FILE: &str = "version_manifest_v2.json";
+const LEGACY_MANIFEST_META_FILE: &str = "version_manifest_v2.meta.json";
+
+pub const VERSION_KINDS: [&str; 4] = ["release", "snapshot", "old_beta", "old_alpha"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionManifestRef {
     pub id: String,
     pub kind: String,
     pub url: String,
+    #[serde(default)]
+    pub release_time: Option<String>,
+    #[serde(default)]
+    pub sha1: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,3 +38,437 @@ pub struct ResolvedVersion {
 pub trait VersionResolver {
     fn resolve(&self, mc_version: &str) -> Result<ResolvedVersion, String>;
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLatest {
+    pub release: String,
+    pub snapshot: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestVersionEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+    #[serde(default)]
+    pub release_time: Option<String>,
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionManifest {
+    pub latest: ManifestLatest,
+    pub versions: Vec<ManifestVersionEntry>,
+}
+
+impl VersionManifest {
+    /// Traduce alias (`latest-release`, `latest-snapshot`) al id concreto.
+    pub fn resolve_alias<'a>(&'a self, requested: &'a str) -> &'a str {
+        match requested.trim().to_ascii_lowercase().as_str() {
+            "latest" | "latest-release" | "latest_release" | "release" => &self.latest.release,
+            "latest-snapshot" | "latest_snapshot" | "snapshot" => &self.latest.snapshot,
+            _ => requested.trim(),
+        }
+    }
+
+    /// Versiones en el orden del manifiesto (más recientes primero), filtradas por tipo.
+    pub fn list(&self, kinds: &[String]) -> Vec<VersionManifestRef> {
+        self.versions
+            .iter()
+            .filter(|entry| kinds.is_empty() || kinds.iter().any(|kind| kind == &entry.kind))
+            .map(|entry| VersionManifestRef {
+                id: entry.id.clone(),
+                kind: entry.kind.clone(),
+                url: entry.url.clone(),
+                release_time: entry.release_time.clone(),
+                sha1: entry.sha1.clone(),
+            })
+            .collect()
+    }
+}
+
+impl VersionResolver for VersionManifest {
+    fn resolve(&self, mc_version: &str) -> Result<ResolvedVersion, String> {
+        let id = self.resolve_alias(mc_version);
+        self.versions
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| ResolvedVersion {
+                mc_version: entry.id.clone(),
+                version_json_url: entry.url.clone(),
+            })
+            .ok_or_else(|| format!("La versión {id} no existe en el manifiesto oficial."))
+    }
+}
+
+pub fn parse_version_kinds(raw: &[String]) -> Result<Vec<String>, String> {
+    raw.iter()
+        .map(|kind| kind.trim().to_ascii_lowercase())
+        .filter(|kind| !kind.is_empty())
+        .map(|kind| {
+            if VERSION_KINDS.contains(&kind.as_str()) {
+                Ok(kind)
+            } else {
+                Err(format!(
+                    "Tipo de versión no soportado: {kind} (usa {}).",
+                    VERSION_KINDS.join(", ")
+                ))
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestSource {
+    Network,
+    Revalidated,
+    Cache,
+    OfflineCache,
+}
+
+impl From<FetchSource> for ManifestSource {
+    fn from(source: FetchSource) -> Self {
+        match source {
+            FetchSource::Network => Self::Network,
+            FetchSource::Revalidated => Self::Revalidated,
+            FetchSource::Cache => Self::Cache,
+            FetchSource::Stale => Self::OfflineCache,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedManifest {
+    pub manifest: VersionManifest,
+    pub source: ManifestSource,
+    pub fetched_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinecraftVersionList {
+    pub latest_release: String,
+    pub latest_snapshot: String,
+    pub versions: Vec<VersionManifestRef>,
+    pub source: ManifestSource,
+    pub fetched_at: u64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMinecraftVersionsArgs {
+    #[serde(default, alias = "kinds")]
+    pub types: Vec<String>,
+    #[serde(default, alias = "force_refresh")]
+    pub force_refresh: bool,
+}
+
+/// Resolver del manifiesto sobre `HttpMetadataCache`: el TTL, la revalidación con
+/// ETag/Last-Modified y la copia sin red son los de la caché de metadata compartida.
+pub struct CachedVersionResolver<'a> {
+    client: reqwest::Client,
+    manifest_urls: Vec<String>,
+    cache: &'a HttpMetadataCache,
+}
+
+impl<'a> CachedVersionResolver<'a> {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        manifest_urls: Vec<String>,
+        cache: &'a HttpMetadataCache,
+    ) -> Self {
+        Self {
+            client,
+            manifest_urls,
+            cache,
+        }
+    }
+
+    /// Pasa la copia de `legacy_dir/version_manifest_v2.json` a la caché de metadata con sus
+    /// validadores y borra los archivos viejos.
+    pub(crate) fn migrate_legacy_cache(&self, legacy_dir: &Path) {
+        let manifest_path = legacy_dir.join(LEGACY_MANIFEST_CACHE_FILE);
+        let meta_path = legacy_dir.join(LEGACY_MANIFEST_META_FILE);
+        if !self.cache.has_store() {
+            return;
+        }
+        let Ok(body) = fs::read_to_string(&manifest_path) else {
+            return;
+        };
+        if serde_json::from_str::<VersionManifest>(&body).is_ok() {
+            let meta = fs::read(&meta_path)
+                .ok()
+                .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok())
+                .unwrap_or_default();
+            let text = |key: &str| meta.get(key).and_then(|value| value.as_str());
+            let url = text("sourceUrl")
+                .filter(|url| self.manifest_urls.iter().any(|known| known == url))
+                .or_else(|| self.manifest_urls.first().map(String::as_str));
+            if let Some(url) = url {
+                self.cache.seed(
+                    url,
+                    body,
+                    meta.get("fetchedAt")
+                        .and_then(|value| value.as_u64())
+                        .unwrap_or_default(),
+                    text("etag").map(str::to_string),
+                    text("lastModified").map(str::to_string),
+                );
+            }
+        }
+        let _ = fs::remove_file(manifest_path);
+        let _ = fs::remove_file(meta_path);
+    }
+
+    /// Una copia caducada sólo se usa si ningún endpoint responde.
+    pub async fn load_manifest(&self, force_refresh: bool) -> Result<LoadedManifest, String> {
+        let mut stale = None;
+        let mut last_error = None;
+        for url in &self.manifest_urls {
+            if let Some(local) = read_local_mirror(url) {
+                match local.and_then(|raw| {
+                    serde_json::from_slice::<VersionManifest>(&raw)
+                        .map_err(|error| format!("Manifiesto inválido en {url}: {error}"))
+                }) {
+                    Ok(manifest) => {
+                        return Ok(LoadedManifest {
+                            manifest,
+                            source: ManifestSource::Network,
+                            fetched_at: unix_now_secs(),
+                        })
+                    }
+                    Err(error) => last_error = Some(error),
+                }
+                continue;
+            }
+
+            let fetched = match self
+                .cache
+                .fetch_persistent(self.client.get(url), url, force_refresh)
+                .await
+            {
+                Ok(fetched) => fetched,
+                Err(error) => {
+                    last_error = Some(error.message);
+                    continue;
+                }
+            };
+            let manifest = match serde_json::from_str::<VersionManifest>(&fetched.body) {
+                Ok(manifest) => manifest,
+                Err(error) => {
+                    self.cache.invalidate(url);
+                    last_error = Some(format!("Manifiesto inválido en {url}: {error}"));
+                    continue;
+                }
+            };
+            let loaded = LoadedManifest {
+                manifest,
+                source: fetched.source.into(),
+                fetched_at: fetched.fetched_at,
+            };
+            if loaded.source != ManifestSource::OfflineCache {
+                return Ok(loaded);
+            }
+            stale.get_or_insert(loaded);
+        }
+
+        stale.ok_or_else(|| {
+            format!(
+                "No se pudo obtener el manifiesto de versiones y no hay copia en caché: {}",
+                last_error.unwrap_or_else(|| "sin endpoints configurados".to_string())
+            )
+        })
+    }
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use serde_json::json;
+
+    use super::{parse_version_kinds, CachedVersionResolver, ManifestSource, VersionResolver};
+    use crate::core::config::HttpCacheConfig;
+    use crate::core::http_cache::HttpMetadataCache;
+    use crate::core::mock_http::{spawn_mock_server, MockResponse};
+
+    fn manifest_json() -> serde_json::Value {
+        json!({
+            "latest": {"release": "1.21.4", "snapshot": "25w02a"},
+            "versions": [
+                {"id": "25w02a", "type": "snapshot", "url": "https://meta/25w02a.json", "releaseTime": "2025-01-08T00:00:00+00:00"},
+                {"id": "1.21.4", "type": "release", "url": "https://meta/1.21.4.json", "sha1": "abc"},
+                {"id": "b1.7.3", "type": "old_beta", "url": "https://meta/b1.7.3.json"},
+                {"id": "a1.0.4", "type": "old_alpha", "url": "https://meta/a1.0.4.json"}
+            ]
+        })
+    }
+
+    fn temp_cache_dir(label: &str) -> std::path::PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!("frutistudio-test-manifest-{label}-{unique}"))
+    }
+
+    #[test]
+    fn resolves_aliases_and_filters_by_kind() {
+        let manifest: super::VersionManifest =
+            serde_json::from_value(manifest_json()).expect("manifest");
+        assert_eq!(
+            manifest
+                .resolve("latest-release")
+                .expect("release")
+                .mc_version,
+            "1.21.4"
+        );
+        assert_eq!(
+            manifest
+                .resolve("latest-snapshot")
+                .expect("snapshot")
+                .version_json_url,
+            "https://meta/25w02a.json"
+        );
+        assert!(manifest.resolve("9.9.9").is_err());
+
+        let kinds =
+            parse_version_kinds(&["old_beta".to_string(), "OLD_ALPHA".to_string()]).expect("kinds");
+        let ids = manifest
+            .list(&kinds)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["b1.7.3", "a1.0.4"]);
+        assert_eq!(manifest.list(&[]).len(), 4);
+        assert!(parse_version_kinds(&["beta".to_string()]).is_err());
+    }
+
+    #[test]
+    fn caches_revalidates_and_falls_back_offline() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let down = Arc::new(AtomicBool::new(false));
+        let server_hits = hits.clone();
+        let server_down = down.clone();
+        let base = spawn_mock_server(move |request| {
+            server_hits.fetch_add(1, Ordering::SeqCst);
+            if server_down.load(Ordering::SeqCst) {
+                return MockResponse::json(503, json!({}));
+            }
+            if request.header("if-none-match") == Some("\"v1\"") {
+                return MockResponse {
+                    status: 304,
+                    headers: Vec::new(),
+                    body: Vec::new(),
+                };
+            }
+            let mut response = MockResponse::json(200, manifest_json());
+            response
+                .headers
+                .push(("ETag".to_string(), "\"v1\"".to_string()));
+            response
+        });
+
+        let cache_dir = temp_cache_dir("cache");
+        let cache = HttpMetadataCache::default();
+        cache.attach_store(cache_dir.clone());
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let resolver = CachedVersionResolver::new(
+            reqwest::Client::new(),
+            vec![
+                "http://127.0.0.1:9/version_manifest_v2.json".to_string(),
+                format!("{base}/version_manifest_v2.json"),
+            ],
+            &cache,
+        );
+
+        let first = rt.block_on(resolver.load_manifest(false)).expect("network");
+        assert_eq!(first.source, ManifestSource::Network);
+        let second = rt.block_on(resolver.load_manifest(false)).expect("cache");
+        assert_eq!(second.source, ManifestSource::Cache);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let revalidated = rt.block_on(resolver.load_manifest(true)).expect("304");
+        assert_eq!(revalidated.source, ManifestSource::Revalidated);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        down.store(true, Ordering::SeqCst);
+        let fallback = rt.block_on(resolver.load_manifest(true)).expect("offline");
+        assert_eq!(fallback.source, ManifestSource::OfflineCache);
+        assert_eq!(fallback.manifest.latest.release, "1.21.4");
+
+        fs::remove_dir_all(&cache_dir).expect("cleanup");
+        assert!(rt.block_on(resolver.load_manifest(true)).is_err());
+
+        // Con la caché desactivada el manifiesto se sigue guardando para jugar sin red.
+        cache.configure(Some(&HttpCacheConfig {
+            enabled: Some(false),
+            ..HttpCacheConfig::default()
+        }));
+        down.store(false, Ordering::SeqCst);
+        let online = rt.block_on(resolver.load_manifest(false)).expect("online");
+        assert_eq!(online.source, ManifestSource::Network);
+        down.store(true, Ordering::SeqCst);
+        let offline = rt.block_on(resolver.load_manifest(false)).expect("offline");
+        assert_eq!(offline.source, ManifestSource::OfflineCache);
+
+        fs::remove_dir_all(&cache_dir).expect("cleanup");
+    }
+
+    #[test]
+    fn migrates_the_legacy_manifest_cache() {
+        let legacy_dir = temp_cache_dir("legacy");
+        fs::create_dir_all(&legacy_dir).expect("legacy");
+        fs::write(
+            legacy_dir.join("version_manifest_v2.json"),
+            manifest_json().to_string(),
+        )
+        .expect("manifest");
+        fs::write(
+            legacy_dir.join("version_manifest_v2.meta.json"),
+            json!({"fetchedAt": 5, "etag": "\"v1\"", "sourceUrl": "http://127.0.0.1:9/b.json"})
+                .to_string(),
+        )
+        .expect("meta");
+
+        let cache_dir = temp_cache_dir("migrated");
+        let cache = HttpMetadataCache::default();
+        cache.attach_store(cache_dir.clone());
+        let resolver = CachedVersionResolver::new(
+            reqwest::Client::new(),
+            vec![
+                "http://127.0.0.1:9/a.json".to_string(),
+                "http://127.0.0.1:9/b.json".to_string(),
+            ],
+            &cache,
+        );
+        resolver.migrate_legacy_cache(&legacy_dir);
+        assert!(!legacy_dir.join("version_manifest_v2.json").exists());
+        assert!(!legacy_dir.join("version_manifest_v2.meta.json").exists());
+
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let loaded = rt
+            .block_on(resolver.load_manifest(false))
+            .expect("migrated");
+        assert_eq!(loaded.source, ManifestSource::OfflineCache);
+        assert_eq!(loaded.fetched_at, 5);
+        assert_eq!(loaded.manifest.latest.release, "1.21.4");
+
+        fs::remove_dir_all(&legacy_dir).expect("cleanup legacy");
+        fs::remove_dir_all(&cache_dir).expect("cleanup");
+    }
+}
@@ -9,8 +9,9 @@ use std::io::{Read, Write};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use futures::stream::{self, StreamExt, TryStreamExt};
 use reqwest::header::{HeaderMap, HeaderValue};
@@ -25,22 +26,48 @@ use serde_json::Value;
 use sha1::{Digest, Sha1};
 use sha2::Sha256;
 use tauri::command;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tauri_plugin_dialog::{DialogExt, FilePath};
 use tokio::io::{AsyncWriteExt, BufWriter};
-use tokio::sync::{oneshot, Semaphore};
+use tokio::sync::oneshot;
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
+use crate::core::accounts::{
+    AccountSummary, AccountVault, StoredAccount, ACCOUNTS_SCHEMA, ACCOUNT_KIND_MSA,
+    ACCOUNT_KIND_OFFLINE, ACCOUNT_KIND_YGGDRASIL,
+};
+use crate::core::auth::microsoft::{
+    MicrosoftAuthEndpoints, MicrosoftAuthProvider, MicrosoftDeviceCode, MicrosoftSession,
+};
+use crate::core::auth::yggdrasil::{
+    encode_prefetched_metadata, ensure_authlib_injector_jar, AuthlibInjectorAgent,
+    YggdrasilAuthProvider, YggdrasilSession, AUTHLIB_INJECTOR_LATEST_URL,
+};
+use crate::core::auth::MINECRAFT_SERVICES_BASE;
+use crate::core::bandwidth::BandwidthLimiter;
 use crate::core::config::{
     AppConfig, BaseDirValidationResult, LauncherFactoryResetArgs, LauncherFactoryResetResult,
-    NetworkTuning, StartupFileEntry,
+    NetworkTuning, ProxyConfig, StartupFileEntry,
+};
+use crate::core::download_cache::{
+    DownloadCachePrunePolicy, DownloadCachePruneReport, DownloadCacheStats,
+    DownloadCacheVerifyReport, GlobalDownloadCache,
 };
+use crate::core::download_journal::DownloadJournal;
 use crate::core::download_routes;
+use crate::core::downloader::{
+    DownloadItem, DownloadPriority, DownloadQueueSnapshot, DownloadRequest, Downloader,
+};
 use crate::core::external_discovery::{
     detect_external_instances, launcher_from_hint, read_external_discovery_cache,
     scan_external_instances, write_external_discovery_cache, ExternalScanOptions,
 };
+use crate::core::forge_processors::{
+    client_processors, library_path, processor_library_coordinates, run_processors,
+    ProcessorEnvironment,
+};
+use crate::core::http_cache::HttpMetadataCache;
 use crate::core::instance::{
     ExternalDetectedInstance, ExternalImportArgs, ExternalScanArgs, ExternalScanReport,
     InstalledModEntry, InstanceArchiveArgs, InstanceCommandArgs, InstancePathArgs, InstanceRecord,
@@ -48,6 +75,9 @@ use crate::core::instance::{
 };
 use crate::core::instance_config::{instance_game_dir, resolve_instance_launch_config};
 use crate::core::java::{JavaManager, JavaResolution, JavaRuntime};
+use crate::core::java_probe::{
+    probe_java, rescan_java_candidates, JavaRescanReport, JAVA_PROBES_SCHEMA, JAVA_RESCAN_EVENT,
+};
 use crate::core::java_resolver::{required_java_major, required_java_major_for_version};
 use crate::core::launch_pipeline::{LauncherDataLayout, CANONICAL_LAUNCHER_DIRS};
 use crate::core::launcher::{
@@ -59,21 +89,44 @@ use crate::core::launcher_discovery::{
     accepted_main_classes_for_loader, detect_loader_from_version_json,
     detect_minecraft_launcher_installations,
 };
+use crate::core::legacy_assets::{game_assets_dir, materialize_legacy_assets, AssetLayout};
 use crate::core::loader_normalizer::{
     normalize_loader_profile as normalize_loader_profile_core, sanitize_version_json_library_typos,
 };
+use crate::core::logging_config::{plain_text_logging_config, LogFormat, LoggingClientConfig};
 use crate::core::maven_loader::{
     default_repositories, parse_install_profile_libraries, repositories_for_library,
     resolve_transitive_dependencies, MavenCoordinate,
 };
+use crate::core::mirror_health::{url_host, MirrorHealth, MirrorHostReport};
 use crate::core::mods::ModDownloadIntegrity;
 use crate::core::network::{
     CurseforgeDownloadResolution, CurseforgeFileEnvelope, CurseforgeFingerprintsEnvelope,
     CurseforgeModEnvelope, DownloadTrace, FingerprintFileResult, FingerprintScanResult,
-    FingerprintsRequestBody, ModpackAction, MojangVersionManifest, SelectFolderResult,
+    FingerprintsRequestBody, ModpackAction, SelectFolderResult,
+};
+use crate::core::offline_launch::{network_reachable, verify_offline_launch, OfflineLaunchReport};
+use crate::core::player_data::{
+    migrate_player_data, offline_player_uuid, PlayerDataMigrationReport,
 };
+use crate::core::progress::{ProgressReporter, BOOTSTRAP_PROGRESS_EVENT};
+use crate::core::proxy::configure_client_proxy;
+use crate::core::redaction::{redact_args, redact_json, redact_lines, redact_text};
 use crate::core::repair::{RepairMode, RepairReport};
-use crate::core::runtime_manager::RuntimeManager;
+use crate::core::runtime_manager::{
+    configure_runtime_providers, managed_runtime_java, ManagedRuntimeArgs, ManagedRuntimeInfo,
+    ManagedRuntimeIntegrity, RuntimeManager, RuntimeUpdateInfo,
+};
+use crate::core::segmented_download::{
+    download_segmented, SegmentedDownloadError, SEGMENTED_DOWNLOAD_THRESHOLD,
+};
+use crate::core::skins::{
+    cached_skin_profile, validate_skin_png, SkinCommandArgs, SkinProfile, SkinService, SkinVariant,
+};
+use crate::core::version_resolver::{
+    parse_version_kinds, CachedVersionResolver, ListMinecraftVersionsArgs, MinecraftVersionList,
+    VersionResolver,
+};
 
 fn copy_if_missing(from: &Path, to: &Path) -> Result<bool, String> {
     if !from.exists() || to.exists() {
@@ -225,6 +278,32 @@ fn resolve_curseforge_api_key(api_key_override: Option<&str>) -> Result<String,
     Err("No se encontró CURSEFORGE_API_KEY en el backend. Configúrala en variables de entorno del proceso Tauri.".to_string())
 }
 
+fn resolve_msa_client_id() -> Result<String, String> {
+    let env_keys = ["FRUTI_MSA_CLIENT_ID", "MSA_CLIENT_ID", "VITE_MSA_CLIENT_ID"];
+    for key in env_keys {
+        if let Ok(value) = std::env::var(key) {
+            let trimmed = value.trim();
+            if !trimmed.is_empty() {
+                return Ok(trimmed.to_string());
+            }
+        }
+
+        if let Some(value) = resolve_env_file_value(key) {
+            return Ok(value);
+        }
+    }
+
+    Err("No se encontró MSA_CLIENT_ID en el backend. Configúrala para iniciar login real de Microsoft.".to_string())
+}
+
+fn microsoft_auth_provider(tuning: &NetworkTuning) -> Result<MicrosoftAuthProvider, String> {
+    Ok(MicrosoftAuthProvider::new(
+        http_client_with_tuning(tuning)?,
+        resolve_msa_client_id()?,
+        MicrosoftAuthEndpoints::default(),
+    ))
+}
+
 fn curseforge_headers(api_key: &str) -> Result<HeaderMap, String> {
     let mut headers = HeaderMap::new();
     let key =
@@ -292,6 +371,15 @@ fn database_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
         .map_err(|error| format!("No se pudo obtener el directorio de datos: {error}"))
 }
 
+fn account_vault(app: &tauri::AppHandle) -> Result<AccountVault, String> {
+    let key_path = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("account-vault.key"))
+        .map_err(|error| format!("No se pudo obtener el directorio de datos: {error}"))?;
+    AccountVault::open_or_create_key(&key_path)
+}
+
 fn database_connection(app: &tauri::AppHandle) -> Result<Connection, String> {
     init_database(app)?;
     let path = database_path(app)?;
@@ -327,6 +415,19 @@ fn init_database(app: &tauri::AppHandle) -> Result<(), String> {
         );",
     )
     .map_err(|error| format!("No se pudo inicializar la base: {error}"))?;
+    conn.execute_batch(ACCOUNTS_SCHEMA)
+        .map_err(|error| format!("No se pudo inicializar tabla de cuentas: {error}"))?;
+    conn.execute_batch(JAVA_PROBES_SCHEMA)
+        .map_err(|error| format!("No se pudo inicializar tabla de sondeos Java: {error}"))?;
+    conn.execute("ALTER TABLE accounts ADD COLUMN auth_server TEXT", [])
+        .or_else(|error| {
+            if error.to_string().contains("duplicate column name") {
+                Ok(0)
+            } else {
+                Err(error)
+            }
+        })
+        .map_err(|error| format!("No se pudo migrar columna auth_server: {error}"))?;
 
     conn.execute("ALTER TABLE instances ADD COLUMN loader_name TEXT", [])
         .or_else(|error| {
@@ -401,6 +502,16 @@ fn init_database(app: &tauri::AppHandle) -> Result<(), String> {
         })
         .map_err(|error| format!("No se pudo migrar columna java_path: {error}"))?;
 
+    conn.execute("ALTER TABLE instances ADD COLUMN log_format TEXT", [])
+        .or_else(|error| {
+            if error.to_string().contains("duplicate column name") {
+                Ok(0)
+            } else {
+                Err(error)
+            }
+        })
+        .map_err(|error| format!("No se pudo migrar columna log_format: {error}"))?;
+
     conn.execute("ALTER TABLE instances ADD COLUMN path TEXT", [])
         .or_else(|error| {
             if error.to_string().contains("duplicate column name") {
@@ -450,7 +561,7 @@ fn env_u64(key: &str, default_value: u64) -> u64 {
         .unwrap_or(default_value)
 }
 
-fn asset_mirror_bases() -> Vec<&'static str> {
+fn asset_mirror_bases() -> Vec<String> {
     let mut mirrors = ASSET_MIRROR_BASES.to_vec();
     let include_legacy_mcbbs = std::env::var("FRUTI_ENABLE_MCBBS_MIRROR")
         .ok()
@@ -464,7 +575,7 @@ fn asset_mirror_bases() -> Vec<&'static str> {
     if include_legacy_mcbbs {
         mirrors.push(ASSET_MIRROR_MCBBS);
     }
-    mirrors
+    download_routes::apply_endpoint_overrides(mirrors.into_iter().map(str::to_string).collect())
 }
 
 fn resolve_network_tuning(config: Option<&AppConfig>) -> NetworkTuning {
@@ -473,33 +584,35 @@ fn resolve_network_tuning(config: Option<&AppConfig>) -> NetworkTuning {
         .unwrap_or_default()
 }
 
-fn http_client_with_tuning(tuning: &NetworkTuning) -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
+static ACTIVE_NETWORK_TUNING: Lazy<RwLock<NetworkTuning>> =
+    Lazy::new(|| RwLock::new(NetworkTuning::default()));
+static DOWNLOAD_CACHE_HARD_LINKS: AtomicBool = AtomicBool::new(false);
+
+/// Ajustes de red vigentes; se actualizan con `apply_runtime_settings`.
+fn active_network_tuning() -> NetworkTuning {
+    ACTIVE_NETWORK_TUNING
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Único punto de construcción de clientes HTTP: timeouts y proxy de `NetworkTuning`.
+/// Quien necesite otros timeouts los sobrescribe sobre el builder.
+fn http_client_builder(tuning: &NetworkTuning) -> Result<reqwest::ClientBuilder, String> {
+    let builder = reqwest::Client::builder()
         .connect_timeout(Duration::from_secs(tuning.connect_timeout_secs.max(1)))
         .timeout(Duration::from_secs(tuning.request_timeout_secs.max(1)))
         .pool_max_idle_per_host(32)
         .tcp_keepalive(Duration::from_secs(30))
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) InterfaceLauncher/1.0")
-        .build()
-        .map_err(|error| format!("No se pudo preparar cliente HTTP: {error}"))
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) InterfaceLauncher/1.0");
+    configure_client_proxy(builder, tuning.proxy.as_ref())
 }
 
-static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
-    reqwest::Client::builder()
-        .connect_timeout(std::time::Duration::from_secs(env_u64(
-            "FRUTI_CONNECT_TIMEOUT_SECS",
-            12,
-        )))
-        .timeout(std::time::Duration::from_secs(env_u64(
-            "FRUTI_REQUEST_TIMEOUT_SECS",
-            120,
-        )))
-        .pool_max_idle_per_host(32)
-        .tcp_keepalive(std::time::Duration::from_secs(30))
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) InterfaceLauncher/1.0")
+fn http_client_with_tuning(tuning: &NetworkTuning) -> Result<reqwest::Client, String> {
+    http_client_builder(tuning)?
         .build()
-        .expect("No se pudo preparar cliente HTTP global")
-});
+        .map_err(|error| format!("No se pudo preparar cliente HTTP: {error}"))
+}
 
 #[derive(Clone)]
 struct AssetDownloadTask {
@@ -507,6 +620,7 @@ struct AssetDownloadTask {
     path: PathBuf,
     sha1: String,
     object_name: String,
+    size: u64,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -540,6 +654,16 @@ fn launcher_persistent_cache_root(app: &tauri::AppHandle) -> Result<PathBuf, Str
     Ok(launcher_root(app)?.join(".fruti_cache"))
 }
 
+fn minecraft_version_resolver(
+    tuning: &NetworkTuning,
+) -> Result<CachedVersionResolver<'static>, String> {
+    Ok(CachedVersionResolver::new(
+        http_client_with_tuning(tuning)?,
+        download_routes::minecraft_manifest_urls(),
+        HttpMetadataCache::global(),
+    ))
+}
+
 fn launcher_global_download_cache_dir() -> PathBuf {
     std::env::temp_dir()
         .join("fruti-launcher")
@@ -557,6 +681,16 @@ fn launcher_asset_indexes_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, S
         .join("indexes"))
 }
 
+fn launcher_mirror_health_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_persistent_cache_root(app)?
+        .join("network")
+        .join("mirror-health.json"))
+}
+
+fn launcher_http_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_persistent_cache_root(app)?.join("http"))
+}
+
 fn load_cached_asset_index_if_valid(
     app: &tauri::AppHandle,
     asset_index_id: &str,
@@ -773,6 +907,8 @@ struct BinaryDownloadTask {
     urls: Vec<String>,
     path: PathBuf,
     sha1: Option<String>,
+    sha256: Option<String>,
+    size: Option<u64>,
     label: String,
     validate_zip: bool,
 }
@@ -892,6 +1028,7 @@ fn ensure_launcher_layout(app: &tauri::AppHandle) -> Result<(), String> {
 static INSTANCE_LOCKS: Lazy<std::sync::Mutex<HashSet<String>>> =
     Lazy::new(|| std::sync::Mutex::new(HashSet::new()));
 static PREFLIGHT_RUNNING: AtomicBool = AtomicBool::new(false);
+static JAVA_RESCAN_RUNNING: AtomicBool = AtomicBool::new(false);
 
 #[command]
 async fn select_folder(app: tauri::AppHandle) -> Result<SelectFolderResult, String> {
@@ -986,11 +1123,131 @@ async fn save_config(app: tauri::AppHandle, config: AppConfig) -> Result<(), Str
             .map_err(|error| format!("No se pudo crear carpeta de config: {error}"))?;
     }
 
-    let config = migrate_config(config);
+    let mut config = migrate_config(config);
+    let previous_proxy = load_config(app.clone())
+        .await
+        .ok()
+        .and_then(|previous| previous.network_tuning?.proxy);
+    seal_proxy_password(&app, &mut config, previous_proxy)?;
     let raw = serde_json::to_string_pretty(&config)
         .map_err(|error| format!("No se pudo serializar config: {error}"))?;
     backup_file(&config_path)?;
-    fs::write(&config_path, raw).map_err(|error| format!("No se pudo guardar config.json: {error}"))
+    fs::write(&config_path, raw)
+        .map_err(|error| format!("No se pudo guardar config.json: {error}"))?;
+    apply_runtime_settings(&config);
+    Ok(())
+}
+
+const PROXY_PASSWORD_SECRET: &str = "proxy-password";
+
+/// La UI sólo envía la contraseña del proxy cuando el usuario la cambia: se cifra con la
+/// clave del almacén de cuentas, `Some("")` la borra y `None` conserva la ya guardada
+/// (cifrando de paso la que versiones anteriores dejaron en claro en config.json).
+fn seal_proxy_password(
+    app: &tauri::AppHandle,
+    config: &mut AppConfig,
+    previous: Option<ProxyConfig>,
+) -> Result<(), String> {
+    let Some(proxy) = config
+        .network_tuning
+        .as_mut()
+        .and_then(|tuning| tuning.proxy.as_mut())
+    else {
+        return Ok(());
+    };
+    if proxy.password.is_none() {
+        let previous = previous.unwrap_or_default();
+        proxy.password = previous.password.filter(|password| !password.is_empty());
+        if proxy.password.is_none() {
+            proxy.password_sealed = previous.password_sealed;
+            unseal_proxy_password(app, config);
+            return Ok(());
+        }
+    }
+    match proxy.password.as_deref() {
+        Some("") | None => {
+            proxy.password = None;
+            proxy.password_sealed = None;
+        }
+        Some(plain) => {
+            proxy.password_sealed =
+                Some(account_vault(app)?.seal_secret(PROXY_PASSWORD_SECRET, plain)?);
+        }
+    }
+    Ok(())
+}
+
+/// Descifra en memoria la contraseña guardada para poder autenticarse con el proxy.
+fn unseal_proxy_password(app: &tauri::AppHandle, config: &mut AppConfig) {
+    let Some(proxy) = config
+        .network_tuning
+        .as_mut()
+        .and_then(|tuning| tuning.proxy.as_mut())
+        .filter(|proxy| proxy.password.is_none())
+    else {
+        return;
+    };
+    let Some(sealed) = proxy.password_sealed.as_deref() else {
+        return;
+    };
+    match account_vault(app).and_then(|vault| vault.open_secret(PROXY_PASSWORD_SECRET, sealed)) {
+        Ok(plain) => proxy.password = Some(plain),
+        Err(error) => eprintln!("No se pudo descifrar la contraseña del proxy: {error}"),
+    }
+}
+
+/// Ajustes que se aplican en caliente sin reiniciar: límites de ancho de banda y
+/// enlaces duros desde la caché global.
+fn apply_runtime_settings(config: &AppConfig) {
+    let tuning = resolve_network_tuning(Some(config));
+    BandwidthLimiter::global().configure(&tuning);
+    *ACTIVE_NETWORK_TUNING
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = tuning;
+    DOWNLOAD_CACHE_HARD_LINKS.store(
+        config.download_cache_hard_links.unwrap_or(false),
+        Ordering::Relaxed,
+    );
+    download_routes::configure_endpoint_overrides(config.mirrors.as_ref());
+    HttpMetadataCache::global().configure(config.http_cache.as_ref());
+    configure_runtime_providers(config.java_runtime_providers.as_deref());
+}
+
+/// Carga los ajustes en caliente guardados al arrancar, antes de cualquier descarga.
+fn apply_persisted_runtime_settings(app: &tauri::AppHandle) {
+    let Some(mut config) = config_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<AppConfig>(&raw).ok())
+    else {
+        return;
+    };
+    unseal_proxy_password(app, &mut config);
+    apply_runtime_settings(&config);
+}
+
+fn attach_mirror_health_store(app: &tauri::AppHandle) {
+    match launcher_mirror_health_path(app) {
+        Ok(path) => MirrorHealth::global().attach_store(path),
+        Err(error) => eprintln!("No se pudo cargar la salud de mirrors: {error}"),
+    }
+}
+
+fn attach_http_metadata_cache(app: &tauri::AppHandle) {
+    match launcher_http_cache_dir(app) {
+        Ok(path) => HttpMetadataCache::global().attach_store(path),
+        Err(error) => {
+            eprintln!("No se pudo preparar la caché HTTP de metadata: {error}");
+            return;
+        }
+    }
+    let migrated = launcher_persistent_cache_root(app).and_then(|root| {
+        minecraft_version_resolver(&active_network_tuning())
+            .map(|resolver| resolver.migrate_legacy_cache(&root.join("metadata")))
+    });
+    if let Err(error) = migrated {
+        eprintln!("No se pudo migrar la caché del manifiesto de versiones: {error}");
+    }
 }
 
 fn backup_file(path: &Path) -> Result<(), String> {
@@ -1483,7 +1740,7 @@ async fn append_log(
         .map_err(|error| format!("No se pudo abrir archivo de log: {error}"))?;
 
     for line in lines {
-        file.write_all(format!("{line}\n").as_bytes())
+        file.write_all(format!("{}\n", redact_text(&line)).as_bytes())
             .map_err(|error| format!("No se pudo escribir en log: {error}"))?;
     }
     Ok(())
@@ -1776,7 +2033,7 @@ fn write_loader_crash_diagnostic(
     let jar_validation = validate_minecraft_client_jar(&version_jar, expected_sha1.as_deref());
     let version_json_raw = fs::read_to_string(&launch_plan.version_json).unwrap_or_default();
     let version_json = serde_json::from_str::<Value>(&version_json_raw).unwrap_or(Value::Null);
-    let stack_excerpt = read_loader_stack_excerpt(lines, 12);
+    let stack_excerpt = redact_lines(&read_loader_stack_excerpt(lines, 12));
     let classification = classify_loader_failure(launch_plan, lines, &jar_validation);
     let fingerprint = loader_failure_fingerprint(
         classification,
@@ -2177,32 +2434,17 @@ fn parse_java_version(output: &str) -> Option<(String, u32)> {
     Some((token, major))
 }
 
-fn inspect_java_runtime(path: &Path, source: &str) -> Option<JavaRuntime> {
+fn inspect_java_runtime(
+    cache: Option<&Connection>,
+    path: &Path,
+    source: &str,
+) -> Option<JavaRuntime> {
     if !path.exists() {
         return None;
     }
 
-    let output = Command::new(path).arg("-version").output().ok()?;
-    if !output.status.success() {
-        return None;
-    }
-
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let combined = if stderr.trim().is_empty() {
-        stdout
-    } else {
-        stderr
-    };
-    let (version, major) = parse_java_version(&combined)?;
-
-    let architecture = if combined.to_lowercase().contains("64-bit") {
-        "x64".to_string()
-    } else if combined.to_lowercase().contains("aarch64") {
-        "arm64".to_string()
-    } else {
-        std::env::consts::ARCH.to_string()
-    };
+    let probe = probe_java(cache, path)?;
+    let major = probe.major;
 
     let runtime_name = path
         .parent()
@@ -2215,11 +2457,12 @@ fn inspect_java_runtime(path: &Path, source: &str) -> Option<JavaRuntime> {
         id: format!("{}-{}", source, major),
         name: format!("Java {major} ({runtime_name})"),
         path: path.display().to_string(),
-        version,
+        version: probe.version,
         major,
-        architecture,
+        architecture: probe.architecture,
         source: source.to_string(),
         recommended: false,
+        vendor: probe.vendor,
     })
 }
 
@@ -2227,6 +2470,9 @@ impl JavaManager {
     fn new(app: &tauri::AppHandle) -> Result<Self, String> {
         Ok(Self {
             launcher_root: launcher_root(app)?,
+            probe_cache: database_connection(app)
+                .map_err(|error| eprintln!("[java] sin caché de sondeos: {error}"))
+                .ok(),
         })
     }
 
@@ -2238,13 +2484,7 @@ impl JavaManager {
         if !runtime_dir.is_dir() {
             return None;
         }
-
-        let java_bin = runtime_dir.join("bin").join(java_bin_name());
-        if java_bin.is_file() {
-            Some(java_bin)
-        } else {
-            None
-        }
+        managed_runtime_java(runtime_dir)
     }
 
     fn required_major_for_minecraft(mc_version: &str) -> u32 {
@@ -2255,25 +2495,36 @@ impl JavaManager {
         required_java_major_for_version(mc_version)
     }
 
-    fn detect_installed(&self) -> Vec<JavaRuntime> {
-        let mut runtimes = Vec::new();
-        let mut seen_paths = HashSet::new();
-
+    /// Binarios Java a sondear: runtimes gestionados y rutas conocidas del sistema.
+    fn java_candidates(&self) -> Vec<(PathBuf, &'static str)> {
+        let mut candidates = Vec::new();
         let managed_root = self.java_runtime_dir();
-
         for root in [managed_root.clone(), managed_root.join("java")] {
             let Ok(entries) = fs::read_dir(root) else {
                 continue;
             };
             for entry in entries.flatten() {
-                let runtime = entry.path();
-                let Some(java) = Self::managed_runtime_bin(&runtime) else {
-                    continue;
-                };
-                if let Some(found) = inspect_java_runtime(&java, "embebido") {
-                    if seen_paths.insert(found.path.clone()) {
-                        runtimes.push(found);
-                    }
+                if let Some(java) = Self::managed_runtime_bin(&entry.path()) {
+                    candidates.push((java, "embebido"));
+                }
+            }
+        }
+        candidates.extend(
+            discover_java_candidates_from_volumes()
+                .into_iter()
+                .map(|java| (java, "sistema")),
+        );
+        candidates
+    }
+
+    fn detect_installed(&self) -> Vec<JavaRuntime> {
+        let mut runtimes = Vec::new();
+        let mut seen_paths = HashSet::new();
+
+        for (java, source) in self.java_candidates() {
+            if let Some(found) = inspect_java_runtime(self.probe_cache.as_ref(), &java, source) {
+                if seen_paths.insert(found.path.clone()) {
+                    runtimes.push(found);
                 }
             }
         }
@@ -2287,6 +2538,20 @@ impl JavaManager {
         runtimes
     }
 
+    /// Actualiza la caché de sondeos con todos los candidatos.
+    fn rescan(&self) -> Result<JavaRescanReport, String> {
+        let conn = self
+            .probe_cache
+            .as_ref()
+            .ok_or_else(|| "La caché de sondeos Java no está disponible.".to_string())?;
+        let candidates = self
+            .java_candidates()
+            .into_iter()
+            .map(|(java, _)| java)
+            .collect::<Vec<_>>();
+        Ok(rescan_java_candidates(conn, &candidates))
+    }
+
     fn resolve_for_minecraft(&self, mc_version: &str) -> JavaResolution {
         let required_major = Self::required_major_for_minecraft(mc_version);
         let mut runtimes = self.detect_installed();
@@ -2518,6 +2783,58 @@ fn append_argument_values(target: &mut Vec<String>, argument_node: &Value) {
     }
 }
 
+/// Argumentos de juego declarados por la versión; el bool indica plantilla `minecraftArguments`.
+fn game_argument_template(version_json: &Value) -> (Value, bool) {
+    let modern = version_json
+        .get("arguments")
+        .and_then(|v| v.get("game"))
+        .filter(|v| v.as_array().is_some_and(|values| !values.is_empty()));
+    if let Some(arguments) = modern {
+        return (arguments.clone(), false);
+    }
+    match version_json
+        .get("minecraftArguments")
+        .and_then(Value::as_str)
+    {
+        Some(legacy_args) => (
+            Value::Array(
+                legacy_args
+                    .split_whitespace()
+                    .map(|value| Value::String(value.to_string()))
+                    .collect(),
+            ),
+            true,
+        ),
+        None => (Value::Array(Vec::new()), false),
+    }
+}
+
+fn expand_game_arguments(template: &Value, variables: &HashMap<&str, String>) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for value in template.as_array().into_iter().flatten() {
+        let mut resolved = Vec::new();
+        append_argument_values(&mut resolved, value);
+        for argument in resolved {
+            expanded.push(expand_launch_placeholders(&argument, variables));
+        }
+    }
+    expanded
+}
+
+fn apply_required_game_args<'a>(
+    args: &mut Vec<String>,
+    required: impl IntoIterator<Item = (&'a str, String)>,
+    legacy_arguments: bool,
+) {
+    for (key, value) in required {
+        if legacy_arguments {
+            replace_game_arg_if_present(args, key, value);
+        } else {
+            upsert_game_arg(args, key, value);
+        }
+    }
+}
+
 fn expand_launch_placeholders(value: &str, variables: &HashMap<&str, String>) -> String {
     let mut expanded = value.to_string();
     for (key, replacement) in variables {
@@ -2852,6 +3169,7 @@ struct ResolvedLibraryArtifact {
     urls: Vec<String>,
     sha1: Option<String>,
     sha256: Option<String>,
+    size: Option<u64>,
     include_in_classpath: bool,
 }
 
@@ -2864,6 +3182,11 @@ fn resolve_library_artifacts(
         "osx" => "natives-osx",
         _ => "natives-linux",
     };
+    let arch_bits = if cfg!(target_pointer_width = "64") {
+        "64"
+    } else {
+        "32"
+    };
     let mut artifacts = Vec::new();
     let mut seen = HashSet::new();
 
@@ -2881,6 +3204,7 @@ fn resolve_library_artifacts(
                                  url: Option<&str>,
                                  sha1: Option<&str>,
                                  sha256: Option<&str>,
+                                 size: Option<u64>,
                                  include_in_classpath: bool| {
             if !seen.insert(path.clone()) {
                 return;
@@ -2889,6 +3213,7 @@ fn resolve_library_artifacts(
                 urls: url.map(mirror_candidates_for_url).unwrap_or_default(),
                 sha1: sha1.map(str::to_string),
                 sha256: sha256.map(str::to_string),
+                size,
                 include_in_classpath,
                 path,
             });
@@ -2918,14 +3243,23 @@ fn resolve_library_artifacts(
                         artifact.get("url").and_then(Value::as_str),
                         artifact.get("sha1").and_then(Value::as_str),
                         artifact.get("sha256").and_then(Value::as_str),
+                        artifact.get("size").and_then(Value::as_u64),
                         include_in_classpath,
                     );
                 }
             }
 
+            // Las libraries con LWJGL 2 declaran el classifier en `natives` (con `${arch}`).
+            let declared_native_key = library
+                .get("natives")
+                .and_then(|natives| natives.get(current_minecraft_os()))
+                .and_then(Value::as_str)
+                .map(|key| key.replace("${arch}", arch_bits));
             if let Some(native) = downloads.get("classifiers").and_then(|classifiers| {
-                classifiers
-                    .get(os_native_key)
+                declared_native_key
+                    .as_deref()
+                    .and_then(|key| classifiers.get(key))
+                    .or_else(|| classifiers.get(os_native_key))
                     .or_else(|| classifiers.get("natives-windows-64"))
             }) {
                 if let Some(path) = native.get("path").and_then(Value::as_str) {
@@ -2934,6 +3268,7 @@ fn resolve_library_artifacts(
                         native.get("url").and_then(Value::as_str),
                         native.get("sha1").and_then(Value::as_str),
                         native.get("sha256").and_then(Value::as_str),
+                        native.get("size").and_then(Value::as_u64),
                         false,
                     );
                 }
@@ -2956,6 +3291,7 @@ fn resolve_library_artifacts(
                     Some(&format!("{base_url}/{rel_url}")),
                     None,
                     None,
+                    None,
                     include_in_classpath,
                 );
             }
@@ -3009,7 +3345,7 @@ async fn enrich_with_transitive_libraries(version_json: &mut Value) {
     };
 
     let mut roots = Vec::new();
-    let mut repos = default_repositories();
+    let mut repos = download_routes::apply_endpoint_overrides(default_repositories());
     for library in &libraries {
         let Some(name) = library.get("name").and_then(Value::as_str) else {
             continue;
@@ -3029,17 +3365,19 @@ async fn enrich_with_transitive_libraries(version_json: &mut Value) {
         return;
     }
 
-    let client = match reqwest::Client::builder()
-        .connect_timeout(Duration::from_secs(env_u64(
-            "FRUTI_HTTP_CONNECT_TIMEOUT_SECS",
-            12,
-        )))
-        .timeout(Duration::from_secs(env_u64(
-            "FRUTI_HTTP_REQUEST_TIMEOUT_SECS",
-            35,
-        )))
-        .build()
-    {
+    let client = match http_client_builder(&active_network_tuning()).and_then(|builder| {
+        builder
+            .connect_timeout(Duration::from_secs(env_u64(
+                "FRUTI_HTTP_CONNECT_TIMEOUT_SECS",
+                12,
+            )))
+            .timeout(Duration::from_secs(env_u64(
+                "FRUTI_HTTP_REQUEST_TIMEOUT_SECS",
+                35,
+            )))
+            .build()
+            .map_err(|error| error.to_string())
+    }) {
         Ok(client) => client,
         Err(_) => return,
     };
@@ -3619,7 +3957,7 @@ async fn resolve_latest_loader_version(
     loader: &str,
     minecraft_version: &str,
 ) -> Result<Option<String>, String> {
-    let client = reqwest::Client::builder()
+    let client = http_client_builder(&active_network_tuning())?
         .connect_timeout(Duration::from_secs(env_u64(
             "FRUTI_ENDPOINT_CONNECT_TIMEOUT_SECS",
             10,
@@ -3634,22 +3972,30 @@ async fn resolve_latest_loader_version(
 
     if loader == "forge" {
         for promotions_url in download_routes::forge_promotions_urls() {
-            if let Ok(resp) = client
-                .get(&promotions_url)
-                .header(reqwest::header::USER_AGENT, user_agent)
-                .send()
-                .await
-            {
-                if let Ok(json) = resp.json::<Value>().await {
-                    let key_recommended = format!("{minecraft_version}-recommended");
-                    let key_latest = format!("{minecraft_version}-latest");
-                    if let Some(version) = json
-                        .get("promos")
-                        .and_then(|v| v.get(&key_recommended).or_else(|| v.get(&key_latest)))
-                        .and_then(|v| v.as_str())
-                    {
-                        return Ok(Some(format!("{minecraft_version}-{version}")));
-                    }
+            let promotions = match download_routes::read_local_mirror(&promotions_url) {
+                Some(local) => local
+                    .ok()
+                    .and_then(|raw| serde_json::from_slice::<Value>(&raw).ok()),
+                None => HttpMetadataCache::global()
+                    .fetch_text(
+                        client
+                            .get(&promotions_url)
+                            .header(reqwest::header::USER_AGENT, user_agent),
+                        &promotions_url,
+                    )
+                    .await
+                    .ok()
+                    .and_then(|raw| serde_json::from_str::<Value>(&raw).ok()),
+            };
+            if let Some(json) = promotions {
+                let key_recommended = format!("{minecraft_version}-recommended");
+                let key_latest = format!("{minecraft_version}-latest");
+                if let Some(version) = json
+                    .get("promos")
+                    .and_then(|v| v.get(&key_recommended).or_else(|| v.get(&key_latest)))
+                    .and_then(|v| v.as_str())
+                {
+                    return Ok(Some(format!("{minecraft_version}-{version}")));
                 }
             }
         }
@@ -3663,23 +4009,38 @@ async fn resolve_latest_loader_version(
     let mut network_errors = Vec::new();
 
     for url in metadata_urls {
-        let resp = match client
-            .get(&url)
-            .header(reqwest::header::USER_AGENT, user_agent)
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(error) => {
-                network_errors.push(format!("{url}: {error}"));
-                continue;
+        let payload = if let Some(local) = download_routes::read_local_mirror(&url) {
+            match local {
+                Ok(raw) => String::from_utf8_lossy(&raw).into_owned(),
+                Err(error) => {
+                    network_errors.push(error);
+                    continue;
+                }
+            }
+        } else {
+            match HttpMetadataCache::global()
+                .fetch(
+                    client
+                        .get(&url)
+                        .header(reqwest::header::USER_AGENT, user_agent),
+                    &url,
+                    false,
+                )
+                .await
+            {
+                Ok(fetched) => fetched.body,
+                // Cualquier respuesta HTTP (p. ej. 404) prueba que hay red.
+                Err(error) if error.status.is_some() => {
+                    metadata_reachable = true;
+                    continue;
+                }
+                Err(error) => {
+                    network_errors.push(error.message);
+                    continue;
+                }
             }
         };
-
         metadata_reachable = true;
-        let Ok(payload) = resp.text().await else {
-            continue;
-        };
         let mut matches =
             if payload.trim_start().starts_with('{') || payload.trim_start().starts_with('[') {
                 serde_json::from_str::<Value>(&payload)
@@ -3780,7 +4141,8 @@ async fn resolve_latest_fabric_like_loader_version(
         format!("https://meta.fabricmc.net/v2/versions/loader/{minecraft_version}")
     };
 
-    let client = reqwest::Client::builder()
+    let client = http_client_builder(&active_network_tuning())
+        .ok()?
         .connect_timeout(Duration::from_secs(env_u64(
             "FRUTI_ENDPOINT_CONNECT_TIMEOUT_SECS",
             10,
@@ -3792,20 +4154,32 @@ async fn resolve_latest_fabric_like_loader_version(
         .build()
         .ok()?;
 
-    let Ok(resp) = client
-        .get(&endpoint)
-        .header(
-            reqwest::header::USER_AGENT,
-            "Interface/1.0 (+https://github.com/fruti-studio)",
-        )
-        .send()
-        .await
-    else {
-        return None;
-    };
-    let Ok(json) = resp.json::<Value>().await else {
-        return None;
-    };
+    for url in download_routes::apply_endpoint_overrides(vec![endpoint]) {
+        let raw = match download_routes::read_local_mirror(&url) {
+            Some(local) => match local {
+                Ok(raw) => String::from_utf8_lossy(&raw).into_owned(),
+                Err(_) => continue,
+            },
+            None => {
+                let request = client.get(&url).header(
+                    reqwest::header::USER_AGENT,
+                    "Interface/1.0 (+https://github.com/fruti-studio)",
+                );
+                match HttpMetadataCache::global().fetch_text(request, &url).await {
+                    Ok(raw) => raw,
+                    Err(_) => continue,
+                }
+            }
+        };
+        if let Some(version) = pick_fabric_like_loader_version(&raw) {
+            return Some(version);
+        }
+    }
+    None
+}
+
+fn pick_fabric_like_loader_version(raw: &str) -> Option<String> {
+    let json = serde_json::from_str::<Value>(raw).ok()?;
     let entries = json.as_array()?;
     let preferred = entries.iter().find(|entry| {
         entry
@@ -3830,11 +4204,11 @@ async fn install_forge_like_loader(
     minecraft_version: &str,
     loader: &str,
     requested_loader_version: Option<&str>,
+    progress: &std::sync::Arc<ProgressReporter>,
 ) -> Result<String, String> {
     crate::core::loaders::validate_loader_request(loader, minecraft_version)?;
     ensure_forge_preflight_files(minecraft_root, minecraft_version)?;
-    let config = load_config(app.clone()).await?;
-    let tuning = resolve_network_tuning(Some(&config));
+    let tuning = active_network_tuning();
 
     let requested = requested_loader_version
         .unwrap_or("latest")
@@ -3899,6 +4273,7 @@ async fn install_forge_like_loader(
 
     let installer_file_name = format!("{artifact_name}-{resolved_version}-installer.jar");
     let installer_cache_target = launcher_cache_dir.join(&installer_file_name);
+    progress.begin_step("resolve_modloader", 1, None);
     download_from_candidates(
         &installer_urls,
         &installer_cache_target,
@@ -3906,6 +4281,7 @@ async fn install_forge_like_loader(
         &tuning,
     )
     .await?;
+    progress.file_done();
 
     let installer_payload = [
         "install_profile.json",
@@ -3926,6 +4302,14 @@ async fn install_forge_like_loader(
     let mut profile_json = installer_payload
         .get("versionInfo")
         .cloned()
+        .or_else(|| {
+            installer_payload
+                .get("json")
+                .and_then(Value::as_str)
+                .and_then(|entry| {
+                    read_json_entry_from_zip(&installer_cache_target, entry.trim_start_matches('/'))
+                })
+        })
         .unwrap_or_else(|| installer_payload.clone());
 
     if !profile_json.is_object() {
@@ -3991,16 +4375,186 @@ async fn install_forge_like_loader(
         &installer_profile_libraries,
     )?;
 
+    if !client_processors(&installer_payload).is_empty() {
+        run_forge_installer_processors(
+            app,
+            instance_root,
+            minecraft_root,
+            minecraft_version,
+            &installer_cache_target,
+            &installer_payload,
+            progress,
+        )
+        .await?;
+    }
+
     Ok(installed_profile_id)
 }
 
-fn expected_forge_like_profile_ids(
-    loader: &str,
-    minecraft_version: &str,
-    resolved_version: &str,
-) -> Vec<String> {
-    let mut candidates = vec![resolved_version.to_string()];
-    let trimmed = resolved_version.trim();
+/// Extrae las librerías embebidas en `maven/` del instalador sin pisar las que ya existen.
+fn extract_installer_maven_entries(
+    installer_path: &Path,
+    libraries_root: &Path,
+) -> Result<usize, String> {
+    let file = fs::File::open(installer_path)
+        .map_err(|error| format!("No se pudo abrir instalador: {error}"))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|error| format!("Instalador inválido: {error}"))?;
+    let mut extracted = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("No se pudo leer entrada del instalador: {error}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|path| path.strip_prefix("maven").ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+        let target = libraries_root.join(relative);
+        if target.is_file() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("No se pudo crear {}: {error}", parent.display()))?;
+        }
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|error| format!("No se pudo extraer {}: {error}", target.display()))?;
+        fs::write(&target, bytes)
+            .map_err(|error| format!("No se pudo escribir {}: {error}", target.display()))?;
+        extracted += 1;
+    }
+    Ok(extracted)
+}
+
+async fn run_forge_installer_processors(
+    app: &tauri::AppHandle,
+    instance_root: &Path,
+    minecraft_root: &Path,
+    minecraft_version: &str,
+    installer_path: &Path,
+    install_profile: &Value,
+    progress: &std::sync::Arc<ProgressReporter>,
+) -> Result<(), String> {
+    let libraries_root = launcher_libraries_root(app)?;
+    extract_installer_maven_entries(installer_path, &libraries_root)?;
+
+    let profile_libraries = serde_json::json!({
+        "libraries": collect_loader_installer_libraries(install_profile)
+    });
+    let missing_artifacts = resolve_library_artifacts(&profile_libraries, &libraries_root)
+        .into_iter()
+        .filter(|artifact| {
+            !artifact.urls.is_empty()
+                && !artifact_valid_on_disk(
+                    &artifact.path,
+                    artifact.sha1.as_deref(),
+                    artifact.sha256.as_deref(),
+                )
+        })
+        .collect::<Vec<_>>();
+    progress.begin_step(
+        "resolve_modloader",
+        missing_artifacts.len() as u64,
+        missing_artifacts
+            .iter()
+            .map(|artifact| artifact.size)
+            .sum::<Option<u64>>(),
+    );
+    let tuning = active_network_tuning();
+    for artifact in missing_artifacts {
+        download_with_progress(
+            &artifact.urls,
+            &artifact.path,
+            ExpectedDigest {
+                sha1: artifact.sha1.as_deref(),
+                sha256: artifact.sha256.as_deref(),
+                size: artifact.size,
+            },
+            should_validate_zip_from_path(&artifact.path),
+            &tuning,
+            "librería de processor Forge/NeoForge",
+            Some(progress),
+        )
+        .await?;
+        progress.file_done();
+    }
+
+    let processors = client_processors(install_profile);
+    for coordinate in processor_library_coordinates(&processors) {
+        let path = library_path(&libraries_root, &coordinate)
+            .ok_or_else(|| format!("Coordenada de processor inválida: {coordinate}"))?;
+        if !path.is_file() {
+            return Err(format!(
+                "Falta la librería del processor {coordinate} en {}",
+                path.display()
+            ));
+        }
+    }
+
+    let java_major = required_java_major_for_version(minecraft_version);
+    let java_path = RuntimeManager::new(app)?
+        .ensure_runtime_for_java_major(java_major)
+        .await?;
+
+    let installer_name = installer_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "installer".to_string());
+    let env = ProcessorEnvironment {
+        libraries_dir: libraries_root,
+        minecraft_root: minecraft_root.to_path_buf(),
+        minecraft_jar: minecraft_root
+            .join("versions")
+            .join(minecraft_version)
+            .join(format!("{minecraft_version}.jar")),
+        minecraft_version: minecraft_version.to_string(),
+        installer_path: installer_path.to_path_buf(),
+        work_dir: minecraft_root
+            .join("installers")
+            .join("processors")
+            .join(installer_name),
+    };
+    fs::create_dir_all(&env.work_dir)
+        .map_err(|error| format!("No se pudo crear carpeta de processors: {error}"))?;
+
+    write_instance_state(
+        instance_root,
+        "running_loader_processors",
+        serde_json::json!({"processors": processors.len(), "javaMajor": java_major}),
+    );
+    progress.begin_step("resolve_modloader", processors.len() as u64, None);
+    let install_profile = install_profile.clone();
+    let processor_progress = progress.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        run_processors(&java_path, &install_profile, &env, |_| {
+            processor_progress.file_done()
+        })
+    })
+    .await
+    .map_err(|error| format!("La ejecución de processors se interrumpió: {error}"))??;
+
+    write_instance_state(
+        instance_root,
+        "loader_processors_ready",
+        serde_json::json!({"executed": report.executed, "skipped": report.skipped}),
+    );
+    Ok(())
+}
+
+fn expected_forge_like_profile_ids(
+    loader: &str,
+    minecraft_version: &str,
+    resolved_version: &str,
+) -> Vec<String> {
+    let mut candidates = vec![resolved_version.to_string()];
+    let trimmed = resolved_version.trim();
 
     if loader == "forge" {
         let without_mc_prefix = trimmed
@@ -4157,16 +4711,44 @@ fn ensure_forge_preflight_files(
     Ok(())
 }
 
+async fn remote_content_length(urls: &[String], tuning: &NetworkTuning) -> Option<u64> {
+    let url = urls
+        .iter()
+        .find(|url| download_routes::local_mirror_path(url).is_none())?;
+    let response = http_client_with_tuning(tuning)
+        .ok()?
+        .head(url)
+        .send()
+        .await
+        .ok()?;
+    response
+        .status()
+        .is_success()
+        .then(|| response.content_length())
+        .flatten()
+        .filter(|length| *length > 0)
+}
+
+/// Los instaladores no publican tamaño en ninguna metadata; se pregunta con HEAD al primer
+/// candidato para saber si conviene descargarlo por segmentos.
 async fn download_from_candidates(
     urls: &[String],
     path: &Path,
     label: &str,
     tuning: &NetworkTuning,
 ) -> Result<(), String> {
+    let size = if path.is_file() {
+        None
+    } else {
+        remote_content_length(urls, tuning).await
+    };
     download_with_retries(
         urls,
         path,
-        None,
+        ExpectedDigest {
+            size,
+            ..ExpectedDigest::default()
+        },
         tuning.retries,
         should_validate_zip_from_path(path),
         tuning,
@@ -4176,6 +4758,15 @@ async fn download_from_candidates(
     .map_err(|error| format!("No se pudo descargar {label}. Último error: {error}"))
 }
 
+/// Variante para plantillas `minecraftArguments`: las versiones antiguas no aceptan flags nuevos.
+fn replace_game_arg_if_present(args: &mut [String], key: &str, value: String) {
+    if let Some(position) = args.iter().position(|arg| arg == key) {
+        if let Some(slot) = args.get_mut(position + 1) {
+            *slot = value;
+        }
+    }
+}
+
 fn upsert_game_arg(args: &mut Vec<String>, key: &str, value: String) {
     if let Some(position) = args.iter().position(|arg| arg == key) {
         if position + 1 < args.len() {
@@ -4194,14 +4785,19 @@ async fn download_to(
     url: &str,
     path: &Path,
     tuning: &NetworkTuning,
-    expected_sha1: Option<&str>,
-    expected_md5: Option<&str>,
+    expected: &ExpectedModFile,
 ) -> Result<ModDownloadIntegrity, String> {
+    let expected_sha1 = expected.sha1.as_deref();
+    let expected_md5 = expected.md5.as_deref();
     let urls = vec![url.to_string()];
     download_with_retries(
         &urls,
         path,
-        expected_sha1,
+        ExpectedDigest {
+            sha1: expected_sha1,
+            size: expected.size,
+            ..ExpectedDigest::default()
+        },
         tuning.retries,
         should_validate_zip_from_path(path),
         tuning,
@@ -4352,9 +4948,12 @@ fn remove_file_with_retry(path: &Path, reason: &str) -> Result<(), String> {
     }))
 }
 
-fn global_cache_path_for_sha1(cache_root: &Path, sha1: &str) -> PathBuf {
-    let prefix = &sha1[0..2];
-    cache_root.join(prefix).join(sha1)
+/// Caché global con la política de enlaces duros vigente.
+fn global_download_cache(cache_root: &Path) -> GlobalDownloadCache {
+    GlobalDownloadCache::new(
+        cache_root.to_path_buf(),
+        DOWNLOAD_CACHE_HARD_LINKS.load(Ordering::Relaxed),
+    )
 }
 
 fn restore_binary_from_global_cache(
@@ -4363,26 +4962,16 @@ fn restore_binary_from_global_cache(
     target: &Path,
     validate_zip: bool,
 ) -> Result<bool, String> {
-    let cache_file = global_cache_path_for_sha1(cache_root, expected_sha1);
-    if !cache_file.exists() {
-        return Ok(false);
-    }
-
-    if file_sha1(&cache_file)? != expected_sha1 {
-        let _ = fs::remove_file(&cache_file);
-        return Ok(false);
-    }
-
-    if let Some(parent) = target.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|error| format!("No se pudo crear carpeta para cache global: {error}"))?;
-    }
-    fs::copy(&cache_file, target).map_err(|error| {
+    let cache = global_download_cache(cache_root);
+    let restored = cache.materialize(expected_sha1, target).map_err(|error| {
         format!(
             "No se pudo restaurar binario desde cache global {}: {error}",
-            cache_file.display()
+            cache.entry_path(expected_sha1).display()
         )
     })?;
+    if restored.is_none() {
+        return Ok(false);
+    }
 
     if validate_zip && !is_valid_zip_stream(target) {
         let _ = remove_file_with_retry(target, "limpiar zip inválido restaurado de cache global");
@@ -4406,21 +4995,9 @@ fn persist_binary_to_global_cache(
             source.display()
         ));
     }
-    let cache_file = global_cache_path_for_sha1(cache_root, expected_sha1);
-    if cache_file.exists() {
-        return Ok(());
-    }
-    if let Some(parent) = cache_file.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|error| format!("No se pudo crear directorio de cache global: {error}"))?;
-    }
-    fs::copy(source, &cache_file).map_err(|error| {
-        format!(
-            "No se pudo persistir archivo en cache global {}: {error}",
-            cache_file.display()
-        )
-    })?;
-    Ok(())
+    global_download_cache(cache_root)
+        .store(expected_sha1, source)
+        .map_err(|error| format!("No se pudo persistir archivo en cache global: {error}"))
 }
 
 fn download_partial_path(path: &Path) -> PathBuf {
@@ -4715,20 +5292,23 @@ fn classpath_has_loader_runtime(loader: &str, entries: &[String]) -> bool {
     evaluate_loader_runtime_classpath(loader, entries).0
 }
 
+/// Devuelve también la latencia del intento que respondió, sin contar los reintentos.
 async fn send_request_with_retry(
     request: reqwest::RequestBuilder,
     max_retries: u8,
-) -> Result<reqwest::Response, reqwest::Error> {
+) -> Result<(reqwest::Response, Duration), reqwest::Error> {
     let retries = max_retries.max(1);
     let mut last_error: Option<reqwest::Error> = None;
 
     for attempt in 1..=retries {
+        let started = Instant::now();
         let Some(clone) = request.try_clone() else {
-            return request.send().await;
+            let response = request.send().await?;
+            return Ok((response, started.elapsed()));
         };
 
         match clone.send().await {
-            Ok(response) => return Ok(response),
+            Ok(response) => return Ok((response, started.elapsed())),
             Err(error) => {
                 let message = error.to_string().to_ascii_lowercase();
                 let is_send_error = message.contains("error sending request");
@@ -4744,29 +5324,69 @@ async fn send_request_with_retry(
     if let Some(error) = last_error {
         Err(error)
     } else {
-        request.send().await
+        let started = Instant::now();
+        let response = request.send().await?;
+        Ok((response, started.elapsed()))
+    }
+}
+
+/// Bytes a contar como muestra de throughput: con un límite de ancho de banda activo la
+/// lentitud la causa el limitador, no el host.
+fn throughput_sample_bytes(url: &str, bytes: u64) -> u64 {
+    if BandwidthLimiter::global().limits(url_host(url).as_deref()) {
+        0
+    } else {
+        bytes
     }
 }
 
 async fn download_with_retries(
     urls: &[String],
     path: &Path,
-    expected_sha1: Option<&str>,
+    expected: ExpectedDigest<'_>,
     attempts: u8,
     validate_zip: bool,
     tuning: &NetworkTuning,
     stage: &str,
 ) -> Result<(), String> {
+    let tuning = NetworkTuning {
+        retries: attempts,
+        ..tuning.clone()
+    };
+    download_with_progress(urls, path, expected, validate_zip, &tuning, stage, None).await
+}
+
+/// Igual que `download_with_retries` con `tuning.retries` intentos, informando intentos
+/// y bytes al `ProgressReporter`.
+async fn download_with_progress(
+    urls: &[String],
+    path: &Path,
+    expected: ExpectedDigest<'_>,
+    validate_zip: bool,
+    tuning: &NetworkTuning,
+    stage: &str,
+    progress: Option<&ProgressReporter>,
+) -> Result<(), String> {
+    let attempts = tuning.retries;
+    let expected_sha1 = expected.sha1;
     let normalized_expected_sha1 = normalized_sha1(expected_sha1)?;
 
     if let Ok(meta) = fs::metadata(path) {
-        if meta.is_file() && meta.len() > 0 && (!validate_zip || is_valid_zip_stream(path)) {
-            if let Some(expected) = normalized_expected_sha1.as_deref() {
-                let existing_hash = file_sha1(path)?;
-                if existing_hash.eq_ignore_ascii_case(expected) {
-                    return Ok(());
-                }
-            } else {
+        if meta.is_file()
+            && meta.len() > 0
+            && expected.size.is_none_or(|size| size == meta.len())
+            && (!validate_zip || is_valid_zip_stream(path))
+        {
+            let expected_sha256 = expected
+                .sha256
+                .map(str::trim)
+                .filter(|value| !value.is_empty());
+            let matches = match (normalized_expected_sha1.as_deref(), expected_sha256) {
+                (Some(sha1), _) => file_sha1(path)?.eq_ignore_ascii_case(sha1),
+                (None, Some(sha256)) => file_sha256(path)?.eq_ignore_ascii_case(sha256),
+                (None, None) => true,
+            };
+            if matches {
                 return Ok(());
             }
         }
@@ -4784,6 +5404,9 @@ async fn download_with_retries(
     }
 
     let client = http_client_with_tuning(tuning)?;
+    let health = MirrorHealth::global();
+    let ordered_urls = health.order(urls);
+    let urls = ordered_urls.as_slice();
 
     if let Some(message) = preflight_maven_availability(&client, urls).await {
         return Err(message);
@@ -4818,6 +5441,7 @@ async fn download_with_retries(
     }
     let max_attempts = attempts.max(1);
     let mut last_error = None;
+    let mut attempted = false;
 
     for attempt in 1..=max_attempts {
         if attempt == 1 {
@@ -4833,9 +5457,91 @@ async fn download_with_retries(
                 sha1: expected_sha1.map(ToOwned::to_owned),
                 md5: None,
             });
+            if let Some(progress) = progress {
+                progress.attempt(url, download_routes::endpoint_label(url), attempted);
+            }
+            attempted = true;
+            if let Some(source) = download_routes::local_mirror_path(url) {
+                match copy_from_local_mirror(
+                    &source,
+                    path,
+                    &partial_path,
+                    expected_sha1,
+                    validate_zip,
+                ) {
+                    Ok(bytes) => {
+                        if let Some(progress) = progress {
+                            progress.add_bytes(bytes);
+                        }
+                        println!("[download:{stage}] mirror local exitoso: {url}");
+                        return Ok(());
+                    }
+                    Err(error) => {
+                        last_error = Some(error);
+                        continue;
+                    }
+                }
+            }
             let resume_from = fs::metadata(&partial_path)
                 .map(|meta| meta.len())
                 .unwrap_or(0);
+            if let Some(total) = expected
+                .size
+                .filter(|size| resume_from == 0 && *size >= SEGMENTED_DOWNLOAD_THRESHOLD)
+            {
+                let started = Instant::now();
+                let first_byte = std::sync::OnceLock::new();
+                let reported = AtomicU64::new(0);
+                let on_bytes = |bytes: u64| {
+                    first_byte.get_or_init(|| started.elapsed());
+                    reported.fetch_add(bytes, Ordering::Relaxed);
+                    if let Some(progress) = progress {
+                        progress.add_bytes(bytes);
+                    }
+                };
+                let result =
+                    download_segmented(&client, url, &partial_path, total, &on_bytes).await;
+                if result.is_err() {
+                    // El temporal se descarta: esos bytes no cuentan para el progreso.
+                    if let Some(progress) = progress {
+                        progress.discard_bytes(reported.load(Ordering::Relaxed));
+                    }
+                }
+                match result {
+                    Ok(()) => {
+                        if let Some(error) = commit_partial_download(
+                            url,
+                            path,
+                            &partial_path,
+                            expected,
+                            validate_zip,
+                        )
+                        .await?
+                        {
+                            health.record_failure(url);
+                            last_error = Some(error);
+                            continue;
+                        }
+                        health.record_success(
+                            url,
+                            first_byte.get().copied().unwrap_or_default(),
+                            throughput_sample_bytes(url, total),
+                            started.elapsed(),
+                        );
+                        println!("[download:{stage}] repositorio exitoso (segmentado): {url}");
+                        return Ok(());
+                    }
+                    Err(SegmentedDownloadError::RangesUnsupported) => {
+                        let _ = fs::remove_file(&partial_path);
+                    }
+                    Err(SegmentedDownloadError::Failed(error)) => {
+                        let _ = fs::remove_file(&partial_path);
+                        health.record_failure(url);
+                        last_error = Some(error);
+                        continue;
+                    }
+                }
+            }
             let mut request = client.get(url).header(
                 reqwest::header::USER_AGENT,
                 "FrutiStudioLauncher/1.0 (Rust/Tauri; +https://github.com/fruti-studio)",
@@ -4845,9 +5551,16 @@ async fn download_with_retries(
             }
 
             match send_request_with_retry(request, 3).await {
-                Ok(response) => {
+                Ok((response, latency)) => {
+                    let transfer_started = Instant::now();
                     let status = response.status();
                     if !status.is_success() {
+                        // Un 404 solo indica que el mirror no tiene el archivo, no que esté caído.
+                        if status.is_server_error()
+                            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        {
+                            health.record_failure(url);
+                        }
                         last_error = Some(format!("{url} respondió {status}"));
                         continue;
                     }
@@ -4890,13 +5603,25 @@ async fn download_with_retries(
                         access_denied_hint(&partial_path, "abrir temporal de descarga", &error)
                     })?;
                     let mut writer = BufWriter::new(output);
+                    let url_host = Url::parse(url)
+                        .ok()
+                        .and_then(|parsed| parsed.host_str().map(str::to_string));
                     let mut stream = response.bytes_stream();
                     let mut received_bytes = 0_u64;
 
                     while let Some(chunk_result) = stream.next().await {
-                        let chunk = chunk_result
-                            .map_err(|error| format!("No se pudo leer respuesta {url}: {error}"))?;
+                        let chunk = chunk_result.map_err(|error| {
+                            health.record_failure(url);
+                            format!("No se pudo leer respuesta {url}: {error}")
+                        })?;
+                        Downloader::global().wait_while_paused().await;
                         received_bytes = received_bytes.saturating_add(chunk.len() as u64);
+                        BandwidthLimiter::global()
+                            .throttle(url_host.as_deref(), chunk.len() as u64)
+                            .await;
+                        if let Some(progress) = progress {
+                            progress.add_bytes(chunk.len() as u64);
+                        }
                         writer.write_all(&chunk).await.map_err(|error| {
                             access_denied_hint(
                                 &partial_path,
@@ -4919,89 +5644,33 @@ async fn download_with_retries(
                             last_error = Some(format!(
                                 "{url} devolvió Content-Length inválido (esperado {content_length} bytes, recibido {received_bytes} bytes)",
                             ));
+                            health.record_failure(url);
                             let _ = fs::remove_file(&partial_path);
                             continue;
                         }
                     }
 
-                    if validate_zip && !is_valid_zip_stream(&partial_path) {
-                        last_error = Some(format!("{url} devolvió un archivo zip/jar inválido"));
-                        let _ = fs::remove_file(&partial_path);
-                        continue;
-                    }
-
-                    if let Some(expected) = expected_sha1 {
-                        let downloaded_hash = file_sha1(&partial_path)?;
-                        if !downloaded_hash.eq_ignore_ascii_case(expected) {
-                            last_error = Some(format!(
-                                "{url} devolvió hash SHA1 inválido (esperado {expected}, obtenido {downloaded_hash})"
-                            ));
-                            let _ = fs::remove_file(&partial_path);
+                    match commit_partial_download(url, path, &partial_path, expected, validate_zip)
+                        .await?
+                    {
+                        None => {}
+                        Some(error) => {
+                            health.record_failure(url);
+                            last_error = Some(error);
                             continue;
                         }
                     }
-
-                    let mut moved = false;
-                    for _ in 0..4 {
-                        if let Some(parent) = path.parent() {
-                            ensure_writable_dir(parent)?;
-                        }
-                        if !partial_path.exists() {
-                            break;
-                        }
-                        match fs::rename(&partial_path, path) {
-                            Ok(()) => {
-                                moved = true;
-                                break;
-                            }
-                            Err(rename_error) => {
-                                if path.exists() {
-                                    match remove_file_with_retry(path, "reemplazar archivo destino")
-                                    {
-                                        Ok(()) => {
-                                            if fs::rename(&partial_path, path).is_ok() {
-                                                moved = true;
-                                                break;
-                                            }
-                                        }
-                                        Err(remove_error) => {
-                                            last_error.get_or_insert(remove_error);
-                                        }
-                                    }
-                                }
-
-                                if is_windows_access_denied(&rename_error) {
-                                    last_error.get_or_insert(access_denied_hint(
-                                        path,
-                                        "mover temporal a destino",
-                                        &rename_error,
-                                    ));
-                                }
-                            }
-                        }
-                        tokio::time::sleep(std::time::Duration::from_millis(120)).await;
-                    }
-
-                    if !moved {
-                        let final_exists = path.exists();
-                        return Err(format!(
-                            "No se pudo mover temporal a destino. sha1={}; temp={}; final={}; finalExiste={final_exists}",
-                            normalized_expected_sha1
-                                .as_deref()
-                                .unwrap_or("sin-sha1"),
-                            normalized_display_path(&partial_path),
-                            normalized_display_path(path),
-                        ));
-                    }
-
-                    if let Some(expected_sha1) = normalized_expected_sha1.as_deref() {
-                        let cache_root = launcher_global_download_cache_dir();
-                        let _ = persist_binary_to_global_cache(&cache_root, path, expected_sha1);
-                    }
+                    health.record_success(
+                        url,
+                        latency,
+                        throughput_sample_bytes(url, received_bytes),
+                        latency + transfer_started.elapsed(),
+                    );
                     println!("[download:{stage}] repositorio exitoso: {url}");
                     return Ok(());
                 }
                 Err(error) => {
+                    health.record_failure(url);
                     last_error = Some(format!("No se pudo descargar {url}: {error}"));
                 }
             }
@@ -5032,60 +5701,202 @@ async fn download_with_retries(
     ))
 }
 
-fn parse_maven_coordinate_from_url(url: &str) -> Option<String> {
-    let parsed = Url::parse(url).ok()?;
-    let segments = parsed
-        .path_segments()?
-        .filter(|segment| !segment.is_empty())
-        .collect::<Vec<_>>();
-    if segments.len() < 4 {
-        return None;
+/// Copia un archivo de un mirror `file://` con las mismas validaciones que una descarga HTTP.
+fn copy_from_local_mirror(
+    source: &Path,
+    path: &Path,
+    partial_path: &Path,
+    expected_sha1: Option<&str>,
+    validate_zip: bool,
+) -> Result<u64, String> {
+    let copied = fs::copy(source, partial_path).map_err(|error| {
+        format!(
+            "No se pudo copiar desde mirror local {}: {error}",
+            source.display()
+        )
+    })?;
+    if validate_zip && !is_valid_zip_stream(partial_path) {
+        let _ = fs::remove_file(partial_path);
+        return Err(format!(
+            "{} es un archivo zip/jar inválido",
+            source.display()
+        ));
     }
-    let file = *segments.last()?;
-    let version = *segments.get(segments.len() - 2)?;
-    let artifact = *segments.get(segments.len() - 3)?;
-    let group = segments[..segments.len() - 3].join(".");
-    if !file.starts_with(&format!("{artifact}-{version}")) {
-        return None;
+    if let Some(expected) = expected_sha1 {
+        let actual = file_sha1(partial_path)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(partial_path);
+            return Err(format!(
+                "{} tiene hash SHA1 inválido (esperado {expected}, obtenido {actual})",
+                source.display()
+            ));
+        }
     }
-    Some(format!("{group}:{artifact}:{version}"))
+    remove_file_with_retry(path, "reemplazar archivo destino")?;
+    fs::rename(partial_path, path)
+        .map_err(|error| access_denied_hint(path, "mover temporal a destino", &error))?;
+    Ok(copied)
 }
 
-fn is_maven_artifact_url(url: &str) -> bool {
-    url.contains("/maven2/")
-        || url.contains("libraries.minecraft.net/")
-        || url.contains("maven.minecraftforge.net/")
-        || url.contains("maven.neoforged.net/")
-        || url.contains("maven.fabricmc.net/")
-        || url.contains("maven.quiltmc.org/")
-        || url.contains("maven.pkg.jetbrains.space/")
+/// Hashes esperados de una descarga; SHA-256 solo lo publican algunos artefactos.
+/// Con `size` conocido y grande se descarga por segmentos sin sondear antes con HEAD.
+#[derive(Debug, Clone, Copy, Default)]
+struct ExpectedDigest<'a> {
+    sha1: Option<&'a str>,
+    sha256: Option<&'a str>,
+    size: Option<u64>,
 }
 
-async fn preflight_maven_availability(client: &reqwest::Client, urls: &[String]) -> Option<String> {
-    let maven_urls = urls
-        .iter()
-        .map(String::as_str)
-        .filter(|url| is_maven_artifact_url(url))
-        .collect::<Vec<_>>();
-    if maven_urls.is_empty() || maven_urls.len() != urls.len() {
-        return None;
+/// Verifica el temporal descargado y lo mueve al destino. `Ok(Some(motivo))` indica que el
+/// contenido del mirror no es válido y hay que probar otro.
+async fn commit_partial_download(
+    url: &str,
+    path: &Path,
+    partial_path: &Path,
+    expected: ExpectedDigest<'_>,
+    validate_zip: bool,
+) -> Result<Option<String>, String> {
+    let normalized_expected_sha1 = normalized_sha1(expected.sha1)?;
+    if validate_zip && !is_valid_zip_stream(partial_path) {
+        let _ = fs::remove_file(partial_path);
+        return Ok(Some(format!("{url} devolvió un archivo zip/jar inválido")));
     }
 
-    let mut checked = 0usize;
-    let mut missing = 0usize;
-    for url in &maven_urls {
-        let Ok(response) = client.head(*url).send().await else {
-            continue;
-        };
-        checked += 1;
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            missing += 1;
+    if let Some(expected) = expected.sha1 {
+        let downloaded_hash = file_sha1(partial_path)?;
+        if !downloaded_hash.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(partial_path);
+            return Ok(Some(format!(
+                "{url} devolvió hash SHA1 inválido (esperado {expected}, obtenido {downloaded_hash})"
+            )));
         }
     }
 
-    if checked > 0 && checked == missing {
-        let pom_candidates = maven_urls
-            .iter()
+    if let Some(expected) = expected.sha256 {
+        let downloaded_hash = file_sha256(partial_path)?;
+        if !downloaded_hash.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(partial_path);
+            return Ok(Some(format!(
+                "{url} devolvió hash SHA-256 inválido (esperado {expected}, obtenido {downloaded_hash})"
+            )));
+        }
+    }
+
+    let mut moved = false;
+    let mut last_error = None;
+    for _ in 0..4 {
+        if let Some(parent) = path.parent() {
+            ensure_writable_dir(parent)?;
+        }
+        if !partial_path.exists() {
+            break;
+        }
+        match fs::rename(partial_path, path) {
+            Ok(()) => {
+                moved = true;
+                break;
+            }
+            Err(rename_error) => {
+                if path.exists() {
+                    match remove_file_with_retry(path, "reemplazar archivo destino") {
+                        Ok(()) => {
+                            if fs::rename(partial_path, path).is_ok() {
+                                moved = true;
+                                break;
+                            }
+                        }
+                        Err(remove_error) => {
+                            last_error.get_or_insert(remove_error);
+                        }
+                    }
+                }
+
+                if is_windows_access_denied(&rename_error) {
+                    last_error.get_or_insert(access_denied_hint(
+                        path,
+                        "mover temporal a destino",
+                        &rename_error,
+                    ));
+                }
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(120)).await;
+    }
+
+    if !moved {
+        let final_exists = path.exists();
+        return Err(format!(
+            "No se pudo mover temporal a destino. sha1={}; temp={}; final={}; finalExiste={final_exists}; detalle={}",
+            normalized_expected_sha1
+                .as_deref()
+                .unwrap_or("sin-sha1"),
+            normalized_display_path(partial_path),
+            normalized_display_path(path),
+            last_error.unwrap_or_else(|| "desconocido".to_string()),
+        ));
+    }
+
+    if let Some(expected_sha1) = normalized_expected_sha1.as_deref() {
+        let cache_root = launcher_global_download_cache_dir();
+        let _ = persist_binary_to_global_cache(&cache_root, path, expected_sha1);
+    }
+    Ok(None)
+}
+
+fn parse_maven_coordinate_from_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let segments = parsed
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    if segments.len() < 4 {
+        return None;
+    }
+    let file = *segments.last()?;
+    let version = *segments.get(segments.len() - 2)?;
+    let artifact = *segments.get(segments.len() - 3)?;
+    let group = segments[..segments.len() - 3].join(".");
+    if !file.starts_with(&format!("{artifact}-{version}")) {
+        return None;
+    }
+    Some(format!("{group}:{artifact}:{version}"))
+}
+
+fn is_maven_artifact_url(url: &str) -> bool {
+    url.contains("/maven2/")
+        || url.contains("libraries.minecraft.net/")
+        || url.contains("maven.minecraftforge.net/")
+        || url.contains("maven.neoforged.net/")
+        || url.contains("maven.fabricmc.net/")
+        || url.contains("maven.quiltmc.org/")
+        || url.contains("maven.pkg.jetbrains.space/")
+}
+
+async fn preflight_maven_availability(client: &reqwest::Client, urls: &[String]) -> Option<String> {
+    let maven_urls = urls
+        .iter()
+        .map(String::as_str)
+        .filter(|url| is_maven_artifact_url(url))
+        .collect::<Vec<_>>();
+    if maven_urls.is_empty() || maven_urls.len() != urls.len() {
+        return None;
+    }
+
+    let mut checked = 0usize;
+    let mut missing = 0usize;
+    for url in &maven_urls {
+        let Ok(response) = client.head(*url).send().await else {
+            continue;
+        };
+        checked += 1;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            missing += 1;
+        }
+    }
+
+    if checked > 0 && checked == missing {
+        let pom_candidates = maven_urls
+            .iter()
             .filter_map(|url| url.strip_suffix(".jar").map(|base| format!("{base}.pom")))
             .collect::<Vec<_>>();
 
@@ -5112,7 +5923,9 @@ async fn preflight_maven_availability(client: &reqwest::Client, urls: &[String])
 
 async fn download_many_with_limit<F>(
     items: Vec<AssetDownloadTask>,
-    concurrency: usize,
+    group: &str,
+    progress: &ProgressReporter,
+    journal: &DownloadJournal,
     on_item_complete: F,
 ) -> Result<(), String>
 where
@@ -5122,39 +5935,57 @@ where
         return Ok(());
     }
 
-    let tuning = NetworkTuning::default();
+    let tuning = active_network_tuning();
+    let concurrency = Downloader::global().config().max_parallel_downloads.max(1);
     let completed = std::sync::Arc::new(AtomicUsize::new(0));
     let on_item_complete = std::sync::Arc::new(on_item_complete);
 
     stream::iter(items)
         .map(Ok::<AssetDownloadTask, String>)
-        .try_for_each_concurrent(concurrency.max(1), |task| {
+        .try_for_each_concurrent(concurrency, |task| {
             let tuning = tuning.clone();
             let completed = completed.clone();
             let on_item_complete = on_item_complete.clone();
             async move {
                 let temp_path = download_partial_path(&task.path);
-                download_with_retries(
-                    &task.urls,
-                    &task.path,
-                    Some(&task.sha1),
-                    tuning.retries,
-                    false,
-                    &tuning,
-                    "asset",
-                )
-                .await
-                .map_err(|error| {
-                    format!(
-                        "Asset {} ({}) falló: {} | final={} | temp={}",
-                        task.object_name,
-                        task.sha1,
-                        error,
-                        normalized_display_path(&task.path),
-                        normalized_display_path(&temp_path)
+                Downloader::global()
+                    .run(
+                        download_request(
+                            &task.urls,
+                            &task.path,
+                            Some(&task.sha1),
+                            format!("Asset {}", task.object_name),
+                            Some(group),
+                            DownloadPriority::Asset,
+                        ),
+                        download_with_progress(
+                            &task.urls,
+                            &task.path,
+                            ExpectedDigest {
+                                sha1: Some(&task.sha1),
+                                sha256: None,
+                                size: Some(task.size),
+                            },
+                            false,
+                            &tuning,
+                            "asset",
+                            Some(progress),
+                        ),
                     )
-                })?;
+                    .await
+                    .map_err(|error| {
+                        format!(
+                            "Asset {} ({}) falló: {} | final={} | temp={}",
+                            task.object_name,
+                            task.sha1,
+                            error,
+                            normalized_display_path(&task.path),
+                            normalized_display_path(&temp_path)
+                        )
+                    })?;
 
+                progress.file_done();
+                journal.mark_completed(&task.path);
                 let completed_now = completed.fetch_add(1, Ordering::SeqCst) + 1;
                 on_item_complete(completed_now)?;
                 Ok::<(), String>(())
@@ -5163,45 +5994,88 @@ where
         .await
 }
 
-fn recommended_download_concurrency(multiplier: usize, min: usize, max: usize) -> usize {
-    let workers = std::thread::available_parallelism()
-        .map(|value| value.get())
-        .unwrap_or(4)
-        .saturating_mul(multiplier.max(1));
-    workers.clamp(min.max(1), max.max(min.max(1)))
+/// Describe una descarga para la cola global (`list_downloads`).
+fn download_request(
+    urls: &[String],
+    path: &Path,
+    sha1: Option<&str>,
+    label: String,
+    group: Option<&str>,
+    priority: DownloadPriority,
+) -> DownloadRequest {
+    DownloadRequest {
+        item: DownloadItem {
+            url: urls.first().cloned().unwrap_or_default(),
+            destination: path.to_string_lossy().to_string(),
+            sha1: sha1.map(str::to_string),
+        },
+        label,
+        group: group.map(str::to_string),
+        priority,
+    }
+}
+
+fn bootstrap_progress_reporter(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+) -> std::sync::Arc<ProgressReporter> {
+    let app = app.clone();
+    std::sync::Arc::new(ProgressReporter::new(
+        instance_id,
+        std::sync::Arc::new(move |event| {
+            let _ = app.emit(BOOTSTRAP_PROGRESS_EVENT, event);
+        }),
+    ))
 }
 
 async fn download_binaries_with_limit(
     items: Vec<BinaryDownloadTask>,
-    concurrency: usize,
+    group: &str,
+    progress: &std::sync::Arc<ProgressReporter>,
+    journal: &std::sync::Arc<DownloadJournal>,
 ) -> Result<(), String> {
     if items.is_empty() {
         return Ok(());
     }
 
-    let gate = std::sync::Arc::new(Semaphore::new(concurrency.max(1)));
     let mut tasks = tokio::task::JoinSet::new();
-    let tuning = NetworkTuning::default();
+    let tuning = active_network_tuning();
 
     for task in items {
-        let permit_gate = gate.clone();
         let tuning = tuning.clone();
+        let group = group.to_string();
+        let progress = progress.clone();
+        let journal = journal.clone();
         tasks.spawn(async move {
-            let _permit = permit_gate
-                .acquire_owned()
+            Downloader::global()
+                .run(
+                    download_request(
+                        &task.urls,
+                        &task.path,
+                        task.sha1.as_deref(),
+                        task.label.clone(),
+                        Some(&group),
+                        DownloadPriority::Library,
+                    ),
+                    download_with_progress(
+                        &task.urls,
+                        &task.path,
+                        ExpectedDigest {
+                            sha1: task.sha1.as_deref(),
+                            sha256: task.sha256.as_deref(),
+                            size: task.size,
+                        },
+                        task.validate_zip,
+                        &tuning,
+                        "binary",
+                        Some(&progress),
+                    ),
+                )
                 .await
-                .map_err(|error| format!("No se pudo adquirir cupo de descarga: {error}"))?;
-            download_with_retries(
-                &task.urls,
-                &task.path,
-                task.sha1.as_deref(),
-                tuning.retries,
-                task.validate_zip,
-                &tuning,
-                "binary",
-            )
-            .await
-            .map_err(|error| format!("{} falló: {error}", task.label))
+                .map_err(|error| format!("{} falló: {error}", task.label))?;
+            progress.file_done();
+            journal.mark_completed(&task.path);
+            Ok::<(), String>(())
         });
     }
 
@@ -5216,7 +6090,8 @@ async fn download_binaries_with_limit(
     Ok(())
 }
 
-fn remove_partial_files(root: &Path) -> Result<u64, String> {
+/// Borra `.part` bajo `root`. Con diario, conserva los de descargas pendientes para reanudarlas.
+fn remove_partial_files(root: &Path, journal: Option<&DownloadJournal>) -> Result<u64, String> {
     if !root.exists() {
         return Ok(0);
     }
@@ -5274,7 +6149,8 @@ fn remove_partial_files(root: &Path) -> Result<u64, String> {
             }
 
             let file_name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
-            if file_name.ends_with(".part") || file_name.contains(".part.") {
+            let abandoned = !journal.is_some_and(|journal| journal.keeps_partial(&path));
+            if abandoned && (file_name.ends_with(".part") || file_name.contains(".part.")) {
                 fs::remove_file(&path).map_err(|error| {
                     format!(
                         "No se pudo limpiar temporal incompleto {}: {error}",
@@ -5377,7 +6253,8 @@ fn validate_minecraft_client_jar(
         };
     }
 
-    if metadata.len() < MIN_CLIENT_JAR_SIZE_BYTES {
+    // Con SHA1 declarado manda el hash: los jars anteriores a 1.13 pesan menos del mínimo.
+    if expected_sha1.is_none() && metadata.len() < MIN_CLIENT_JAR_SIZE_BYTES {
         return MinecraftJarValidation {
             ok: false,
             reason: Some(format!(
@@ -6050,7 +6927,7 @@ fn evaluate_mod_loader_compatibility(
 }
 
 async fn fetch_json_with_fallback(urls: &[String], context: &str) -> Result<Value, String> {
-    let client = reqwest::Client::builder()
+    let client = http_client_builder(&active_network_tuning())?
         .connect_timeout(std::time::Duration::from_secs(env_u64(
             "FRUTI_ENDPOINT_CONNECT_TIMEOUT_SECS",
             10,
@@ -6066,29 +6943,31 @@ async fn fetch_json_with_fallback(urls: &[String], context: &str) -> Result<Valu
     let retries = env_u64("FRUTI_ENDPOINT_RETRIES", 3).max(1);
     for attempt in 1..=retries {
         for url in urls {
-            match client.get(url).send().await {
-                Ok(response) => {
-                    if !response.status().is_success() {
+            if let Some(local) = download_routes::read_local_mirror(url) {
+                match local.and_then(|raw| {
+                    serde_json::from_slice::<Value>(&raw)
+                        .map_err(|error| format!("JSON inválido en {url}: {error}"))
+                }) {
+                    Ok(json) => return Ok(json),
+                    Err(error) => last_error = Some(error),
+                }
+                continue;
+            }
+            let cache = HttpMetadataCache::global();
+            match cache.fetch_text(client.get(url), url).await {
+                Ok(raw) => match serde_json::from_str::<Value>(&raw) {
+                    Ok(json) => return Ok(json),
+                    Err(error) => {
+                        cache.invalidate(url);
                         last_error = Some(format!(
-                            "{url} [{}] respondió {}",
-                            download_routes::endpoint_label(url),
-                            response.status()
+                            "JSON inválido en {url} [{}]: {error}",
+                            download_routes::endpoint_label(url)
                         ));
-                        continue;
                     }
-                    match response.json::<Value>().await {
-                        Ok(json) => return Ok(json),
-                        Err(error) => {
-                            last_error = Some(format!(
-                                "JSON inválido en {url} [{}]: {error}",
-                                download_routes::endpoint_label(url)
-                            ));
-                        }
-                    }
-                }
+                },
                 Err(error) => {
                     last_error = Some(format!(
-                        "No se pudo descargar {url} [{}]: {error}",
+                        "{error} [{}]",
                         download_routes::endpoint_label(url)
                     ));
                 }
@@ -6207,13 +7086,14 @@ fn normalize_uuid(raw: &str) -> Option<String> {
 }
 
 fn default_offline_uuid(username: &str) -> String {
+    offline_player_uuid(username)
+}
+
+/// Prefijo de los UUID aleatorios que generaban versiones anteriores (murmurhash + tiempo).
+fn legacy_offline_uuid_prefix(username: &str) -> String {
     let left = murmurhash2(username.as_bytes());
     let right = murmurhash2(format!("fruti-{username}").as_bytes());
-    let time = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or_default();
-    format!("{left:08x}{right:08x}{:016x}", time)
+    format!("{left:08x}{right:08x}")
 }
 
 fn current_unix_secs() -> u64 {
@@ -6223,7 +7103,18 @@ fn current_unix_secs() -> u64 {
         .unwrap_or(0)
 }
 
+/// Valor de `${auth_session}` en versiones antiguas; `-` equivale a sesión offline.
+fn legacy_auth_session(access_token: &str, uuid: &str) -> String {
+    let token = access_token.trim();
+    if token.is_empty() || token == "0" {
+        "-".to_string()
+    } else {
+        format!("token:{token}:{}", uuid.replace('-', ""))
+    }
+}
+
 fn apply_auth_to_launch_plan(plan: &mut LaunchPlan, auth: LaunchAuth) {
+    let legacy_arguments = plan.legacy_arguments;
     let mut replace_flag_value = |flag: &str, value: &str| {
         if let Some(index) = plan.game_args.iter().position(|arg| arg == flag) {
             if let Some(slot) = plan.game_args.get_mut(index + 1) {
@@ -6231,6 +7122,9 @@ fn apply_auth_to_launch_plan(plan: &mut LaunchPlan, auth: LaunchAuth) {
                 return;
             }
         }
+        if legacy_arguments {
+            return;
+        }
         plan.game_args.push(flag.to_string());
         plan.game_args.push(value.to_string());
     };
@@ -6239,6 +7133,24 @@ fn apply_auth_to_launch_plan(plan: &mut LaunchPlan, auth: LaunchAuth) {
     replace_flag_value("--uuid", &auth.uuid);
     replace_flag_value("--accessToken", &auth.access_token);
     replace_flag_value("--userType", &auth.user_type);
+
+    if legacy_arguments {
+        let session = legacy_auth_session(&auth.access_token, &auth.uuid);
+        replace_game_arg_if_present(&mut plan.game_args, "--session", session.clone());
+        // Pre-1.6: `${auth_player_name} ${auth_session}` van como argumentos posicionales.
+        let positional = plan
+            .game_args
+            .iter()
+            .take(2)
+            .take_while(|arg| !arg.starts_with("--"))
+            .count();
+        if positional >= 1 {
+            plan.game_args[0] = auth.username.clone();
+        }
+        if positional >= 2 {
+            plan.game_args[1] = session;
+        }
+    }
     plan.auth = auth;
 }
 
@@ -6318,14 +7230,23 @@ fn normalize_critical_game_args(
         version_type = format!("{pretty_loader}{loader_suffix}");
     }
 
-    ensure_single_game_arg(plan, "--username", &username);
-    ensure_single_game_arg(plan, "--version", version);
-    ensure_single_game_arg(plan, "--uuid", &uuid);
-    ensure_single_game_arg(plan, "--accessToken", &access_token);
-    ensure_single_game_arg(plan, "--userType", &user_type);
-    ensure_single_game_arg(plan, "--versionType", &version_type);
+    let critical_args = [
+        ("--username", username.as_str()),
+        ("--version", version),
+        ("--uuid", uuid.as_str()),
+        ("--accessToken", access_token.as_str()),
+        ("--userType", user_type.as_str()),
+        ("--versionType", version_type.as_str()),
+    ];
+    for (flag, value) in critical_args {
+        if plan.legacy_arguments {
+            replace_game_arg_if_present(&mut plan.game_args, flag, value.to_string());
+        } else {
+            ensure_single_game_arg(plan, flag, value);
+        }
+    }
 
-    if normalized_loader == "forge" || normalized_loader == "neoforge" {
+    if !plan.legacy_arguments && (normalized_loader == "forge" || normalized_loader == "neoforge") {
         let fallback_target = if normalized_loader == "forge" {
             "forgeclient"
         } else {
@@ -6423,6 +7344,54 @@ fn loader_profile_chain_is_valid(plan: &LaunchPlan) -> bool {
     .is_ok()
 }
 
+/// Descarga y verifica `logging.client` en `assets/log_configs`; devuelve el argumento JVM ya expandido.
+async fn prepare_logging_client_config(
+    minecraft_root: &Path,
+    version_json: &Value,
+    log_format: LogFormat,
+    tuning: &NetworkTuning,
+) -> Result<Option<String>, String> {
+    let Some(config) = LoggingClientConfig::from_version_json(version_json) else {
+        return Ok(None);
+    };
+    let log_configs_dir = minecraft_root.join("assets").join("log_configs");
+    let mojang_config = log_configs_dir.join(&config.file_id);
+    download_with_retries(
+        &mirror_candidates_for_url(&config.url),
+        &mojang_config,
+        ExpectedDigest {
+            sha1: config.sha1.as_deref(),
+            ..ExpectedDigest::default()
+        },
+        tuning.retries,
+        false,
+        tuning,
+        "logging_config",
+    )
+    .await?;
+
+    let config_path = match log_format {
+        LogFormat::Xml => mojang_config,
+        LogFormat::Plain => {
+            let raw = fs::read_to_string(&mojang_config).map_err(|error| {
+                format!(
+                    "No se pudo leer configuración de logging {}: {error}",
+                    mojang_config.display()
+                )
+            })?;
+            let plain_path = log_configs_dir.join(config.plain_file_id());
+            fs::write(&plain_path, plain_text_logging_config(&raw)).map_err(|error| {
+                format!(
+                    "No se pudo escribir configuración de logging {}: {error}",
+                    plain_path.display()
+                )
+            })?;
+            plain_path
+        }
+    };
+    Ok(Some(config.jvm_argument(&config_path.to_string_lossy())))
+}
+
 async fn bootstrap_instance_runtime(
     app: &tauri::AppHandle,
     instance_root: &Path,
@@ -6430,6 +7399,7 @@ async fn bootstrap_instance_runtime(
 ) -> Result<(), String> {
     let launch_config = resolve_instance_launch_config(instance_root, instance);
     let minecraft_root = launch_config.game_dir.clone();
+    let progress = bootstrap_progress_reporter(app, &instance.id);
     let version = launch_config.minecraft_version.trim();
     let mut loader = launch_config.modloader.trim().to_ascii_lowercase();
 
@@ -6441,10 +7411,22 @@ async fn bootstrap_instance_runtime(
         .map_err(|error| format!("No se pudo asegurar libraries global: {error}"))?;
     let versions_dir = minecraft_root.join("versions");
     let mods_dir = minecraft_root.join("mods");
-    let cleaned_assets = remove_partial_files(&assets_objects_dir)?;
-    let cleaned_libraries = remove_partial_files(&libraries_dir)?;
-    let cleaned_versions = remove_partial_files(&versions_dir)?;
-    let cleaned_mods = remove_partial_files(&mods_dir)?;
+    let journal = std::sync::Arc::new(DownloadJournal::open(instance_root, &instance.id));
+    let (journal_completed, journal_pending) = journal.counts();
+    if journal_pending > 0 {
+        write_instance_state(
+            instance_root,
+            "resuming_bootstrap",
+            serde_json::json!({
+                "completed": journal_completed,
+                "pending": journal_pending,
+            }),
+        );
+    }
+    let cleaned_assets = remove_partial_files(&assets_objects_dir, Some(&journal))?;
+    let cleaned_libraries = remove_partial_files(&libraries_dir, Some(&journal))?;
+    let cleaned_versions = remove_partial_files(&versions_dir, Some(&journal))?;
+    let cleaned_mods = remove_partial_files(&mods_dir, None)?;
     if cleaned_assets > 0 || cleaned_libraries > 0 || cleaned_versions > 0 || cleaned_mods > 0 {
         write_instance_state(
             instance_root,
@@ -6458,30 +7440,20 @@ async fn bootstrap_instance_runtime(
         );
     }
 
-    let manifest_urls = download_routes::MINECRAFT_MANIFEST_URLS
-        .iter()
-        .map(|url| (*url).to_string())
-        .collect::<Vec<_>>();
     write_instance_state(
         instance_root,
         "downloading_manifest",
         serde_json::json!({"step": "version_manifest"}),
     );
-    let manifest_json = fetch_json_with_fallback(&manifest_urls, "manifiesto de versiones").await?;
-    let manifest = serde_json::from_value::<MojangVersionManifest>(manifest_json)
-        .map_err(|error| format!("No se pudo parsear el manifiesto de versiones: {error}"))?;
-
-    let Some(version_entry) = manifest
-        .versions
-        .into_iter()
-        .find(|entry| entry.id == version)
-    else {
-        return Err(format!(
-            "La versión {version} no existe en el manifiesto oficial."
-        ));
-    };
-
-    let version_json_urls = download_routes::version_metadata_urls(&version_entry.url);
+    let manifest_tuning = active_network_tuning();
+    let resolved_version = minecraft_version_resolver(&manifest_tuning)?
+        .load_manifest(false)
+        .await?
+        .manifest
+        .resolve(version)?;
+
+    let version_json_urls =
+        download_routes::version_metadata_urls(&resolved_version.version_json_url);
     write_instance_state(
         instance_root,
         "downloading_version_metadata",
@@ -6527,17 +7499,40 @@ async fn bootstrap_instance_runtime(
             "sha1": client_sha1
         }),
     );
-    let network_tuning = resolve_network_tuning(Some(&load_config(app.clone()).await?));
-    download_with_retries(
-        &[client_url.to_string()],
-        &client_jar,
-        Some(client_sha1),
-        network_tuning.retries,
-        should_validate_zip_from_path(&client_jar),
-        &network_tuning,
-        "client_jar",
-    )
-    .await?;
+    let network_tuning = active_network_tuning();
+    let client_urls = [client_url.to_string()];
+    progress.begin_step(
+        "download_client_jar",
+        1,
+        client_download.get("size").and_then(Value::as_u64),
+    );
+    Downloader::global()
+        .run(
+            download_request(
+                &client_urls,
+                &client_jar,
+                Some(client_sha1),
+                format!("Client jar {version}"),
+                Some(&instance.id),
+                DownloadPriority::Critical,
+            ),
+            download_with_progress(
+                &client_urls,
+                &client_jar,
+                ExpectedDigest {
+                    sha1: Some(client_sha1),
+                    sha256: None,
+                    size: client_download.get("size").and_then(Value::as_u64),
+                },
+                should_validate_zip_from_path(&client_jar),
+                &network_tuning,
+                "client_jar",
+                Some(&progress),
+            ),
+        )
+        .await?;
+
+    progress.file_done();
 
     let initial_jar_validation = validate_minecraft_client_jar(&client_jar, Some(client_sha1));
     if !initial_jar_validation.ok {
@@ -6564,16 +7559,32 @@ async fn bootstrap_instance_runtime(
                 "deletedVersionDir": version_dir.to_string_lossy()
             }),
         );
-        download_with_retries(
-            &[client_url.to_string()],
-            &client_jar,
-            Some(client_sha1),
-            network_tuning.retries,
-            should_validate_zip_from_path(&client_jar),
-            &network_tuning,
-            "client_jar_retry",
-        )
-        .await?;
+        let client_urls = [client_url.to_string()];
+        Downloader::global()
+            .run(
+                download_request(
+                    &client_urls,
+                    &client_jar,
+                    Some(client_sha1),
+                    format!("Client jar {version}"),
+                    Some(&instance.id),
+                    DownloadPriority::Critical,
+                ),
+                download_with_progress(
+                    &client_urls,
+                    &client_jar,
+                    ExpectedDigest {
+                        sha1: Some(client_sha1),
+                        sha256: None,
+                        size: client_download.get("size").and_then(Value::as_u64),
+                    },
+                    should_validate_zip_from_path(&client_jar),
+                    &network_tuning,
+                    "client_jar_retry",
+                    Some(&progress),
+                ),
+            )
+            .await?;
 
         let second_validation = validate_minecraft_client_jar(&client_jar, Some(client_sha1));
         if !second_validation.ok {
@@ -6666,8 +7677,11 @@ async fn bootstrap_instance_runtime(
     if let Some(objects) = asset_index_json.get("objects").and_then(|v| v.as_object()) {
         let assets_cache_root = launcher_assets_cache_root(app)?;
         ensure_writable_dir(&assets_cache_root)?;
-        let _ = remove_partial_files(&minecraft_root.join("assets").join("objects"));
-        let _ = remove_partial_files(&assets_cache_root);
+        let _ = remove_partial_files(
+            &minecraft_root.join("assets").join("objects"),
+            Some(&journal),
+        );
+        let _ = remove_partial_files(&assets_cache_root, None);
 
         let mut validation_cache = load_asset_validation_cache(app);
         let mut seen_hashes = HashSet::new();
@@ -6693,6 +7707,11 @@ async fn bootstrap_instance_runtime(
                 .join(sub)
                 .join(hash);
 
+            if journal.is_completed(&target, Some(hash)) {
+                reused_existing += 1;
+                continue;
+            }
+
             if expected_size > 0
                 && existing_asset_is_valid(
                     &target,
@@ -6733,9 +7752,18 @@ async fn bootstrap_instance_runtime(
                     .and_then(|v| v.as_str())
                     .unwrap_or("asset")
                     .to_string(),
+                size: expected_size,
             });
         }
 
+        for task in &downloads {
+            journal.plan(
+                &task.path,
+                Some(&task.sha1),
+                Some(task.size).filter(|size| *size > 0),
+            );
+        }
+        journal.flush();
         let total_downloads = downloads.len();
         write_instance_state(
             instance_root,
@@ -6747,25 +7775,40 @@ async fn bootstrap_instance_runtime(
                 "reusedExisting": reused_existing
             }),
         );
-        let asset_concurrency = recommended_download_concurrency(16, 48, 128);
-        download_many_with_limit(downloads.clone(), asset_concurrency, |completed| {
-            if completed == 1 || completed % 25 == 0 || completed == total_downloads {
-                write_instance_state(
-                    instance_root,
-                    "downloading_assets",
-                    serde_json::json!({
-                        "assetIndex": asset_index_id,
-                        "total": total_downloads,
-                        "restoredFromCache": restored_from_cache,
-                        "reusedExisting": reused_existing,
-                        "completed": completed,
-                        "remaining": total_downloads.saturating_sub(completed),
-                        "progress": if total_downloads == 0 { 100 } else { completed * 100 / total_downloads },
-                    }),
-                );
-            }
-            Ok(())
-        })
+        progress.begin_step(
+            "download_assets",
+            total_downloads as u64,
+            Some(downloads.iter().map(|task| task.size).sum()),
+        );
+        download_many_with_limit(
+            downloads.clone(),
+            &instance.id,
+            &progress,
+            &journal,
+            |completed| {
+                if completed == 1 || completed % 25 == 0 || completed == total_downloads {
+                    let percent = if total_downloads == 0 {
+                        100
+                    } else {
+                        completed * 100 / total_downloads
+                    };
+                    write_instance_state(
+                        instance_root,
+                        "downloading_assets",
+                        serde_json::json!({
+                            "assetIndex": asset_index_id,
+                            "total": total_downloads,
+                            "restoredFromCache": restored_from_cache,
+                            "reusedExisting": reused_existing,
+                            "completed": completed,
+                            "remaining": total_downloads.saturating_sub(completed),
+                            "progress": percent,
+                        }),
+                    );
+                }
+                Ok(())
+            },
+        )
         .await?;
         for task in downloads {
             persist_asset_to_cache(&assets_cache_root, &task.path, &task.sha1, &task.sha1)?;
@@ -6792,6 +7835,26 @@ async fn bootstrap_instance_runtime(
         );
     }
 
+    let asset_layout = AssetLayout::from_index(&asset_index_json);
+    let game_assets = game_assets_dir(&minecraft_root, asset_index_id, asset_layout);
+    if asset_layout.is_legacy() {
+        let materialized = materialize_legacy_assets(
+            &minecraft_root.join("assets").join("objects"),
+            &asset_index_json,
+            &game_assets,
+        )?;
+        write_instance_state(
+            instance_root,
+            "assets_ready",
+            serde_json::json!({
+                "assetIndex": asset_index_id,
+                "layout": asset_layout.as_str(),
+                "gameAssets": game_assets.to_string_lossy(),
+                "materialized": materialized
+            }),
+        );
+    }
+
     let mut effective_version_json = base_version_json.clone();
     let detected_loader = detect_loader_from_version_json(&effective_version_json);
     if let Some(detected) = detected_loader {
@@ -6857,6 +7920,7 @@ async fn bootstrap_instance_runtime(
                 version,
                 &loader,
                 Some(launch_config.modloader_version.as_str()),
+                &progress,
             )
             .await?;
             normalize_loader_profile_json_file(
@@ -6978,16 +8042,38 @@ async fn bootstrap_instance_runtime(
                     "target": launch_jar_path.to_string_lossy(),
                 }),
             );
-            download_with_retries(
-                &[client_url.to_string()],
-                &launch_jar_path,
-                client_sha1,
-                network_tuning.retries,
-                should_validate_zip_from_path(&launch_jar_path),
-                &network_tuning,
-                "loader_client_jar",
-            )
-            .await?;
+            let client_urls = [client_url.to_string()];
+            progress.begin_step(
+                "resolve_modloader",
+                1,
+                client_download.get("size").and_then(Value::as_u64),
+            );
+            Downloader::global()
+                .run(
+                    download_request(
+                        &client_urls,
+                        &launch_jar_path,
+                        client_sha1,
+                        format!("Jar de loader {launch_jar_id}"),
+                        Some(&instance.id),
+                        DownloadPriority::Critical,
+                    ),
+                    download_with_progress(
+                        &client_urls,
+                        &launch_jar_path,
+                        ExpectedDigest {
+                            sha1: client_sha1,
+                            sha256: None,
+                            size: client_download.get("size").and_then(Value::as_u64),
+                        },
+                        should_validate_zip_from_path(&launch_jar_path),
+                        &network_tuning,
+                        "loader_client_jar",
+                        Some(&progress),
+                    ),
+                )
+                .await?;
+            progress.file_done();
         }
     }
     if !launch_jar_path.exists() {
@@ -7002,30 +8088,12 @@ async fn bootstrap_instance_runtime(
         .and_then(|v| v.as_str())
         .unwrap_or("net.minecraft.client.main.Main")
         .to_string();
-    let mut game_arguments = effective_version_json
-        .get("arguments")
-        .and_then(|v| v.get("game"))
-        .cloned()
-        .unwrap_or(Value::Array(Vec::new()));
+    let (game_arguments, legacy_arguments) = game_argument_template(&effective_version_json);
     let mut jvm_arguments = effective_version_json
         .get("arguments")
         .and_then(|v| v.get("jvm"))
         .cloned()
         .unwrap_or(Value::Array(Vec::new()));
-    if game_arguments.as_array().is_none() {
-        if let Some(legacy_args) = effective_version_json
-            .get("minecraftArguments")
-            .and_then(|v| v.as_str())
-        {
-            game_arguments = Value::Array(
-                legacy_args
-                    .split_whitespace()
-                    .map(|value| Value::String(value.to_string()))
-                    .collect(),
-            );
-        }
-    }
-
     if jvm_arguments.as_array().is_none() {
         jvm_arguments = Value::Array(Vec::new());
     }
@@ -7059,12 +8127,16 @@ async fn bootstrap_instance_runtime(
         })
         .collect::<Vec<_>>();
 
+    let artifact_ready = |artifact: &ResolvedLibraryArtifact| {
+        journal.is_completed(&artifact.path, artifact.sha1.as_deref())
+            || artifact_valid_on_disk(
+                &artifact.path,
+                artifact.sha1.as_deref(),
+                artifact.sha256.as_deref(),
+            )
+    };
     for artifact in &resolved_artifacts {
-        if artifact_valid_on_disk(
-            &artifact.path,
-            artifact.sha1.as_deref(),
-            artifact.sha256.as_deref(),
-        ) {
+        if artifact_ready(artifact) {
             existing_artifacts.push(artifact.path.to_string_lossy().to_string());
         } else {
             missing_artifacts.push(serde_json::json!({
@@ -7091,21 +8163,23 @@ async fn bootstrap_instance_runtime(
     let mut native_archives = Vec::new();
     let mut library_downloads = Vec::new();
     for artifact in &resolved_artifacts {
-        let sha1 = artifact.sha1.as_deref();
-        let sha256 = artifact.sha256.as_deref();
-        if artifact.urls.is_empty() && !artifact_valid_on_disk(&artifact.path, sha1, sha256) {
+        let ready = artifact_ready(artifact);
+        if artifact.urls.is_empty() && !ready {
             return Err(format!(
                 "No se pudo resolver descarga para librería requerida: {}",
                 artifact.path.display()
             ));
         }
 
-        if !artifact_valid_on_disk(&artifact.path, sha1, sha256) {
+        if !ready {
             let _ = fs::remove_file(&artifact.path);
+            journal.plan(&artifact.path, artifact.sha1.as_deref(), artifact.size);
             library_downloads.push(BinaryDownloadTask {
                 urls: artifact.urls.clone(),
                 path: artifact.path.clone(),
                 sha1: artifact.sha1.clone(),
+                sha256: artifact.sha256.clone(),
+                size: artifact.size,
                 label: format!("Librería {}", artifact.path.display()),
                 validate_zip: should_validate_zip_from_path(&artifact.path),
             });
@@ -7120,13 +8194,15 @@ async fn bootstrap_instance_runtime(
     }
 
     let total_libraries = library_downloads.len();
-    let library_concurrency = recommended_download_concurrency(8, 16, 64);
+    let library_concurrency = Downloader::global().config().max_parallel_downloads;
     write_instance_state(
         instance_root,
         "downloading_libraries",
         serde_json::json!({"step": "libraries", "total": total_libraries, "concurrency": library_concurrency}),
     );
-    download_binaries_with_limit(library_downloads, library_concurrency).await?;
+    journal.flush();
+    progress.begin_step("download_libraries", total_libraries as u64, None);
+    download_binaries_with_limit(library_downloads, &instance.id, &progress, &journal).await?;
 
     for native_jar in native_archives {
         if native_jar.exists() {
@@ -7165,9 +8241,22 @@ async fn bootstrap_instance_runtime(
             Some(&effective_version_json),
         )
     });
+    // El componente exacto de Mojang sólo vale si coincide con la versión mayor elegida.
+    let java_component = [&effective_version_json, &base_version_json]
+        .into_iter()
+        .filter_map(|json| json.get("javaVersion"))
+        .find(|java_version| {
+            java_version
+                .get("majorVersion")
+                .and_then(Value::as_u64)
+                .is_some_and(|major| major == u64::from(java_major))
+        })
+        .and_then(|java_version| java_version.get("component"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
     let runtime_manager = RuntimeManager::new(app)?;
     let selected = runtime_manager
-        .ensure_runtime_for_java_major(java_major)
+        .ensure_runtime(java_major, java_component.as_deref())
         .await
         .ok()
         .map(|path| JavaRuntime {
@@ -7179,6 +8268,7 @@ async fn bootstrap_instance_runtime(
             architecture: std::env::consts::ARCH.to_string(),
             source: "embebido".to_string(),
             recommended: true,
+            vendor: None,
         })
         .ok_or_else(|| {
             format!(
@@ -7224,8 +8314,6 @@ async fn bootstrap_instance_runtime(
     let user = "Player";
     let uuid = default_offline_uuid(user);
 
-    let mut game_args = Vec::new();
-
     let mut java_args = vec![
         format!("-Xms{}M", memory.min),
         format!("-Xmx{}M", memory.max),
@@ -7247,6 +8335,7 @@ async fn bootstrap_instance_runtime(
     };
     let game_dir = minecraft_root.to_string_lossy().to_string();
     let assets_root = minecraft_root.join("assets").to_string_lossy().to_string();
+    let game_assets_value = game_assets.to_string_lossy().to_string();
     let library_directory = libraries_dir.to_string_lossy().to_string();
     let classpath_value = classpath_entries_raw.join(&cp_separator.to_string());
 
@@ -7264,8 +8353,14 @@ async fn bootstrap_instance_runtime(
         ("game_directory", game_dir.clone()),
         ("assets_root", assets_root.clone()),
         ("assets_index_name", asset_index_id.to_string()),
+        ("game_assets", game_assets_value.clone()),
         ("auth_uuid", auth_uuid.clone()),
         ("auth_access_token", auth_access_token.clone()),
+        (
+            "auth_session",
+            legacy_auth_session(&auth_access_token, &auth_uuid),
+        ),
+        ("user_properties", "{}".to_string()),
         ("user_type", "offline".to_string()),
         ("version_type", "Interface".to_string()),
         ("library_directory", library_directory.clone()),
@@ -7281,17 +8376,9 @@ async fn bootstrap_instance_runtime(
         }
     }
 
-    if let Some(values) = game_arguments.as_array() {
-        for value in values {
-            let mut resolved = Vec::new();
-            append_argument_values(&mut resolved, value);
-            for argument in resolved {
-                game_args.push(expand_launch_placeholders(&argument, &replacements));
-            }
-        }
-    }
+    let mut game_args = expand_game_arguments(&game_arguments, &replacements);
 
-    if loader == "forge" || loader == "neoforge" {
+    if !legacy_arguments && (loader == "forge" || loader == "neoforge") {
         let fallback_launch_target = if loader == "forge" {
             "forgeclient"
         } else {
@@ -7311,10 +8398,7 @@ async fn bootstrap_instance_runtime(
         ("--username", user.to_string()),
         ("--version", version_name.clone()),
         ("--gameDir", minecraft_root.to_string_lossy().to_string()),
-        (
-            "--assetsDir",
-            minecraft_root.join("assets").to_string_lossy().to_string(),
-        ),
+        ("--assetsDir", game_assets_value.clone()),
         ("--assetIndex", asset_index_id.to_string()),
         ("--uuid", uuid.clone()),
         ("--accessToken", "0".to_string()),
@@ -7322,13 +8406,23 @@ async fn bootstrap_instance_runtime(
         ("--versionType", "Interface".to_string()),
     ];
 
-    for (key, value) in required_game_args {
-        upsert_game_arg(&mut game_args, key, value);
-    }
+    apply_required_game_args(&mut game_args, required_game_args, legacy_arguments);
 
     sanitize_game_args(&mut game_args);
     normalize_resolution_args(&mut game_args);
 
+    if let Some(logging_argument) = prepare_logging_client_config(
+        &minecraft_root,
+        &effective_version_json,
+        LogFormat::parse(launch_config.log_format.as_deref()),
+        &network_tuning,
+    )
+    .await?
+    {
+        java_args.retain(|arg| !arg.starts_with("-Dlog4j.configurationFile="));
+        java_args.push(logging_argument);
+    }
+
     java_args = normalize_java_launch_args(java_args, classpath_value, &natives_dir);
 
     let persisted_runtime_version_json = persist_instance_runtime_version_json(
@@ -7364,6 +8458,7 @@ async fn bootstrap_instance_runtime(
         resolved_java_major: selected.major,
         loader,
         loader_profile_resolved: true,
+        legacy_arguments,
         auth: LaunchAuth {
             username: user.to_string(),
             uuid,
@@ -7376,17 +8471,12 @@ async fn bootstrap_instance_runtime(
         )]),
     };
 
-    fs::write(
-        instance_root.join("launch-plan.json"),
-        serde_json::to_string_pretty(&launch_plan)
-            .map_err(|error| format!("No se pudo serializar launch plan: {error}"))?,
-    )
-    .map_err(|error| format!("No se pudo escribir launch-plan.json: {error}"))?;
+    write_launch_plan(instance_root, &launch_plan)?;
 
     let mut command = vec![shell_escape(&selected.path)];
     command.extend(java_args.iter().map(|arg| shell_escape(arg)));
     command.push(main_class);
-    command.extend(game_args.iter().map(|arg| shell_escape(arg)));
+    command.extend(redact_args(&game_args).iter().map(|arg| shell_escape(arg)));
 
     fs::write(
         instance_root.join("launch-command.txt"),
@@ -7398,6 +8488,7 @@ async fn bootstrap_instance_runtime(
     )
     .map_err(|error| format!("No se pudo escribir launch-command.txt: {error}"))?;
 
+    journal.finish();
     Ok(())
 }
 
@@ -7556,7 +8647,7 @@ fn write_instance_state(instance_root: &Path, status: &str, details: Value) {
     let state = serde_json::json!({
         "status": status,
         "updatedAt": updated_at,
-        "details": details
+        "details": redact_json(&details)
     });
     let _ = fs::write(
         instance_root.join("instance-state.json"),
@@ -7615,16 +8706,20 @@ fn validate_launch_plan(instance_root: &Path, plan: &LaunchPlan) -> ValidationRe
             canonical_or_original(expected) == canonical_or_original(Path::new(&plan.libraries_dir))
         })
         .unwrap_or(true);
+    let expected_client_sha1 =
+        expected_client_sha1_from_version_json(Path::new(&plan.version_json));
     let minecraft_jar_size_ok = fs::metadata(&version_jar_path)
-        .map(|meta| meta.is_file() && meta.len() >= MIN_MINECRAFT_JAR_SIZE_BYTES)
+        .map(|meta| {
+            meta.is_file()
+                && (meta.len() >= MIN_MINECRAFT_JAR_SIZE_BYTES
+                    || (expected_client_sha1.is_some() && meta.len() > 0))
+        })
         .unwrap_or(false);
     let minecraft_jar_client_marker_ok = if version_jar_path.is_file() {
         has_minecraft_client_marker(&version_jar_path)
     } else {
         false
     };
-    let expected_client_sha1 =
-        expected_client_sha1_from_version_json(Path::new(&plan.version_json));
     let minecraft_jar_sha1_ok = if !version_jar_path.is_file() {
         false
     } else if let Some(expected_sha1) = expected_client_sha1.as_deref() {
@@ -7987,14 +9082,57 @@ fn validate_launch_plan(instance_root: &Path, plan: &LaunchPlan) -> ValidationRe
     }
 }
 
+fn write_launch_plan(instance_root: &Path, plan: &LaunchPlan) -> Result<(), String> {
+    fs::write(
+        instance_root.join("launch-plan.json"),
+        serde_json::to_string_pretty(&plan.redacted())
+            .map_err(|error| format!("No se pudo serializar launch plan: {error}"))?,
+    )
+    .map_err(|error| format!("No se pudo escribir launch-plan.json: {error}"))
+}
+
 fn read_launch_plan(instance_root: &Path) -> Result<LaunchPlan, String> {
     let launch_plan_path = instance_root.join("launch-plan.json");
-    fs::read_to_string(&launch_plan_path)
+    let mut plan = fs::read_to_string(&launch_plan_path)
         .map_err(|error| format!("No se pudo leer launch-plan.json: {error}"))
         .and_then(|raw| {
             serde_json::from_str::<LaunchPlan>(&raw)
                 .map_err(|error| format!("launch-plan.json inválido: {error}"))
-        })
+        })?;
+    if plan.has_persisted_secrets() {
+        scrub_persisted_launch_secrets(instance_root, &plan);
+    }
+    plan.clear_redaction_placeholders();
+    Ok(plan)
+}
+
+/// Migra planes antiguos que guardaban el token de sesión en claro.
+fn scrub_persisted_launch_secrets(instance_root: &Path, plan: &LaunchPlan) {
+    if let Err(error) = write_launch_plan(instance_root, plan) {
+        eprintln!("[auth] No se pudo limpiar token de launch-plan.json: {error}");
+    }
+    let launch_command_path = instance_root.join("launch-command.txt");
+    if let Ok(raw) = fs::read_to_string(&launch_command_path) {
+        let redacted = redact_text(&raw);
+        if redacted != raw {
+            let _ = fs::write(&launch_command_path, redacted);
+        }
+    }
+}
+
+fn migrate_persisted_launch_secrets(app: &tauri::AppHandle) {
+    let Ok(instances_dir) = launcher_root(app).map(|root| root.join("instances")) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(instances_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let instance_root = entry.path();
+        if instance_root.join("launch-plan.json").is_file() {
+            let _ = read_launch_plan(&instance_root);
+        }
+    }
 }
 
 fn launch_plan_matches_instance(
@@ -8245,6 +9383,7 @@ async fn import_external_instance(
             source_instance_name: Some(external.name.clone()),
             java_mode: None,
             java_path: None,
+            log_format: None,
         };
 
         let meta = serde_json::json!({
@@ -8280,7 +9419,7 @@ async fn import_external_instance(
         let connection = database_connection(&app)?;
         connection
             .execute(
-                "INSERT OR REPLACE INTO instances (id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT OR REPLACE INTO instances (id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path, log_format) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 params![
                     record.id,
                     record.name,
@@ -8291,7 +9430,8 @@ async fn import_external_instance(
                     record.source_path,
                     record.source_instance_name,
                     record.java_mode,
-                    record.java_path
+                    record.java_path,
+                    record.log_format
                 ],
             )
             .map_err(|error| format!("No se pudo guardar la instancia externa: {error}"))?;
@@ -8366,6 +9506,33 @@ async fn list_java_runtimes(app: tauri::AppHandle) -> Result<Vec<JavaRuntime>, S
     Ok(manager.detect_installed())
 }
 
+/// Reescanea en segundo plano y avisa con `JAVA_RESCAN_EVENT`. Devuelve `false` si ya hay
+/// un reescaneo en curso.
+#[command]
+async fn rescan_java_runtimes(app: tauri::AppHandle) -> Result<bool, String> {
+    if JAVA_RESCAN_RUNNING.swap(true, Ordering::SeqCst) {
+        return Ok(false);
+    }
+    let manager = match JavaManager::new(&app) {
+        Ok(manager) => manager,
+        Err(error) => {
+            JAVA_RESCAN_RUNNING.store(false, Ordering::SeqCst);
+            return Err(error);
+        }
+    };
+    tokio::task::spawn_blocking(move || {
+        let result = manager.rescan();
+        JAVA_RESCAN_RUNNING.store(false, Ordering::SeqCst);
+        match result {
+            Ok(report) => {
+                let _ = app.emit(JAVA_RESCAN_EVENT, report);
+            }
+            Err(error) => eprintln!("[java] reescaneo fallido: {error}"),
+        }
+    });
+    Ok(true)
+}
+
 #[command]
 async fn resolve_java_for_minecraft(
     app: tauri::AppHandle,
@@ -8379,7 +9546,7 @@ async fn resolve_java_for_minecraft(
 async fn list_instances(app: tauri::AppHandle) -> Result<Vec<InstanceRecord>, String> {
     let conn = database_connection(&app)?;
     let mut stmt = conn
-        .prepare("SELECT id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path FROM instances")
+        .prepare("SELECT id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path, log_format FROM instances")
         .map_err(|error| format!("No se pudo leer instancias: {error}"))?;
     let rows = stmt
         .query_map([], |row| {
@@ -8394,6 +9561,7 @@ async fn list_instances(app: tauri::AppHandle) -> Result<Vec<InstanceRecord>, St
                 source_instance_name: row.get(7)?,
                 java_mode: row.get(8)?,
                 java_path: row.get(9)?,
+                log_format: row.get(10)?,
             })
         })
         .map_err(|error| format!("No se pudo mapear instancias: {error}"))?;
@@ -8522,6 +9690,9 @@ fn write_instance_metadata(instance_root: &Path, instance: &InstanceRecord) -> R
             "mode": java_mode,
             "path": java_path,
         },
+        "logging": {
+            "format": instance.log_format.as_deref().map(str::trim).filter(|value| !value.is_empty()),
+        },
         "memory_alloc": {"min": 2048, "max": 4096},
         "memory": {"min": 2048, "max": 4096},
         "game_dir": game_dir,
@@ -8633,7 +9804,7 @@ fn read_instance_record(
     let conn = database_connection(app)?;
 
     conn.query_row(
-        "SELECT id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path FROM instances WHERE id = ?1",
+        "SELECT id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path, log_format FROM instances WHERE id = ?1",
         params![instance_id],
         |row| {
             Ok(InstanceRecord {
@@ -8647,6 +9818,7 @@ fn read_instance_record(
                 source_instance_name: row.get(7)?,
                 java_mode: row.get(8)?,
                 java_path: row.get(9)?,
+                log_format: row.get(10)?,
             })
         },
     )
@@ -8666,6 +9838,7 @@ async fn create_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Res
         source_instance_name: None,
         java_mode: instance.java_mode.clone(),
         java_path: instance.java_path.clone(),
+        log_format: instance.log_format.clone(),
     };
 
     let conn = database_connection(&app)?;
@@ -8679,7 +9852,7 @@ async fn create_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Res
     let created_at = current_unix_secs() as i64;
 
     conn.execute(
-        "INSERT OR REPLACE INTO instances (id, name, version, path, loader, created_at, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        "INSERT OR REPLACE INTO instances (id, name, version, path, loader, created_at, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path, log_format) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
         params![
             normalized.id,
             normalized.name,
@@ -8693,7 +9866,8 @@ async fn create_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Res
             normalized.source_path,
             normalized.source_instance_name,
             normalized.java_mode,
-            normalized.java_path
+            normalized.java_path,
+            normalized.log_format
         ],
     )
     .map_err(|error| format!("No se pudo crear la instancia: {error}"))?;
@@ -8709,7 +9883,7 @@ async fn create_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Res
 async fn update_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Result<(), String> {
     let conn = database_connection(&app)?;
     conn.execute(
-        "UPDATE instances SET name = ?2, version = ?3, loader_name = ?4, loader_version = ?5, java_mode = ?6, java_path = ?7 WHERE id = ?1",
+        "UPDATE instances SET name = ?2, version = ?3, loader_name = ?4, loader_version = ?5, java_mode = ?6, java_path = ?7, log_format = ?8 WHERE id = ?1",
         params![
             instance.id,
             instance.name,
@@ -8717,7 +9891,8 @@ async fn update_instance(app: tauri::AppHandle, instance: InstanceRecord) -> Res
             instance.loader_name,
             Some(normalized_loader_version(&instance)),
             instance.java_mode,
-            instance.java_path
+            instance.java_path,
+            instance.log_format
         ],
     )
     .map_err(|error| format!("No se pudo actualizar la instancia: {error}"))?;
@@ -8881,12 +10056,17 @@ async fn import_instance(
                 .and_then(|value| value.get("path"))
                 .and_then(Value::as_str)
                 .map(str::to_string),
+            log_format: metadata
+                .get("logging")
+                .and_then(|value| value.get("format"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
         };
 
         let connection = database_connection(&app)?;
         connection
             .execute(
-                "INSERT OR REPLACE INTO instances (id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT OR REPLACE INTO instances (id, name, version, loader_name, loader_version, source_launcher, source_path, source_instance_name, java_mode, java_path, log_format) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 params![
                     record.id,
                     record.name,
@@ -8897,7 +10077,8 @@ async fn import_instance(
                     record.source_path,
                     record.source_instance_name,
                     record.java_mode,
-                    record.java_path
+                    record.java_path,
+                    record.log_format
                 ],
             )
             .map_err(|error| format!("No se pudo guardar la instancia importada: {error}"))?;
@@ -8969,7 +10150,8 @@ fn reset_instance_runtime(
     // Preserve mods, saves, resourcepacks, config and any external roots.
     ensure_instance_layout(&instance_root)?;
 
-    let partials = remove_partial_files(&minecraft_root)? + remove_partial_files(&instance_root)?;
+    let partials =
+        remove_partial_files(&minecraft_root, None)? + remove_partial_files(&instance_root, None)?;
     let killed_processes = kill_running_minecraft_processes();
 
     write_instance_state(
@@ -9097,6 +10279,132 @@ async fn prepare_instance_runtime(
     Ok((instance_root, instance))
 }
 
+/// Con `forced` manda la elección del usuario; si no, se sondean los mismos endpoints y
+/// mirrors de los que descarga la preparación (manifest, librerías y assets).
+async fn resolve_offline_launch(forced: Option<bool>) -> bool {
+    if let Some(forced) = forced {
+        return forced;
+    }
+    let Ok(client) = http_client_with_tuning(&active_network_tuning()) else {
+        return false;
+    };
+    let mut urls = download_routes::minecraft_manifest_urls();
+    urls.extend(download_routes::mirror_candidates_for_url(
+        "https://libraries.minecraft.net/",
+    ));
+    urls.extend(asset_mirror_bases());
+    urls.sort();
+    urls.dedup();
+    !network_reachable(&client, &urls).await
+}
+
+/// Evalúa el lanzamiento sin red: el plan sale de `launch-plan.json` y del runtime
+/// persistido, y sólo se comprueban archivos locales.
+fn offline_launch_report(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+) -> Result<(PathBuf, InstanceRecord, OfflineLaunchReport), String> {
+    let instance_root = launcher_root(app)?.join("instances").join(instance_id);
+    let instance = read_instance_record(app, instance_id)?;
+    let plan = match read_launch_plan(&instance_root) {
+        Ok(plan) => plan,
+        Err(error) => {
+            let mut report = OfflineLaunchReport::default();
+            report.add_error(format!(
+                "{error}. La instancia debe prepararse una vez con conexión"
+            ));
+            return Ok((instance_root, instance, report));
+        }
+    };
+
+    let mut report = verify_offline_launch(&plan);
+    if !launch_plan_matches_instance(&instance_root, &plan, &instance) {
+        report.add_error("launch-plan.json no corresponde a la versión o loader de la instancia");
+    }
+    if !launch_plan_matches_persisted_runtime(&instance_root, &plan) {
+        report.add_error("el runtime persistido no coincide con launch-plan.json");
+    }
+    if let Err(error) = validate_persisted_runtime_version(&instance_root, &plan) {
+        report.add_error(error);
+    }
+    Ok((instance_root, instance, report))
+}
+
+fn prepare_offline_instance_runtime(
+    app: &tauri::AppHandle,
+    instance_id: &str,
+) -> Result<(PathBuf, InstanceRecord), String> {
+    let (instance_root, instance, report) = offline_launch_report(app, instance_id)?;
+    write_instance_state(
+        &instance_root,
+        if report.ready {
+            "offline_launch_ready"
+        } else {
+            "offline_launch_blocked"
+        },
+        serde_json::to_value(&report).unwrap_or_default(),
+    );
+    if !report.ready {
+        return Err(report.summary());
+    }
+    Ok((instance_root, instance))
+}
+
+#[command]
+async fn list_managed_runtimes(app: tauri::AppHandle) -> Result<Vec<ManagedRuntimeInfo>, String> {
+    Ok(RuntimeManager::new(&app)?.list())
+}
+
+#[command]
+async fn check_runtime_updates(app: tauri::AppHandle) -> Result<Vec<RuntimeUpdateInfo>, String> {
+    RuntimeManager::new(&app)?.check_updates().await
+}
+
+#[command]
+async fn update_managed_runtime(
+    app: tauri::AppHandle,
+    args: ManagedRuntimeArgs,
+) -> Result<ManagedRuntimeInfo, String> {
+    let major = args
+        .major
+        .ok_or_else(|| "Debes indicar la versión mayor de Java a actualizar.".to_string())?;
+    RuntimeManager::new(&app)?.update(major).await
+}
+
+#[command]
+async fn uninstall_managed_runtime(
+    app: tauri::AppHandle,
+    args: ManagedRuntimeArgs,
+) -> Result<(), String> {
+    let runtime_id = args
+        .runtime_id
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| "Debes indicar el runtime a desinstalar.".to_string())?;
+    RuntimeManager::new(&app)?.uninstall(&runtime_id)
+}
+
+#[command]
+async fn verify_managed_runtimes(
+    app: tauri::AppHandle,
+) -> Result<Vec<ManagedRuntimeIntegrity>, String> {
+    let manager = RuntimeManager::new(&app)?;
+    tokio::task::spawn_blocking(move || manager.verify_runtimes())
+        .await
+        .map_err(|error| format!("La verificación de runtimes se interrumpió: {error}"))
+}
+
+#[command]
+async fn check_offline_launch(
+    app: tauri::AppHandle,
+    args: InstanceCommandArgs,
+) -> Result<OfflineLaunchReport, String> {
+    let instance_id = args.instance_id.unwrap_or_default().trim().to_string();
+    if instance_id.is_empty() {
+        return Err("No hay una instancia válida seleccionada para comprobar.".to_string());
+    }
+    offline_launch_report(&app, &instance_id).map(|(_, _, report)| report)
+}
+
 fn explain_validation_errors(errors: &[String]) -> String {
     let friendly = errors
         .iter()
@@ -9159,6 +10467,30 @@ async fn repair_instance(
 
     let mode = parse_repair_mode(args.repair_mode);
     let reinstall = matches!(mode, RepairMode::Completa);
+    if matches!(mode, RepairMode::Completa | RepairMode::VerificarIntegridad) {
+        // Sólo se revisa el runtime de esta instancia; si está alterado va a cuarentena y el
+        // bootstrap lo vuelve a instalar.
+        let plan_java =
+            read_launch_plan(&launcher_root(&app)?.join("instances").join(&instance_id))
+                .ok()
+                .map(|plan| PathBuf::from(plan.java_path));
+        let manager = RuntimeManager::new(&app)?;
+        let report = tokio::task::spawn_blocking(move || {
+            plan_java.and_then(|java_path| manager.verify_runtime_of(&java_path))
+        })
+        .await
+        .map_err(|error| format!("La verificación de runtimes se interrumpió: {error}"))?;
+        if let Some(report) = report {
+            if let Some(quarantined) = report.quarantined_to {
+                eprintln!(
+                    "[repair] runtime {} alterado ({} faltantes, {} modificados), movido a {quarantined}",
+                    report.runtime_id,
+                    report.missing.len(),
+                    report.modified.len()
+                );
+            }
+        }
+    }
     let (instance_root, instance) =
         prepare_instance_runtime(&app, &instance_id, reinstall, true, true).await?;
 
@@ -9264,48 +10596,705 @@ async fn preflight_instance(
         prepare_instance_runtime(&app, &instance_id, false, true, true).await?;
     write_instance_state(
         &instance_root,
-        "preflight",
-        serde_json::json!({"instance": instance_id, "killedZombies": killed_before_preflight}),
+        "preflight",
+        serde_json::json!({"instance": instance_id, "killedZombies": killed_before_preflight}),
+    );
+
+    let launch_plan = match read_launch_plan(&instance_root) {
+        Ok(plan) => plan,
+        Err(_) => {
+            let mut checks = HashMap::new();
+            checks.insert("runtime_preparado".to_string(), false);
+            return Ok(ValidationReport {
+                ok: false,
+                errors: vec![
+                    "La instancia todavía no está instalada. Iníciala o repárala manualmente para descargar runtime, loader y libraries."
+                        .to_string(),
+                ],
+                warnings: vec![
+                    "No se logró generar el plan de arranque automáticamente durante la verificación."
+                        .to_string(),
+                ],
+                checks,
+            });
+        }
+    };
+
+    let mut validation = validate_launch_plan(&instance_root, &launch_plan);
+    if !validation.ok && validation_requires_runtime_repair(&validation.errors) {
+        write_instance_state(
+            &instance_root,
+            "preflight_auto_repair",
+            serde_json::json!({
+                "instance": instance.id,
+                "reason": "validation_requires_runtime_repair",
+                "errors": validation.errors,
+            }),
+        );
+        bootstrap_instance_runtime(&app, &instance_root, &instance).await?;
+        build_launch_command(&app, &instance_root, &instance)?;
+        let rebuilt_plan = read_launch_plan(&instance_root)?;
+        validation = validate_launch_plan(&instance_root, &rebuilt_plan);
+    }
+
+    Ok(validation)
+}
+
+#[command]
+async fn list_minecraft_versions(
+    args: Option<ListMinecraftVersionsArgs>,
+) -> Result<MinecraftVersionList, String> {
+    let args = args.unwrap_or_default();
+    let kinds = parse_version_kinds(&args.types)?;
+    let tuning = active_network_tuning();
+    let loaded = minecraft_version_resolver(&tuning)?
+        .load_manifest(args.force_refresh)
+        .await?;
+    Ok(MinecraftVersionList {
+        latest_release: loaded.manifest.latest.release.clone(),
+        latest_snapshot: loaded.manifest.latest.snapshot.clone(),
+        versions: loaded.manifest.list(&kinds),
+        source: loaded.source,
+        fetched_at: loaded.fetched_at,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CancelDownloadArgs {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    instance_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PauseDownloadsArgs {
+    #[serde(default)]
+    instance_id: Option<String>,
+    paused: bool,
+}
+
+#[command]
+async fn list_downloads() -> Result<DownloadQueueSnapshot, String> {
+    Ok(Downloader::global().snapshot())
+}
+
+/// Cancela un trabajo por id o todos los de una instancia; devuelve cuántos se cancelaron.
+#[command]
+async fn cancel_download(args: CancelDownloadArgs) -> Result<usize, String> {
+    let downloader = Downloader::global();
+    match (args.id, args.instance_id.as_deref()) {
+        (Some(id), _) => {
+            if downloader.cancel(id) {
+                Ok(1)
+            } else {
+                Err(format!("No existe la descarga {id} en la cola."))
+            }
+        }
+        (None, Some(instance_id)) => Ok(downloader.cancel_group(instance_id)),
+        (None, None) => Err("Indica id o instanceId para cancelar descargas.".to_string()),
+    }
+}
+
+#[command]
+async fn pause_downloads(args: PauseDownloadsArgs) -> Result<DownloadQueueSnapshot, String> {
+    let downloader = Downloader::global();
+    downloader.set_paused(args.instance_id.as_deref(), args.paused);
+    Ok(downloader.snapshot())
+}
+
+#[command]
+async fn download_cache_stats() -> Result<DownloadCacheStats, String> {
+    Ok(global_download_cache(&launcher_global_download_cache_dir()).stats())
+}
+
+#[command]
+async fn prune_download_cache(
+    policy: Option<DownloadCachePrunePolicy>,
+) -> Result<DownloadCachePruneReport, String> {
+    let policy = policy.unwrap_or_default();
+    if policy.max_age_days.is_none() && policy.max_total_mb.is_none() {
+        return Err("Indica maxAgeDays o maxTotalMb para limpiar la caché.".to_string());
+    }
+    let cache = global_download_cache(&launcher_global_download_cache_dir());
+    tokio::task::spawn_blocking(move || cache.prune(&policy))
+        .await
+        .map_err(|error| format!("La limpieza de caché se interrumpió: {error}"))
+}
+
+#[command]
+async fn verify_download_cache() -> Result<DownloadCacheVerifyReport, String> {
+    let cache = global_download_cache(&launcher_global_download_cache_dir());
+    tokio::task::spawn_blocking(move || cache.verify())
+        .await
+        .map_err(|error| format!("La verificación de caché se interrumpió: {error}"))
+}
+
+#[command]
+async fn network_diagnostics() -> Result<Vec<MirrorHostReport>, String> {
+    let health = MirrorHealth::global();
+    health.flush();
+    Ok(health.report())
+}
+
+#[command]
+async fn start_microsoft_login() -> Result<MicrosoftDeviceCode, String> {
+    let tuning = active_network_tuning();
+    microsoft_auth_provider(&tuning)?
+        .request_device_code()
+        .await
+}
+
+/// Guarda la sesión en el almacén cifrado; los tokens nunca vuelven al frontend.
+#[command]
+async fn complete_microsoft_login(
+    app: tauri::AppHandle,
+    device_code: MicrosoftDeviceCode,
+) -> Result<AccountSummary, String> {
+    let tuning = active_network_tuning();
+    let session = microsoft_auth_provider(&tuning)?
+        .with_device_code(device_code)
+        .complete_device_login()
+        .await?;
+    let now = current_unix_secs();
+    save_account(
+        &app,
+        stored_account_from_session(session, None, now),
+        false,
+        now,
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddAccountArgs {
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    device_code: Option<MicrosoftDeviceCode>,
+    /// Cuenta Microsoft ya guardada por `complete_microsoft_login`.
+    #[serde(default)]
+    account_id: Option<String>,
+    #[serde(default)]
+    server_url: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    make_default: bool,
+}
+
+fn stored_account_from_session(
+    session: MicrosoftSession,
+    previous: Option<&StoredAccount>,
+    now: u64,
+) -> StoredAccount {
+    StoredAccount {
+        id: format!("{ACCOUNT_KIND_MSA}-{}", session.auth.uuid),
+        kind: ACCOUNT_KIND_MSA.to_string(),
+        username: session.auth.username,
+        uuid: session.auth.uuid,
+        access_token: Some(session.auth.access_token),
+        refresh_token: session
+            .refresh_token
+            .or_else(|| previous.and_then(|account| account.refresh_token.clone())),
+        expires_at: Some(session.expires_at),
+        is_default: previous.is_some_and(|account| account.is_default),
+        created_at: previous.map(|account| account.created_at).unwrap_or(now),
+        last_used_at: previous.and_then(|account| account.last_used_at),
+        auth_server: None,
+    }
+}
+
+fn stored_account_from_yggdrasil(
+    session: YggdrasilSession,
+    previous: Option<&StoredAccount>,
+    now: u64,
+) -> StoredAccount {
+    StoredAccount {
+        id: format!("{ACCOUNT_KIND_YGGDRASIL}-{}", session.auth.uuid),
+        kind: ACCOUNT_KIND_YGGDRASIL.to_string(),
+        username: session.auth.username,
+        uuid: session.auth.uuid,
+        access_token: Some(session.auth.access_token),
+        refresh_token: Some(session.client_token),
+        expires_at: None,
+        is_default: previous.is_some_and(|account| account.is_default),
+        created_at: previous.map(|account| account.created_at).unwrap_or(now),
+        last_used_at: previous.and_then(|account| account.last_used_at),
+        auth_server: Some(session.api_root),
+    }
+}
+
+fn yggdrasil_session_from_account(account: &StoredAccount) -> Result<YggdrasilSession, String> {
+    let api_root = account.auth_server.clone().ok_or_else(|| {
+        format!(
+            "La cuenta {} no tiene servidor Yggdrasil configurado.",
+            account.username
+        )
+    })?;
+    let client_token = account.refresh_token.clone().ok_or_else(|| {
+        format!(
+            "La cuenta {} no tiene clientToken; vuelve a iniciar sesión.",
+            account.username
+        )
+    })?;
+    Ok(YggdrasilSession {
+        auth: account.launch_auth(),
+        client_token,
+        api_root,
+    })
+}
+
+async fn authlib_injector_agent(
+    app: &tauri::AppHandle,
+    tuning: &NetworkTuning,
+    api_root: &str,
+) -> Result<AuthlibInjectorAgent, String> {
+    let client = http_client_with_tuning(tuning)?;
+    let cache_dir = launcher_root(app)?.join("authlib-injector");
+    let jar_path =
+        ensure_authlib_injector_jar(&client, AUTHLIB_INJECTOR_LATEST_URL, &cache_dir).await?;
+    // Los metadatos precargados evitan que el agente haga una petición extra al arrancar.
+    let prefetched_metadata = YggdrasilAuthProvider::new(client, api_root)
+        .fetch_metadata(api_root)
+        .await
+        .ok()
+        .map(|metadata| encode_prefetched_metadata(&metadata));
+    Ok(AuthlibInjectorAgent {
+        jar_path: jar_path.to_string_lossy().to_string(),
+        api_root: api_root.to_string(),
+        prefetched_metadata,
+    })
+}
+
+async fn refresh_stored_account(
+    app: &tauri::AppHandle,
+    account: StoredAccount,
+) -> Result<StoredAccount, String> {
+    if account.kind == ACCOUNT_KIND_YGGDRASIL {
+        let session = yggdrasil_session_from_account(&account)?;
+        let tuning = active_network_tuning();
+        let provider =
+            YggdrasilAuthProvider::new(http_client_with_tuning(&tuning)?, &session.api_root)
+                .with_session(session);
+        let refreshed = stored_account_from_yggdrasil(
+            provider.ensure_valid_session().await?,
+            Some(&account),
+            current_unix_secs(),
+        );
+        let vault = account_vault(app)?;
+        let conn = database_connection(app)?;
+        vault.upsert(&conn, &refreshed)?;
+        return Ok(refreshed);
+    }
+    if account.kind != ACCOUNT_KIND_MSA {
+        return Ok(account);
+    }
+    let refresh_token = account.refresh_token.clone().ok_or_else(|| {
+        format!(
+            "La cuenta {} no tiene refresh token; vuelve a iniciar sesión.",
+            account.username
+        )
+    })?;
+
+    let tuning = active_network_tuning();
+    let provider = microsoft_auth_provider(&tuning)?;
+    let (msa_token, rotated_refresh) = provider.refresh_msa_token(&refresh_token).await?;
+    let mut session = provider.login_with_msa_token(&msa_token).await?;
+    session.refresh_token = rotated_refresh.or(Some(refresh_token));
+
+    let refreshed = stored_account_from_session(session, Some(&account), current_unix_secs());
+    let vault = account_vault(app)?;
+    let conn = database_connection(app)?;
+    if refreshed.id != account.id {
+        vault.remove(&conn, &account.id)?;
+    }
+    vault.upsert(&conn, &refreshed)?;
+    Ok(refreshed)
+}
+
+/// Lee la cuenta (o la de por defecto) y renueva sus credenciales si hace falta.
+fn stored_account(
+    app: &tauri::AppHandle,
+    account_id: Option<&str>,
+) -> Result<StoredAccount, String> {
+    let vault = account_vault(app)?;
+    let conn = database_connection(app)?;
+    match account_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(account_id) => vault
+            .get(&conn, account_id)?
+            .ok_or_else(|| format!("La cuenta {account_id} no existe.")),
+        None => vault
+            .default_account(&conn)?
+            .ok_or_else(|| "No hay ninguna cuenta guardada.".to_string()),
+    }
+}
+
+async fn fresh_stored_account(
+    app: &tauri::AppHandle,
+    account_id: Option<&str>,
+) -> Result<StoredAccount, String> {
+    let account = stored_account(app, account_id)?;
+    if account.kind == ACCOUNT_KIND_YGGDRASIL || account.needs_refresh(current_unix_secs()) {
+        refresh_stored_account(app, account).await
+    } else {
+        Ok(account)
+    }
+}
+
+/// Sin red se usa la sesión guardada tal cual: no se refresca ni se prepara authlib-injector.
+async fn resolve_account_launch_auth(
+    app: &tauri::AppHandle,
+    account_id: &str,
+    offline: bool,
+) -> Result<(LaunchAuth, Option<AuthlibInjectorAgent>), String> {
+    let account = if offline {
+        stored_account(app, Some(account_id))?
+    } else {
+        fresh_stored_account(app, Some(account_id)).await?
+    };
+    let agent = match account.auth_server.as_deref() {
+        Some(api_root) if !offline && account.kind == ACCOUNT_KIND_YGGDRASIL => {
+            let tuning = active_network_tuning();
+            Some(authlib_injector_agent(app, &tuning, api_root).await?)
+        }
+        _ => None,
+    };
+    let vault = account_vault(app)?;
+    let conn = database_connection(app)?;
+    vault.touch(&conn, &account.id, current_unix_secs())?;
+    Ok((account.launch_auth(), agent))
+}
+
+#[command]
+async fn list_accounts(app: tauri::AppHandle) -> Result<Vec<AccountSummary>, String> {
+    let vault = account_vault(&app)?;
+    let conn = database_connection(&app)?;
+    let now = current_unix_secs();
+    Ok(vault
+        .list(&conn)?
+        .iter()
+        .map(|account| account.summary(now))
+        .collect())
+}
+
+#[command]
+async fn add_account(
+    app: tauri::AppHandle,
+    args: AddAccountArgs,
+) -> Result<AccountSummary, String> {
+    let now = current_unix_secs();
+    let kind = args
+        .kind
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(if args.device_code.is_some() || args.account_id.is_some() {
+            ACCOUNT_KIND_MSA
+        } else {
+            ACCOUNT_KIND_OFFLINE
+        })
+        .to_ascii_lowercase();
+
+    let account = if kind == ACCOUNT_KIND_OFFLINE {
+        let username = args
+            .username
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                "Debes indicar un nombre de usuario para la cuenta offline.".to_string()
+            })?
+            .to_string();
+        StoredAccount {
+            id: format!("{ACCOUNT_KIND_OFFLINE}-{}", username.to_ascii_lowercase()),
+            kind: ACCOUNT_KIND_OFFLINE.to_string(),
+            uuid: default_offline_uuid(&username),
+            username,
+            access_token: None,
+            refresh_token: None,
+            expires_at: None,
+            is_default: false,
+            created_at: now,
+            last_used_at: None,
+            auth_server: None,
+        }
+    } else if kind == ACCOUNT_KIND_YGGDRASIL {
+        let server_url = args
+            .server_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| "Debes indicar la URL del servidor de autenticación.".to_string())?;
+        let username = args
+            .username
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| "Debes indicar el usuario o correo de la cuenta.".to_string())?;
+        let password = args.password.as_deref().unwrap_or_default();
+        let tuning = active_network_tuning();
+        let session = YggdrasilAuthProvider::new(http_client_with_tuning(&tuning)?, server_url)
+            .authenticate(username, password, None)
+            .await?;
+        stored_account_from_yggdrasil(session, None, now)
+    } else if kind == ACCOUNT_KIND_MSA {
+        match (args.account_id, args.device_code) {
+            (Some(account_id), _) => stored_account(&app, Some(&account_id))?,
+            (None, Some(device_code)) => {
+                let tuning = active_network_tuning();
+                let session = microsoft_auth_provider(&tuning)?
+                    .with_device_code(device_code)
+                    .complete_device_login()
+                    .await?;
+                stored_account_from_session(session, None, now)
+            }
+            (None, None) => {
+                return Err(
+                    "Para añadir una cuenta Microsoft se requiere deviceCode o accountId."
+                        .to_string(),
+                )
+            }
+        }
+    } else {
+        return Err(format!("Tipo de cuenta no soportado: {kind}"));
+    };
+
+    save_account(&app, account, args.make_default, now)
+}
+
+/// Conserva la fecha de alta y la marca de cuenta por defecto si la cuenta ya existía.
+fn save_account(
+    app: &tauri::AppHandle,
+    mut account: StoredAccount,
+    make_default: bool,
+    now: u64,
+) -> Result<AccountSummary, String> {
+    let vault = account_vault(app)?;
+    let conn = database_connection(app)?;
+    // Una fila que ya no se descifra se sobrescribe: volver a iniciar sesión es la forma de
+    // repararla.
+    if let Some(previous) = vault.get(&conn, &account.id).ok().flatten() {
+        account.created_at = previous.created_at;
+        account.is_default = previous.is_default;
+        account.last_used_at = previous.last_used_at;
+    }
+    account.is_default |= make_default;
+    vault.upsert(&conn, &account)?;
+    let stored = vault
+        .get(&conn, &account.id)?
+        .ok_or_else(|| "No se pudo releer la cuenta guardada.".to_string())?;
+    Ok(stored.summary(now))
+}
+
+#[command]
+async fn remove_account(app: tauri::AppHandle, account_id: String) -> Result<bool, String> {
+    let vault = account_vault(&app)?;
+    let conn = database_connection(&app)?;
+    vault.remove(&conn, account_id.trim())
+}
+
+#[command]
+async fn set_default_account(app: tauri::AppHandle, account_id: String) -> Result<(), String> {
+    let vault = account_vault(&app)?;
+    let conn = database_connection(&app)?;
+    vault.set_default(&conn, account_id.trim())
+}
+
+#[command]
+async fn refresh_account(
+    app: tauri::AppHandle,
+    account_id: String,
+) -> Result<AccountSummary, String> {
+    let account = {
+        let vault = account_vault(&app)?;
+        let conn = database_connection(&app)?;
+        vault
+            .get(&conn, account_id.trim())?
+            .ok_or_else(|| format!("La cuenta {account_id} no existe."))?
+    };
+    let refreshed = refresh_stored_account(&app, account).await?;
+    Ok(refreshed.summary(current_unix_secs()))
+}
+
+async fn skin_service_for(
+    app: &tauri::AppHandle,
+    account_id: Option<&str>,
+) -> Result<SkinService, String> {
+    let account = fresh_stored_account(app, account_id).await?;
+    if account.kind != ACCOUNT_KIND_MSA {
+        return Err(
+            "La gestión de skins y capas requiere una cuenta Microsoft con sesión iniciada."
+                .to_string(),
+        );
+    }
+    let access_token = account
+        .access_token
+        .ok_or_else(|| "La cuenta no tiene token de Minecraft; refréscala.".to_string())?;
+    let tuning = active_network_tuning();
+    Ok(SkinService::new(
+        http_client_with_tuning(&tuning)?,
+        MINECRAFT_SERVICES_BASE,
+        access_token,
+        skin_texture_cache_dir(app)?,
+    ))
+}
+
+fn skin_texture_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(launcher_persistent_cache_root(app)?.join("textures"))
+}
+
+/// Sin red devuelve el último perfil guardado con las texturas que haya en disco.
+#[command]
+async fn get_skin_profile(
+    app: tauri::AppHandle,
+    args: SkinCommandArgs,
+) -> Result<SkinProfile, String> {
+    let account = stored_account(&app, args.account_id.as_deref())?;
+    let fetched = match skin_service_for(&app, Some(&account.id)).await {
+        Ok(service) => service.profile().await,
+        Err(error) => Err(error),
+    };
+    match fetched {
+        Err(error) if resolve_offline_launch(None).await => {
+            cached_skin_profile(&skin_texture_cache_dir(&app)?, &account.uuid).ok_or(error)
+        }
+        result => result,
+    }
+}
+
+#[command]
+async fn upload_skin(app: tauri::AppHandle, args: SkinCommandArgs) -> Result<SkinProfile, String> {
+    let variant = SkinVariant::parse(args.variant.as_deref())?;
+    let file_path = args
+        .file_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "Debes seleccionar un archivo PNG de skin.".to_string())?;
+    let png = fs::read(file_path)
+        .map_err(|error| format!("No se pudo leer la skin {file_path}: {error}"))?;
+    validate_skin_png(&png)?;
+    skin_service_for(&app, args.account_id.as_deref())
+        .await?
+        .upload_skin(png, variant)
+        .await
+}
+
+#[command]
+async fn set_skin_from_url(
+    app: tauri::AppHandle,
+    args: SkinCommandArgs,
+) -> Result<SkinProfile, String> {
+    let variant = SkinVariant::parse(args.variant.as_deref())?;
+    let url = args
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "Debes indicar la URL de la skin.".to_string())?;
+    skin_service_for(&app, args.account_id.as_deref())
+        .await?
+        .set_skin_url(url, variant)
+        .await
+}
+
+#[command]
+async fn set_active_cape(
+    app: tauri::AppHandle,
+    args: SkinCommandArgs,
+) -> Result<SkinProfile, String> {
+    skin_service_for(&app, args.account_id.as_deref())
+        .await?
+        .set_active_cape(args.cape_id.as_deref())
+        .await
+}
+
+#[command]
+async fn reset_skin(app: tauri::AppHandle, args: SkinCommandArgs) -> Result<SkinProfile, String> {
+    skin_service_for(&app, args.account_id.as_deref())
+        .await?
+        .reset_skin()
+        .await
+}
+
+/// UUID offline estable de `username`, para que el frontend no genere uno propio.
+#[command]
+async fn offline_account_uuid(username: String) -> Result<String, String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err("El nombre offline no puede estar vacío.".to_string());
+    }
+    Ok(default_offline_uuid(username))
+}
+
+#[command]
+async fn migrate_offline_player_data(
+    app: tauri::AppHandle,
+    args: InstanceCommandArgs,
+) -> Result<PlayerDataMigrationReport, String> {
+    let instance_id = args.instance_id.unwrap_or_default().trim().to_string();
+    if instance_id.is_empty() {
+        return Err("No hay una instancia válida seleccionada para migrar.".to_string());
+    }
+    let instance_root = launcher_root(&app)?.join("instances").join(&instance_id);
+    if !instance_root.exists() {
+        return Err(format!("La instancia {instance_id} no existe."));
+    }
+    let previous_plan = read_launch_plan(&instance_root).ok();
+    let username = args
+        .username
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            previous_plan
+                .as_ref()
+                .map(|plan| plan.auth.username.clone())
+        })
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| "Debes indicar el nombre del jugador offline a migrar.".to_string())?;
+
+    let target_uuid = default_offline_uuid(&username);
+    let legacy_prefix = legacy_offline_uuid_prefix(&username);
+    let previous_uuid = previous_plan
+        .filter(|plan| plan.auth.username == username)
+        .and_then(|plan| normalize_uuid(&plan.auth.uuid));
+    // UUID aleatorio que la cuenta offline guardaba antes de derivarlo del nombre.
+    let account_uuid = args.uuid.as_deref().and_then(normalize_uuid);
+
+    let game_dir = instance_game_dir(&instance_root);
+    let (renamed, skipped) = with_instance_lock(&instance_id, || {
+        migrate_player_data(&game_dir, &target_uuid, |uuid| {
+            uuid.starts_with(&legacy_prefix)
+                || previous_uuid.as_deref() == Some(uuid)
+                || account_uuid.as_deref() == Some(uuid)
+        })
+    })?;
+
+    write_instance_state(
+        &instance_root,
+        "offline_player_data_migrated",
+        serde_json::json!({
+            "instance": instance_id,
+            "username": username,
+            "uuid": target_uuid,
+            "renamed": renamed.len(),
+            "skipped": skipped.len()
+        }),
     );
 
-    let launch_plan = match read_launch_plan(&instance_root) {
-        Ok(plan) => plan,
-        Err(_) => {
-            let mut checks = HashMap::new();
-            checks.insert("runtime_preparado".to_string(), false);
-            return Ok(ValidationReport {
-                ok: false,
-                errors: vec![
-                    "La instancia todavía no está instalada. Iníciala o repárala manualmente para descargar runtime, loader y libraries."
-                        .to_string(),
-                ],
-                warnings: vec![
-                    "No se logró generar el plan de arranque automáticamente durante la verificación."
-                        .to_string(),
-                ],
-                checks,
-            });
-        }
-    };
-
-    let mut validation = validate_launch_plan(&instance_root, &launch_plan);
-    if !validation.ok && validation_requires_runtime_repair(&validation.errors) {
-        write_instance_state(
-            &instance_root,
-            "preflight_auto_repair",
-            serde_json::json!({
-                "instance": instance.id,
-                "reason": "validation_requires_runtime_repair",
-                "errors": validation.errors,
-            }),
-        );
-        bootstrap_instance_runtime(&app, &instance_root, &instance).await?;
-        build_launch_command(&app, &instance_root, &instance)?;
-        let rebuilt_plan = read_launch_plan(&instance_root)?;
-        validation = validate_launch_plan(&instance_root, &rebuilt_plan);
-    }
-
-    Ok(validation)
+    Ok(PlayerDataMigrationReport {
+        username,
+        uuid: target_uuid,
+        renamed,
+        skipped,
+    })
 }
 
 #[command]
@@ -9318,65 +11307,132 @@ async fn launch_instance(
         return Err("No hay una instancia válida seleccionada para iniciar.".to_string());
     }
 
+    let offline = resolve_offline_launch(args.offline).await;
     let mut launch_repair_attempted = false;
     let mut safe_mode_attempted = false;
     let mut safe_mode_mods_result: Option<bool> = None;
     let mut version_purge_attempted = false;
     let mut previous_fingerprint: Option<String> = None;
 
-    let (mut instance_root, mut instance) =
-        prepare_instance_runtime(&app, &instance_id, false, true, true).await?;
+    let requested_account_id = args
+        .account_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            // Sin credenciales explícitas, se usa la cuenta por defecto del almacén.
+            if args.username.is_some() || args.access_token.is_some() {
+                return None;
+            }
+            let vault = account_vault(&app).ok()?;
+            let conn = database_connection(&app).ok()?;
+            vault
+                .default_account(&conn)
+                .ok()
+                .flatten()
+                .map(|account| account.id)
+        });
+    let (account_auth, authlib_agent) = match requested_account_id {
+        Some(account_id) => {
+            let (auth, agent) = resolve_account_launch_auth(&app, &account_id, offline).await?;
+            (Some(auth), agent)
+        }
+        None => (None, None),
+    };
+
+    let (mut instance_root, mut instance) = if offline {
+        prepare_offline_instance_runtime(&app, &instance_id)?
+    } else {
+        prepare_instance_runtime(&app, &instance_id, false, true, true).await?
+    };
 
     'launch_attempt: loop {
         let mut launch_plan = read_launch_plan(&instance_root)?;
-
-        let auth_username = args
-            .username
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .unwrap_or(&launch_plan.auth.username)
-            .to_string();
-        let auth_uuid = args
-            .uuid
-            .as_deref()
-            .and_then(normalize_uuid)
-            .unwrap_or_else(|| {
-                normalize_uuid(&launch_plan.auth.uuid)
+        if let Some(agent) = authlib_agent.as_ref() {
+            agent.apply_to_jvm_args(&mut launch_plan.java_args);
+        }
+        if let Some(auth) = account_auth.clone() {
+            apply_auth_to_launch_plan(&mut launch_plan, auth);
+        } else {
+            let auth_username = args
+                .username
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&launch_plan.auth.username)
+                .to_string();
+            let auth_user_type = args
+                .user_type
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&launch_plan.auth.user_type)
+                .to_string();
+            let is_offline = auth_user_type.is_empty() || auth_user_type == "offline";
+            // En offline el UUID se deriva siempre del nombre. El que envía la cuenta (antes
+            // aleatorio) sólo sirve para migrar los datos guardados con él.
+            let auth_uuid = if is_offline {
+                default_offline_uuid(&auth_username)
+            } else {
+                args.uuid
+                    .as_deref()
+                    .and_then(normalize_uuid)
+                    .or_else(|| normalize_uuid(&launch_plan.auth.uuid))
                     .unwrap_or_else(|| default_offline_uuid(&auth_username))
-            });
-        let auth_access_token = args
-            .access_token
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .unwrap_or(&launch_plan.auth.access_token)
-            .to_string();
-        let auth_user_type = args
-            .user_type
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .unwrap_or(&launch_plan.auth.user_type)
-            .to_string();
-
-        apply_auth_to_launch_plan(
-            &mut launch_plan,
-            LaunchAuth {
-                username: auth_username,
-                uuid: auth_uuid,
-                access_token: if auth_access_token.is_empty() {
-                    "0".to_string()
-                } else {
-                    auth_access_token
-                },
-                user_type: if auth_user_type.is_empty() {
-                    "offline".to_string()
-                } else {
-                    auth_user_type
+            };
+            let legacy_account_uuid = args
+                .uuid
+                .as_deref()
+                .and_then(normalize_uuid)
+                .filter(|uuid| is_offline && uuid != &auth_uuid);
+            if let Some(legacy_uuid) = legacy_account_uuid {
+                let game_dir = instance_game_dir(&instance_root);
+                match with_instance_lock(&instance_id, || {
+                    migrate_player_data(&game_dir, &auth_uuid, |uuid| uuid == legacy_uuid)
+                }) {
+                    Ok((renamed, _)) if !renamed.is_empty() => write_instance_state(
+                        &instance_root,
+                        "offline_player_data_migrated",
+                        serde_json::json!({
+                            "instance": instance_id,
+                            "username": auth_username,
+                            "uuid": auth_uuid,
+                            "renamed": renamed.len()
+                        }),
+                    ),
+                    Ok(_) => {}
+                    Err(error) => {
+                        eprintln!("[launch] No se pudieron migrar datos offline: {error}")
+                    }
+                }
+            }
+            let auth_access_token = args
+                .access_token
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or(&launch_plan.auth.access_token)
+                .to_string();
+
+            apply_auth_to_launch_plan(
+                &mut launch_plan,
+                LaunchAuth {
+                    username: auth_username,
+                    uuid: auth_uuid,
+                    access_token: if auth_access_token.is_empty() {
+                        "0".to_string()
+                    } else {
+                        auth_access_token
+                    },
+                    user_type: if auth_user_type.is_empty() {
+                        "offline".to_string()
+                    } else {
+                        auth_user_type
+                    },
                 },
-            },
-        );
+            );
+        }
 
         let current_version =
             extract_or_fallback_arg(&launch_plan.game_args, "--version", &instance.version);
@@ -9389,7 +11445,7 @@ async fn launch_instance(
 
         let validation = validate_launch_plan(&instance_root, &launch_plan);
         if !validation.ok {
-            if !launch_repair_attempted {
+            if !offline && !launch_repair_attempted {
                 launch_repair_attempted = true;
                 write_instance_state(
                     &instance_root,
@@ -9428,7 +11484,11 @@ async fn launch_instance(
         write_instance_state(
             &instance_root,
             "launching",
-            serde_json::json!({"checks": validation.checks, "warnings": validation.warnings}),
+            serde_json::json!({
+                "checks": validation.checks,
+                "warnings": validation.warnings,
+                "offline": offline
+            }),
         );
 
         let logs_dir = instance_root.join("logs");
@@ -9468,8 +11528,7 @@ async fn launch_instance(
                 .collect::<Vec<_>>()
                 .join(" "),
             shell_escape(&launch_plan.main_class),
-            launch_plan
-                .game_args
+            redact_args(&launch_plan.game_args)
                 .iter()
                 .map(|arg| shell_escape(arg))
                 .collect::<Vec<_>>()
@@ -9556,7 +11615,7 @@ async fn launch_instance(
                     instance_root.join("logs").to_string_lossy().to_string(),
                 ];
 
-                if is_loader_failure && !launch_repair_attempted {
+                if is_loader_failure && !offline && !launch_repair_attempted {
                     launch_repair_attempted = true;
                     write_instance_state(
                         &instance_root,
@@ -9587,6 +11646,7 @@ async fn launch_instance(
                 }
 
                 if is_loader_failure
+                    && !offline
                     && safe_mode_mods_result == Some(false)
                     && !version_purge_attempted
                 {
@@ -9827,7 +11887,7 @@ async fn curseforge_scan_fingerprints(mods_dir: String) -> Result<FingerprintSca
 
     let api_key = resolve_curseforge_api_key(None)?;
     let headers = curseforge_headers(&api_key)?;
-    let client = reqwest::Client::new();
+    let client = http_client_with_tuning(&active_network_tuning())?;
     let response = client
         .post("https://api.curseforge.com/v1/fingerprints")
         .headers(headers.clone())
@@ -9899,7 +11959,7 @@ async fn curseforge_resolve_download(
 ) -> Result<CurseforgeDownloadResolution, String> {
     let api_key = resolve_curseforge_api_key(None)?;
     let headers = curseforge_headers(&api_key)?;
-    let client = reqwest::Client::new();
+    let client = http_client_with_tuning(&active_network_tuning())?;
 
     let file_response = client
         .get(format!(
@@ -9948,16 +12008,28 @@ async fn curseforge_resolve_download(
     })
 }
 
+/// Hashes y tamaño que publican Modrinth/CurseForge para el archivo de un mod.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpectedModFile {
+    #[serde(default)]
+    sha1: Option<String>,
+    #[serde(default)]
+    md5: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+}
+
 #[command]
 async fn install_mod_file(
     app: tauri::AppHandle,
     instance_id: String,
     url: String,
     file_name: String,
-    expected_sha1: Option<String>,
-    expected_md5: Option<String>,
+    expected: Option<ExpectedModFile>,
     mod_loader_hint: Option<String>,
 ) -> Result<String, String> {
+    let expected = expected.unwrap_or_default();
     let id = instance_id.trim();
     if id.is_empty() {
         return Err("instance_id es requerido".to_string());
@@ -9990,7 +12062,7 @@ async fn install_mod_file(
     let loader_name = instance.loader_name.as_deref().unwrap_or("vanilla");
     crate::core::mods::validate_mod_loader_compatibility(loader_name, mod_loader_hint.as_deref())?;
 
-    let tuning = resolve_network_tuning(Some(&load_config(app.clone()).await?));
+    let tuning = active_network_tuning();
     write_instance_state(
         &instance_root,
         "preflight",
@@ -9998,25 +12070,19 @@ async fn install_mod_file(
             "step": "mod_download",
             "url": url.trim(),
             "endpoint": download_routes::endpoint_label(url.trim()),
-            "expectedSha1": expected_sha1,
-            "expectedMd5": expected_md5
+            "expectedSha1": expected.sha1,
+            "expectedMd5": expected.md5
         }),
     );
-    let integrity = download_to(
-        url.trim(),
-        &target,
-        &tuning,
-        expected_sha1.as_deref(),
-        expected_md5.as_deref(),
-    )
-    .await
-    .map_err(|error| {
-        format!(
-            "No se pudo instalar el mod {} desde {}: {error}",
-            effective_name,
-            url.trim()
-        )
-    })?;
+    let integrity = download_to(url.trim(), &target, &tuning, &expected)
+        .await
+        .map_err(|error| {
+            format!(
+                "No se pudo instalar el mod {} desde {}: {error}",
+                effective_name,
+                url.trim()
+            )
+        })?;
 
     if !is_valid_zip_stream(&target) {
         let _ = fs::remove_file(&target);
@@ -10069,7 +12135,7 @@ async fn curseforge_v1_get(
 
     let api_key = resolve_curseforge_api_key(api_key.as_deref())?;
     let headers = curseforge_headers(&api_key)?;
-    let client = reqwest::Client::new();
+    let client = http_client_with_tuning(&active_network_tuning())?;
     let url = format!("https://api.curseforge.com/v1{normalized}");
     let request = client.get(url).headers(headers);
     let request = if let Some(query_params) = query {
@@ -10273,6 +12339,7 @@ pub fn run() {
             detect_installed_mods,
             list_java_runtimes,
             resolve_java_for_minecraft,
+            rescan_java_runtimes,
             create_instance,
             update_instance,
             export_instance,
@@ -10283,6 +12350,34 @@ pub fn run() {
             preflight_instance,
             kill_zombie_java_processes,
             launch_instance,
+            start_microsoft_login,
+            complete_microsoft_login,
+            list_minecraft_versions,
+            list_downloads,
+            cancel_download,
+            pause_downloads,
+            download_cache_stats,
+            prune_download_cache,
+            verify_download_cache,
+            network_diagnostics,
+            check_offline_launch,
+            list_managed_runtimes,
+            check_runtime_updates,
+            update_managed_runtime,
+            uninstall_managed_runtime,
+            verify_managed_runtimes,
+            list_accounts,
+            add_account,
+            remove_account,
+            set_default_account,
+            refresh_account,
+            migrate_offline_player_data,
+            offline_account_uuid,
+            get_skin_profile,
+            upload_skin,
+            set_skin_from_url,
+            set_active_cape,
+            reset_skin,
             read_instance_runtime_logs,
             manage_modpack,
             curseforge_scan_fingerprints,
@@ -10294,6 +12389,10 @@ pub fn run() {
             if let Err(error) = init_database(app.handle()) {
                 eprintln!("Error al inicializar la base de datos: {error}");
             }
+            migrate_persisted_launch_secrets(app.handle());
+            apply_persisted_runtime_settings(app.handle());
+            attach_mirror_health_store(app.handle());
+            attach_http_metadata_cache(app.handle());
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -10527,6 +12626,7 @@ mod tests {
             resolved_java_major: 17,
             loader: "fabric".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "Steve".to_string(),
                 uuid: "uuid".to_string(),
@@ -10542,6 +12642,75 @@ mod tests {
         fs::remove_dir_all(base).expect("cleanup");
     }
 
+    #[test]
+    fn read_launch_plan_scrubs_persisted_access_tokens() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let instance_root =
+            std::env::temp_dir().join(format!("frutistudio-test-plan-secrets-{unique}"));
+        fs::create_dir_all(&instance_root).expect("instance root");
+
+        let plan = LaunchPlan {
+            java_path: "java".to_string(),
+            java_args: vec!["-Xmx2G".to_string()],
+            game_args: vec![
+                "--username".to_string(),
+                "Steve".to_string(),
+                "--accessToken".to_string(),
+                "legacy-secret-token".to_string(),
+            ],
+            main_class: "net.minecraft.client.main.Main".to_string(),
+            classpath_entries: Vec::new(),
+            classpath_separator: ":".to_string(),
+            game_dir: instance_root.to_string_lossy().to_string(),
+            assets_dir: String::new(),
+            libraries_dir: String::new(),
+            natives_dir: String::new(),
+            version_json: String::new(),
+            asset_index: "19".to_string(),
+            required_java_major: 17,
+            resolved_java_major: 17,
+            loader: "vanilla".to_string(),
+            loader_profile_resolved: true,
+            legacy_arguments: false,
+            auth: LaunchAuth {
+                username: "Steve".to_string(),
+                uuid: "uuid".to_string(),
+                access_token: "legacy-secret-token".to_string(),
+                user_type: "msa".to_string(),
+            },
+            env: HashMap::new(),
+        };
+        fs::write(
+            instance_root.join("launch-plan.json"),
+            serde_json::to_string_pretty(&plan).expect("plan json"),
+        )
+        .expect("legacy plan");
+        fs::write(
+            instance_root.join("launch-command.txt"),
+            "'java' '--accessToken' 'legacy-secret-token'\n",
+        )
+        .expect("legacy command");
+
+        let loaded = read_launch_plan(&instance_root).expect("read plan");
+        assert_eq!(loaded.auth.access_token, "legacy-secret-token");
+
+        let persisted_plan =
+            fs::read_to_string(instance_root.join("launch-plan.json")).expect("plan");
+        let persisted_command =
+            fs::read_to_string(instance_root.join("launch-command.txt")).expect("command");
+        assert!(!persisted_plan.contains("legacy-secret-token"));
+        assert!(!persisted_command.contains("legacy-secret-token"));
+
+        let reloaded = read_launch_plan(&instance_root).expect("reread plan");
+        assert_eq!(reloaded.auth.access_token, "0");
+        assert_eq!(reloaded.game_args[3], "0");
+
+        fs::remove_dir_all(instance_root).expect("cleanup");
+    }
+
     #[test]
     fn validate_launch_plan_accepts_existing_empty_natives_dir() {
         let unique = SystemTime::now()
@@ -10596,6 +12765,7 @@ mod tests {
             resolved_java_major: 17,
             loader: "vanilla".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "Steve".to_string(),
                 uuid: "uuid".to_string(),
@@ -10868,6 +13038,63 @@ mod tests {
         assert!(maven_path("too:many:segments:for:one:artifact").is_none());
     }
 
+    #[test]
+    fn download_with_progress_replaces_existing_files_that_fail_sha256_or_size() {
+        use crate::core::mock_http::{spawn_mock_server, MockResponse};
+        use sha2::{Digest, Sha256};
+
+        let base = spawn_mock_server(|_| MockResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: b"contenido correcto".to_vec(),
+        });
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("frutistudio-test-existing-{unique}"));
+        fs::create_dir_all(&root).expect("root");
+        let target = root.join("runtime.tar.gz");
+        let urls = vec![format!("{base}/runtime.tar.gz")];
+        let sha256 = format!("{:x}", Sha256::digest(b"contenido correcto"));
+        let tuning = NetworkTuning::default();
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+
+        fs::write(&target, b"contenido corrupto").expect("corrupt");
+        rt.block_on(download_with_progress(
+            &urls,
+            &target,
+            ExpectedDigest {
+                sha256: Some(&sha256),
+                ..ExpectedDigest::default()
+            },
+            false,
+            &tuning,
+            "test",
+            None,
+        ))
+        .expect("sha256");
+        assert_eq!(fs::read(&target).expect("target"), b"contenido correcto");
+
+        fs::write(&target, b"contenido").expect("truncated");
+        rt.block_on(download_with_progress(
+            &urls,
+            &target,
+            ExpectedDigest {
+                size: Some(b"contenido correcto".len() as u64),
+                ..ExpectedDigest::default()
+            },
+            false,
+            &tuning,
+            "test",
+            None,
+        ))
+        .expect("size");
+        assert_eq!(fs::read(&target).expect("target"), b"contenido correcto");
+
+        fs::remove_dir_all(&root).expect("cleanup");
+    }
+
     #[test]
     fn resolve_library_artifacts_uses_neoforge_maven_for_neoforged_coords_without_url() {
         let version_json = serde_json::json!({
@@ -11021,6 +13248,7 @@ mod tests {
             resolved_java_major: 17,
             loader: "fabric".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "dev".to_string(),
                 uuid: "00000000000000000000000000000000".to_string(),
@@ -11057,6 +13285,7 @@ mod tests {
             resolved_java_major: 17,
             loader: "fabric".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "dev".to_string(),
                 uuid: "00000000000000000000000000000000".to_string(),
@@ -11604,6 +13833,7 @@ mandatory=true
             source_instance_name: None,
             java_mode: None,
             java_path: None,
+            log_format: None,
         };
 
         let instance_root = std::env::temp_dir().join("frutistudio-launch-plan-matches");
@@ -11628,6 +13858,7 @@ mandatory=true
             resolved_java_major: 17,
             loader: "fabric".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "Player".to_string(),
                 uuid: "uuid".to_string(),
@@ -11752,6 +13983,7 @@ mandatory=true
             resolved_java_major: 17,
             loader: "vanilla".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "Player".to_string(),
                 uuid: "uuid".to_string(),
@@ -11823,6 +14055,7 @@ mandatory=true
             resolved_java_major: 17,
             loader: "fabric".to_string(),
             loader_profile_resolved: true,
+            legacy_arguments: false,
             auth: LaunchAuth {
                 username: "Player".to_string(),
                 uuid: "uuid".to_string(),
@@ -11889,4 +14122,197 @@ mandatory=true
 
         fs::remove_dir_all(root).expect("cleanup");
     }
+
+    const B1_7_3_VERSION_JSON: &str = include_str!("../tests/fixtures/versions/b1.7.3.json");
+    const V1_6_4_VERSION_JSON: &str = include_str!("../tests/fixtures/versions/1.6.4.json");
+    const V1_7_10_VERSION_JSON: &str = include_str!("../tests/fixtures/versions/1.7.10.json");
+
+    fn legacy_fixture_plan(raw: &str, game_dir: &Path, layout: AssetLayout) -> LaunchPlan {
+        let version_json: Value = serde_json::from_str(raw).expect("fixture version json");
+        let version_name = version_json["id"].as_str().expect("id").to_string();
+        let asset_index = version_json["assetIndex"]["id"]
+            .as_str()
+            .expect("asset index")
+            .to_string();
+        let (template, legacy_arguments) = game_argument_template(&version_json);
+        let game_assets = game_assets_dir(game_dir, &asset_index, layout)
+            .to_string_lossy()
+            .to_string();
+        let uuid = default_offline_uuid("Player");
+        let replacements = HashMap::from([
+            ("auth_player_name", "Player".to_string()),
+            ("auth_session", legacy_auth_session("0", &uuid)),
+            ("auth_uuid", uuid.clone()),
+            ("auth_access_token", "0".to_string()),
+            ("user_type", "offline".to_string()),
+            ("user_properties", "{}".to_string()),
+            ("version_name", version_name.clone()),
+            ("game_directory", game_dir.to_string_lossy().to_string()),
+            (
+                "assets_root",
+                game_dir.join("assets").to_string_lossy().to_string(),
+            ),
+            ("assets_index_name", asset_index.clone()),
+            ("game_assets", game_assets.clone()),
+        ]);
+
+        let mut game_args = expand_game_arguments(&template, &replacements);
+        apply_required_game_args(
+            &mut game_args,
+            [
+                ("--username", "Player".to_string()),
+                ("--version", version_name),
+                ("--assetsDir", game_assets.clone()),
+                ("--uuid", uuid.clone()),
+                ("--versionType", "Interface".to_string()),
+            ],
+            legacy_arguments,
+        );
+        sanitize_game_args(&mut game_args);
+
+        LaunchPlan {
+            java_path: "java".to_string(),
+            java_args: vec!["-Xmx2G".to_string()],
+            game_args,
+            main_class: version_json["mainClass"]
+                .as_str()
+                .expect("main")
+                .to_string(),
+            classpath_entries: vec![],
+            classpath_separator: ":".to_string(),
+            game_dir: game_dir.to_string_lossy().to_string(),
+            assets_dir: game_dir.join("assets").to_string_lossy().to_string(),
+            libraries_dir: "/tmp/libraries".to_string(),
+            natives_dir: game_dir.join("natives").to_string_lossy().to_string(),
+            version_json: String::new(),
+            asset_index,
+            required_java_major: 8,
+            resolved_java_major: 8,
+            loader: "vanilla".to_string(),
+            loader_profile_resolved: true,
+            legacy_arguments,
+            auth: LaunchAuth {
+                username: "Player".to_string(),
+                uuid,
+                access_token: "0".to_string(),
+                user_type: "offline".to_string(),
+            },
+            env: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn beta_fixture_builds_positional_launchwrapper_args() {
+        let game_dir = Path::new("/tmp/game");
+        let index = serde_json::json!({"map_to_resources": true, "objects": {}});
+        let mut plan = legacy_fixture_plan(
+            B1_7_3_VERSION_JSON,
+            game_dir,
+            AssetLayout::from_index(&index),
+        );
+        assert!(plan.legacy_arguments);
+        assert!(accepted_main_classes_for_loader("vanilla").contains(&plan.main_class.as_str()));
+
+        let uuid = "0123456789abcdef0123456789abcdef".to_string();
+        apply_auth_to_launch_plan(
+            &mut plan,
+            LaunchAuth {
+                username: "Steve".to_string(),
+                uuid: uuid.clone(),
+                access_token: "msa-token-value".to_string(),
+                user_type: "msa".to_string(),
+            },
+        );
+        normalize_critical_game_args(&mut plan, "b1.7.3", None, None);
+
+        assert_eq!(plan.game_args[0], "Steve");
+        assert_eq!(plan.game_args[1], format!("token:msa-token-value:{uuid}"));
+        assert_eq!(
+            extract_or_fallback_arg(&plan.game_args, "--assetsDir", ""),
+            game_dir.join("resources").to_string_lossy()
+        );
+        assert_eq!(
+            extract_or_fallback_arg(&plan.game_args, "--tweakClass", ""),
+            "net.minecraft.launchwrapper.AlphaVanillaTweaker"
+        );
+        for modern_flag in ["--username", "--uuid", "--accessToken", "--version"] {
+            assert!(!plan.game_args.iter().any(|arg| arg == modern_flag));
+        }
+        assert!(!plan
+            .redacted()
+            .game_args
+            .iter()
+            .any(|arg| arg.contains("msa-token-value")));
+    }
+
+    #[test]
+    fn legacy_minecraft_arguments_fixtures_resolve_every_placeholder() {
+        let game_dir = Path::new("/tmp/game");
+        let virtual_index = serde_json::json!({"virtual": true, "objects": {}});
+        let mut plan_164 = legacy_fixture_plan(
+            V1_6_4_VERSION_JSON,
+            game_dir,
+            AssetLayout::from_index(&virtual_index),
+        );
+        let offline = plan_164.auth.clone();
+        apply_auth_to_launch_plan(&mut plan_164, offline);
+        normalize_critical_game_args(&mut plan_164, "1.6.4", None, None);
+        assert_eq!(
+            extract_or_fallback_arg(&plan_164.game_args, "--session", ""),
+            "-"
+        );
+        assert_eq!(
+            extract_or_fallback_arg(&plan_164.game_args, "--assetsDir", ""),
+            game_dir
+                .join("assets")
+                .join("virtual")
+                .join("legacy")
+                .to_string_lossy()
+        );
+        assert!(!plan_164.game_args.iter().any(|arg| arg == "--uuid"));
+
+        let mut plan_1710 = legacy_fixture_plan(
+            V1_7_10_VERSION_JSON,
+            game_dir,
+            AssetLayout::from_index(&serde_json::json!({"objects": {}})),
+        );
+        normalize_critical_game_args(&mut plan_1710, "1.7.10", None, None);
+        assert_eq!(
+            extract_or_fallback_arg(&plan_1710.game_args, "--userProperties", ""),
+            "{}"
+        );
+        assert_eq!(
+            extract_or_fallback_arg(&plan_1710.game_args, "--assetsDir", ""),
+            game_dir.join("assets").to_string_lossy()
+        );
+        assert!(!plan_1710.game_args.iter().any(|arg| arg == "--versionType"));
+
+        for plan in [&plan_164, &plan_1710] {
+            assert!(!plan
+                .game_args
+                .iter()
+                .any(|arg| has_unresolved_placeholder(arg)));
+            assert_eq!(
+                extract_or_fallback_arg(&plan.game_args, "--username", ""),
+                "Player"
+            );
+        }
+    }
+
+    #[test]
+    fn game_argument_template_prefers_modern_arguments() {
+        let modern = serde_json::json!({
+            "arguments": {"game": ["--username", "${auth_player_name}"]},
+            "minecraftArguments": "--username ${auth_player_name} --session ${auth_session}"
+        });
+        let (template, legacy) = game_argument_template(&modern);
+        assert!(!legacy);
+        assert_eq!(template.as_array().map(Vec::len), Some(2));
+
+        let empty_modern = serde_json::json!({
+            "arguments": {"game": []},
+            "minecraftArguments": "--username ${auth_player_name}"
+        });
+        assert!(game_argument_template(&empty_modern).1);
+    }
 }
@@ -1984,6 +1984,7 @@ export const InstancePanel = ({
           selectedInstance.id,
           preferred.downloadUrl,
           `${mod.name}-${preferred.id}.jar`,
+          preferred.fileSize,
         );
         setModInstallStage("installing");
         installedEntries.push(mod);
@@ -1,5 +1,6 @@
 import { loginWithMicrosoftDeviceCode } from "./minecraftAuthService";
 import { refreshAccountSkin } from "./skinService";
+import { invokeWithHandling } from "./tauriClient";
 import type { AccountStore, LauncherAccount } from "../types/account";
 
 const STORE_KEY = "interface.accounts.v1";
@@ -68,11 +69,12 @@ export const addOfflineAccount = async (username: string) => {
     throw new Error("El nombre offline no puede estar vacío.");
   }
 
+  const uuid = await invokeWithHandling<string>("offline_account_uuid", { username: trimmed });
   const account = await refreshAccountSkin({
     id: crypto.randomUUID(),
     type: "offline",
     username: trimmed,
-    uuid: crypto.randomUUID().replace(/-/g, ""),
+    uuid,
     status: "ready",
     createdAt: Date.now(),
     lastUsedAt: Date.now(),
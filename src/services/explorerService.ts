@@ -58,6 +58,7 @@ export interface ExplorerItemFileVersion {
   loaders: string[];
   loaderVersion?: string;
   downloadUrl?: string;
+  fileSize?: number;
   modId?: string;
   fileId?: string;
   dependencies?: string[];
@@ -660,6 +661,7 @@ export const fetchExplorerItemDetails = async (
             (version.loaders ?? [])[0],
           ),
           downloadUrl: version.files?.[0]?.url,
+          fileSize: version.files?.[0]?.size,
           dependencies: (version.dependencies ?? [])
             .filter((dependency) => dependency.dependency_type === "required")
             .map((dependency) => dependency.project_id)
@@ -739,6 +741,7 @@ export const fetchExplorerItemDetails = async (
       loaders: normalizedLoader ? [normalizedLoader] : [],
       loaderVersion: resolveLoaderVersion(file.displayName, normalizedLoader),
       downloadUrl: file.downloadUrl,
+      fileSize: file.fileLength,
       modId: item.projectId,
       fileId: String(file.id ?? ""),
       dependencies: (file.dependencies ?? [])
@@ -1,3 +1,5 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
 import type { Instance, LocalInstance } from "../types/models";
 import { invokeWithHandling } from "./tauriClient";
 import { getActiveAccount } from "./accountService";
@@ -229,6 +231,24 @@ export const readInstanceRuntimeLogs = async (instanceId: string) => {
   });
 };
 
+export interface BootstrapProgress {
+  instanceId: string;
+  step: string;
+  stepIndex: number;
+  stepCount: number;
+  filesDone: number;
+  filesTotal: number;
+  bytesDone: number;
+  bytesTotal?: number | null;
+  currentUrl?: string | null;
+  endpoint?: string | null;
+  retries: number;
+}
+
+export const onBootstrapProgress = (
+  handler: (progress: BootstrapProgress) => void,
+): Promise<UnlistenFn> =>
+  listen<BootstrapProgress>("instance-bootstrap-progress", (event) => handler(event.payload));
 
 export interface MinecraftLauncherInstallation {
   launcher: string;
@@ -1,18 +1,26 @@
-import { apiFetch } from "./apiClients/client";
+import { invokeWithHandling } from "./tauriClient";
 
 export interface MinecraftVersion {
   id: string;
   type: "release" | "snapshot" | "old_alpha" | "old_beta";
 }
 
-interface MinecraftManifest {
-  versions: MinecraftVersion[];
+interface BackendVersionRef {
+  id: string;
+  kind: MinecraftVersion["type"];
+  url: string;
+  releaseTime?: string | null;
 }
 
-const MANIFEST_URL =
-  "https://launchermeta.mojang.com/mc/game/version_manifest.json";
+interface BackendVersionList {
+  latestRelease: string;
+  latestSnapshot: string;
+  versions: BackendVersionRef[];
+}
 
 export const fetchMinecraftVersions = async (): Promise<MinecraftVersion[]> => {
-  const data = await apiFetch<MinecraftManifest>(MANIFEST_URL, { ttl: 300_000 });
-  return data.versions;
+  const data = await invokeWithHandling<BackendVersionList>("list_minecraft_versions", {
+    args: { types: [] },
+  });
+  return data.versions.map((version) => ({ id: version.id, type: version.kind }));
 };
@@ -45,10 +45,12 @@ export const installModFileToInstance = async (
   instanceId: string,
   downloadUrl: string,
   fileName: string,
+  expectedSize?: number,
 ) => {
   return invokeWithHandling<string>("install_mod_file", {
     instanceId,
     url: downloadUrl,
     fileName,
+    expected: expectedSize ? { size: expectedSize } : undefined,
   });
 };
@@ -6,13 +6,22 @@ export interface MinecraftVersion {
   releaseTime?: string;
 }
 
-export const fetchMinecraftVersions = async () => {
-  const { apiFetch } = await import("./apiClients/client");
-  const response = await apiFetch<{ versions: MinecraftVersion[] }>(
-    "https://launchermeta.mojang.com/mc/game/version_manifest.json",
-    { ttl: 60_000 * 10 },
-  );
-  return response.versions ?? [];
+export const fetchMinecraftVersions = async (): Promise<MinecraftVersion[]> => {
+  const { invokeWithHandling } = await import("./tauriClient");
+  const response = await invokeWithHandling<{
+    versions: Array<{
+      id: string;
+      kind: MinecraftVersion["type"];
+      url: string;
+      releaseTime?: string | null;
+    }>;
+  }>("list_minecraft_versions", { args: { types: [] } });
+  return (response.versions ?? []).map((version) => ({
+    id: version.id,
+    type: version.kind,
+    url: version.url,
+    releaseTime: version.releaseTime ?? undefined,
+  }));
 };
 
 export const fetchForgeVersions = async () => {
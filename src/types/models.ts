@@ -30,6 +30,7 @@ export interface Instance {
   sourceInstanceName?: string;
   javaMode?: "auto" | "embedded" | "manual";
   javaPath?: string;
+  logFormat?: "plain" | "xml";
 }
 
 export interface Modpack {